@@ -707,7 +707,9 @@ impl CPrecacheManager {
             }
             BuildTool::TfbTool => {
                 // TODO: move to where tfb handles loading stuff
-                ig_file_context.load_archive(ig_registry, &package_name);
+                if let Err(e) = ig_file_context.load_archive(ig_registry, &package_name) {
+                    error!("Failed to load {}: {}", package_name, e);
+                }
 
                 let _pkg_dir = ig_object_stream_manager
                     .load(
@@ -883,7 +885,9 @@ fn process_task(
 
     match task {
         LoaderTask::LooseIga => {
-            ig_file_context.load_archive(ig_registry, &line);
+            if let Err(e) = ig_file_context.load_archive(ig_registry, &line) {
+                error!("Failed to load {}: {}", line, e);
+            }
         }
         LoaderTask::FullPackage => {
             precache_manager.precache_package(
@@ -900,7 +904,9 @@ fn process_task(
         }
         LoaderTask::LoosePackage => {
             let full_path = format!("app:/archives/{}.pak", line);
-            ig_file_context.load_archive(ig_registry, &full_path);
+            if let Err(e) = ig_file_context.load_archive(ig_registry, &full_path) {
+                error!("Failed to load {}: {}", full_path, e);
+            }
         }
         LoaderTask::EngineType => match line.as_str() {
             "None" => ig_registry.build_tool = BuildTool::None,
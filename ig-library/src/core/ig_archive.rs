@@ -1,18 +1,27 @@
 use crate::core::ig_file_context::WorkStatus::{
-    kStatusComplete, kStatusGeneralError, kStatusInvalidPath, kStatusUnsupported,
+    kStatusBadParam, kStatusComplete, kStatusGeneralError, kStatusInvalidPath, kStatusUnsupported,
+};
+use crate::core::ig_file_context::{
+    igFileContext, igFileWorkItem, WorkItemBuffer, OPEN_FLAG_HEADER_ONLY, OPEN_FLAG_MEMORY_MAP,
 };
-use crate::core::ig_file_context::{igFileContext, igFileWorkItem, WorkItemBuffer};
 use crate::core::ig_fs::{igFileWorkItemProcessor, igStorageDevice, Endian};
 use crate::core::ig_registry::{igRegistry, BuildTool};
 use crate::util::byteorder_fixes::{
-    read_string, read_struct_array_u16, read_struct_array_u32, read_struct_array_u8,
-    read_struct_array_u8_ref, read_u32, read_u64,
+    read_string, read_struct_array_u16, read_struct_array_u32,
+    read_struct_array_u8, read_u32, read_u64, write_string, write_u16, write_u32, write_u64,
 };
 use crate::util::ig_hash;
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use crate::util::ig_hash_dictionary::{add_string, find_string};
+use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
 use flate2::read::DeflateDecoder;
-use log::debug;
-use lzma_rust2::LZMAReader;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use log::{debug, error};
+use lzma_rust2::{LZMA2Options, LZMAReader, LZMAWriter};
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 use std::sync::{Arc, Mutex, RwLock};
@@ -30,39 +39,208 @@ pub struct igArchive {
     pub _open: bool,
     pub _configured: bool,
     pub _needs_endian_swap: bool,
-    pub _archive_header: Header,
-    pub _files: Vec<FileInfo>,
+    pub _archive_header: RwLock<Header>,
+    pub _files: RwLock<Vec<FileInfo>>,
+    /// The block tables the archive was opened with, so [igArchive::save_preserving_layout] can keep the entries that don't belong to a single file
+    block_tables: BlockTables,
+    /// The bytes of the archive that was opened, file data is only read out of it when needed
+    source: RwLock<ArchiveSource>,
+    /// Recently decompressed files, see [igArchive::set_cache_capacity]
+    cache: Mutex<FileCache>,
     pub _native_media: String,
     pub _native_path: String,
     pub _native_app_path: String,
 }
 
-/// Deletes a file present in an archive
-fn delete(_path: &str) -> Result<(), ()> {
-    todo!("implement delete file in igArchive")
+/// Represents the different ways reading an archive can fail
+#[derive(Debug)]
+pub enum igArchiveError {
+    /// Returned when the archive file couldn't be opened
+    OpenFailed(String),
+    /// Returned when the file doesn't start with "IGA\x1A"
+    BadMagic(String),
+    /// Returned when the archive is a version that isn't implemented
+    UnsupportedVersion(u32),
+    /// Returned when the header, file info or block tables go past the end of the archive
+    TruncatedToc(String),
+    /// Returned when a file points at an entry outside of the block tables
+    BadBlockIndex { file: String, index: usize },
+    /// Returned when a file's data goes past the end of the archive
+    FileDataOutOfRange(String),
+    /// Returned when the name table or a name in it goes past the end of the archive
+    NameTableOutOfRange(String),
+    /// Returned when a file uses a compression type that isn't known
+    UnsupportedCompression(u32),
+    /// Returned when a block of a file fails to decompress
+    Decompression {
+        file: String,
+        block: usize,
+        reason: String,
+    },
+    /// Returned when a file is opened by a path that doesn't follow the naming of the build tool
+    InvalidPath(String),
+    /// Returned when a file is opened while the registry has no build tool set, as the build tool decides how files are looked up
+    UnsupportedBuildTool,
+    /// Returned by [igArchive::verify] when values in the header don't agree with each other
+    InconsistentHeader(String),
+    /// Returned by [igArchive::verify] when a file isn't sorted by its hash
+    UnsortedToc(String),
+    /// Returned by [igArchive::verify] when [igArchive::hash_search] doesn't find a file
+    HashSearchFailed(String),
+    /// Returned by [igArchive::verify] when the hash of a file doesn't match its logical name
+    HashMismatch {
+        file: String,
+        expected: u32,
+        actual: u32,
+    },
+    /// Returned by [igArchive::verify] when a block doesn't decompress to the size it should
+    BadBlockSize {
+        file: String,
+        block: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// Returned when a file isn't present in the archive
+    FileNotFound(String),
+    /// Returned when a file has the same hash as another file in the archive
+    FileExists(String),
+    /// Returned when a compression type can't be used by the version of the archive
+    UnsupportedCompressionForVersion { compression: String, version: u32 },
+    /// Returned when a block of a file fails to compress
+    Compression {
+        file: String,
+        block: usize,
+        reason: String,
+    },
 }
 
-impl igArchive {
-    pub fn hash_file_path(&self, file_path: &str) -> u32 {
-        let mut path_copy = file_path.to_string();
-
-        // kCaseInsensitiveHash
-        if (self._archive_header._flags & 1u32) != 0 {
-            path_copy = path_copy.replace("\\", "/");
-            path_copy = path_copy.to_lowercase();
+impl Display for igArchiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            igArchiveError::OpenFailed(path) => write!(f, "{} could not be opened.", path),
+            igArchiveError::BadMagic(path) => write!(f, "{} is not a valid igArchive.", path),
+            igArchiveError::UnsupportedVersion(version) => {
+                write!(f, "igArchive version {} is not implemented.", version)
+            }
+            igArchiveError::TruncatedToc(path) => {
+                write!(f, "{} has a truncated table of contents.", path)
+            }
+            igArchiveError::BadBlockIndex { file, index } => {
+                write!(f, "{} uses block {} which is outside of the block table.", file, index)
+            }
+            igArchiveError::FileDataOutOfRange(file) => {
+                write!(f, "{} points outside of the archive.", file)
+            }
+            igArchiveError::NameTableOutOfRange(path) => {
+                write!(f, "{} has a name table outside of the archive.", path)
+            }
+            igArchiveError::UnsupportedCompression(index) => {
+                write!(f, "Unknown compression type {}", index)
+            }
+            igArchiveError::Decompression {
+                file,
+                block,
+                reason,
+            } => write!(f, "Failed to decompress block {} of {}: {}", block, file, reason),
+            igArchiveError::InvalidPath(path) => {
+                write!(f, "{} is not a valid path for a file in an archive.", path)
+            }
+            igArchiveError::UnsupportedBuildTool => {
+                write!(f, "Files can't be opened from an archive without a build tool set.")
+            }
+            igArchiveError::InconsistentHeader(reason) => {
+                write!(f, "The header is inconsistent: {}", reason)
+            }
+            igArchiveError::UnsortedToc(file) => {
+                write!(f, "{} is not sorted by hash.", file)
+            }
+            igArchiveError::HashSearchFailed(file) => {
+                write!(f, "{} can't be found with the hash search values in the header.", file)
+            }
+            igArchiveError::HashMismatch {
+                file,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} should have the hash {:08X} but has {:08X}",
+                file, expected, actual
+            ),
+            igArchiveError::BadBlockSize {
+                file,
+                block,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Block {} of {} decompressed to {:#X} bytes instead of {:#X}",
+                block, file, actual, expected
+            ),
+            igArchiveError::FileNotFound(file) => {
+                write!(f, "{} is not present in the archive", file)
+            }
+            igArchiveError::FileExists(file) => {
+                write!(f, "{} has the same hash as another file in the archive.", file)
+            }
+            igArchiveError::UnsupportedCompressionForVersion {
+                compression,
+                version,
+            } => write!(
+                f,
+                "{} is not supported by igArchive version {}",
+                compression, version
+            ),
+            igArchiveError::Compression {
+                file,
+                block,
+                reason,
+            } => write!(f, "Failed to compress block {} of {}: {}", block, file, reason),
         }
+    }
+}
+
+/// Every problem found by [igArchive::verify]
+#[derive(Debug, Default)]
+pub struct igArchiveReport {
+    /// Problems with the archive as a whole
+    pub archive_problems: Vec<igArchiveError>,
+    /// Problems with each file as (logical name, problems). Files without any problems are left out
+    pub file_problems: Vec<(String, Vec<igArchiveError>)>,
+}
 
-        // kHashNameAndExtensionOnly
-        if (self._archive_header._flags & 2u32) != 0 {
-            path_copy = Path::new(&path_copy)
-                .file_name()
-                .and_then(|os_str| os_str.to_str())
-                .unwrap_or("")
-                .to_string();
+impl igArchiveReport {
+    /// Returns true when no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.archive_problems.is_empty() && self.file_problems.is_empty()
+    }
+}
+
+impl Display for igArchiveReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for problem in &self.archive_problems {
+            writeln!(f, "{}", problem)?;
         }
+        for (file, problems) in &self.file_problems {
+            writeln!(f, "{}:", file)?;
+            for problem in problems {
+                writeln!(f, "    {}", problem)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for igArchiveError {}
+
+impl From<igArchiveError> for String {
+    fn from(value: igArchiveError) -> Self {
+        value.to_string()
+    }
+}
 
-        path_copy = path_copy.trim_start_matches(['/', '\\']).to_string();
-        ig_hash::hash(&path_copy)
+impl igArchive {
+    pub fn hash_file_path(&self, file_path: &str) -> u32 {
+        hash_file_path(self._archive_header.read().unwrap()._flags, file_path)
     }
 
     /// Reverse engineered by DTZxPorter. It will Search the list of files for a given hash and will return the index of the file info
@@ -72,6 +250,10 @@ impl igArchive {
         hash_search_slop: u32,
         file_hash: u32,
     ) -> Option<usize> {
+        if file_info.is_empty() || hash_search_divider == 0 {
+            return None;
+        }
+
         let mut file_count = file_info.len() as u32;
         let mut file_hash_divided = file_hash / hash_search_divider; // most likely an optimization to make searching easier when fewer collisions can happen.
 
@@ -86,6 +268,9 @@ impl igArchive {
         }
 
         let mut index = search_at;
+        if file_count < index {
+            return None; // The divider and slop don't match the files
+        }
         search_at = file_count - index;
         let mut i = search_at;
         while 0 < i {
@@ -111,105 +296,205 @@ impl igArchive {
 
     /// Similar to [has_file], but will use a hash instead of a file path.
     fn has_hash(&self, _hash: u32) -> bool {
+        let header = self._archive_header.read().unwrap();
         Self::hash_search(
-            &self._files,
-            self._archive_header._hash_search_divider,
-            self._archive_header._hash_search_slop,
+            &self._files.read().unwrap(),
+            header._hash_search_divider,
+            header._hash_search_slop,
             _hash,
         )
         .is_some()
     }
 
-    fn decompress_as_handle(&self, file_info: &FileInfo) -> Cursor<Vec<u8>> {
-        Cursor::new(self.decompress(file_info, self._archive_header._version))
+    /// Decompresses a file, going through the cache if one is set up with [igArchive::set_cache_capacity]
+    fn get_file_data(&self, header: &Header, file_info: &FileInfo) -> Result<Vec<u8>, igArchiveError> {
+        if let Some(data) = self.cache.lock().unwrap().get(file_info._hash) {
+            return Ok(data);
+        }
+        let source = self.source.read().unwrap();
+        let data = Self::decompress(header, file_info, file_info.compressed_data(source.bytes())?)?;
+        self.cache.lock().unwrap().insert(file_info._hash, &data);
+        Ok(data)
     }
 
-    fn decompress(&self, file_info: &FileInfo, iga_version: u32) -> Vec<u8> {
-        let mut dst = Vec::<u8>::new();
+    /// Decompresses only the first block of a file, which is all that's needed to tell what the file is. Goes through the cache like [igArchive::get_file_data],
+    /// but the partial data is never added to it
+    fn get_file_start(&self, header: &Header, file_info: &FileInfo) -> Result<Vec<u8>, igArchiveError> {
+        if let Some(data) = self.cache.lock().unwrap().get(file_info._hash) {
+            return Ok(data);
+        }
+        let source = self.source.read().unwrap();
+        let compressed_data = file_info.compressed_data(source.bytes())?;
         if file_info._block_index == 0xFFFFFFFF {
-            dst.write_all(&file_info._compressed_data).unwrap();
-            return dst;
-        }
-        let blocks = file_info._blocks.clone().unwrap();
-        let compression_type = CompressionType::from_index(file_info._block_index, iga_version);
-        for i in 0..blocks.len() {
-            let decompressed_size = if file_info._length < ((i + 1) * 0x8000) as u32 {
-                file_info._length & 0x7FFF
-            } else {
-                0x8000
-            };
+            return Ok(compressed_data[..compressed_data.len().min(0x8000)].to_vec());
+        }
+        match file_info._blocks.as_ref().and_then(|blocks| blocks.first()) {
+            Some(block) => Self::decompress_block(header, file_info, compressed_data, *block, 0),
+            None => Ok(vec![]),
+        }
+    }
 
-            // let should_decompress = blocks[i] & 0x80000000u32 != 0; // unused
-            let mut offset =
-                ((blocks[i] & 0x7FFFFFFF) * self._archive_header._sector_size) as usize;
-            if blocks[i] & 0x80000000u32 == 0 {
-                let compressed_data =
-                    &file_info._compressed_data[offset..(offset + decompressed_size as usize)];
-                dst.write_all(compressed_data).unwrap();
-                continue;
+    /// Opens a file in the archive for a work item. Files that aren't in the archive set kStatusInvalidPath
+    fn open_work_item(
+        &self,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        work_item: &mut igFileWorkItem,
+    ) -> Result<(), igArchiveError> {
+        let header = self._archive_header.read().unwrap();
+        let files = self._files.read().unwrap();
+        let file = match work_item.ig_registry.build_tool {
+            BuildTool::AlchemyLaboratory => {
+                #[cfg(debug_assertions)]
+                debug!(
+                    "{} has hash {}",
+                    work_item._path,
+                    self.hash_file_path(&work_item._path)
+                );
+                find_file(&header, &files, &work_item._path).map(|idx| &files[idx])
             }
+            BuildTool::TfbTool => {
+                // Files are opened as the path of the archive followed by the name of the file
+                let (archive_path, file_name) = work_item
+                    ._path
+                    .rsplit_once('/')
+                    .ok_or_else(|| igArchiveError::InvalidPath(work_item._path.clone()))?;
+                files
+                    .iter()
+                    .find(|file| archive_path == self._path && file._name == file_name)
+            }
+            BuildTool::None => return Err(igArchiveError::UnsupportedBuildTool),
+        };
 
-            let mut cursor = Cursor::new(&file_info._compressed_data);
-            cursor.seek(SeekFrom::Start(offset as u64)).unwrap();
-            let compressed_size;
-            if iga_version <= 0x04 {
-                compressed_size = cursor.read_u16::<BigEndian>().unwrap(); // C# Implementation does not state what endian igCauldron is reading this in.
-            } else {
-                compressed_size = cursor.read_u16::<LittleEndian>().unwrap(); // C# Implementation does not state what endian igCauldron is reading this in.
+        let Some(file) = file else {
+            work_item._status = kStatusInvalidPath;
+            return Ok(());
+        };
+        work_item._file._path = work_item._path.clone();
+        work_item._file._size = file._length as u64;
+        work_item._file._position = 0;
+        work_item._file._device = Some(this);
+        let data = if work_item.flags & OPEN_FLAG_HEADER_ONLY != 0 {
+            self.get_file_start(&header, file)?
+        } else {
+            self.get_file_data(&header, file)?
+        };
+        work_item._file._handle = Some(Cursor::new(data));
+        work_item._status = kStatusComplete;
+        Ok(())
+    }
+
+    fn decompress(
+        header: &Header,
+        file_info: &FileInfo,
+        compressed_data: &[u8],
+    ) -> Result<Vec<u8>, igArchiveError> {
+        if file_info._block_index == 0xFFFFFFFF {
+            return Ok(compressed_data.to_vec());
+        }
+        let blocks = file_info._blocks.as_ref().unwrap();
+        // Every block is compressed on its own, so they can all be decompressed at the same time
+        blocks
+            .par_iter()
+            .enumerate()
+            .map(|(i, block)| {
+                Self::decompress_block(header, file_info, compressed_data, *block, i)
+            })
+            .collect::<Result<Vec<Vec<u8>>, igArchiveError>>()
+            .map(|blocks| blocks.concat())
+    }
+
+    /// Decompresses the i-th 0x8000 byte block of a file
+    fn decompress_block(
+        header: &Header,
+        file_info: &FileInfo,
+        compressed_data: &[u8],
+        block: u32,
+        i: usize,
+    ) -> Result<Vec<u8>, igArchiveError> {
+        let iga_version = header._version;
+        let compression_type = CompressionType::from_index(file_info._block_index, iga_version)?;
+        let decompression_error = |reason: String| igArchiveError::Decompression {
+            file: file_info._logical_name.clone(),
+            block: i,
+            reason,
+        };
+        let block_data = |start: usize, len: usize| {
+            compressed_data
+                .get(start..start + len)
+                .ok_or_else(|| decompression_error("the block is outside of the file data".to_string()))
+        };
+        let mut dst = Vec::<u8>::with_capacity(0x8000);
+        let decompressed_size = get_block_size(file_info, i);
+
+        let offset = (block & 0x7FFFFFFF) as usize * header._sector_size as usize;
+        if block & 0x80000000u32 == 0 {
+            return Ok(block_data(offset, decompressed_size as usize)?.to_vec());
+        }
+
+        let size_bytes: [u8; 2] = block_data(offset, 2)?.try_into().unwrap();
+        let compressed_size = if iga_version <= 0x04 {
+            u16::from_be_bytes(size_bytes) // C# Implementation does not state what endian igCauldron is reading this in.
+        } else {
+            u16::from_le_bytes(size_bytes) // C# Implementation does not state what endian igCauldron is reading this in.
+        };
+        let offset = offset + 2;
+
+        match compression_type {
+            CompressionType::kZlib => {
+                let slice = block_data(offset, compressed_size as usize)?;
+                let mut decoder = DeflateDecoder::new(slice);
+                decoder
+                    .read_to_end(&mut dst)
+                    .map_err(|e| decompression_error(e.to_string()))?;
             }
-            drop(cursor);
-            offset += 2;
-
-            match compression_type {
-                CompressionType::kZlib => {
-                    let slice =
-                        &file_info._compressed_data[offset..offset + compressed_size as usize];
-                    let mut decoder = DeflateDecoder::new(slice);
-                    decoder.read_to_end(&mut dst).unwrap();
+            CompressionType::kLzma => {
+                let lzma_properties = block_data(offset, 5)?;
+                let slice = block_data(offset + 5, compressed_size as usize)?;
+
+                let first = lzma_properties[0] as usize;
+                let lc = first % 9;
+                let num = first / 9;
+                let lp = num % 5;
+                let pb = num / 5;
+
+                // reconstruct little‑endian dictionary size from bytes 1..5
+                let mut dictionary_size: u32 = 0;
+                for i in 0..4 {
+                    dictionary_size = dictionary_size
+                        .wrapping_add((lzma_properties[1 + i] as u32) << (i * 8));
                 }
-                CompressionType::kLzma => {
-                    let lzma_properties = &file_info._compressed_data[offset..offset + 5];
-                    let slice = &file_info._compressed_data
-                        [offset + 5..(5 + offset + compressed_size as usize)];
-
-                    let first = lzma_properties[0] as usize;
-                    let lc = first % 9;
-                    let num = first / 9;
-                    let lp = num % 5;
-                    let pb = num / 5;
-
-                    // reconstruct little‑endian dictionary size from bytes 1..5
-                    let mut dictionary_size: u32 = 0;
-                    for i in 0..4 {
-                        dictionary_size = dictionary_size
-                            .wrapping_add((lzma_properties[1 + i] as u32) << (i * 8));
-                    }
 
-                    let mut reader = LZMAReader::new(
-                        Cursor::new(slice),
-                        decompressed_size as u64,
-                        lc as u32,
-                        lp as u32,
-                        pb as u32,
-                        dictionary_size,
-                        None,
-                    )
-                    .unwrap();
+                let mut reader = LZMAReader::new(
+                    Cursor::new(slice),
+                    decompressed_size as u64,
+                    lc as u32,
+                    lp as u32,
+                    pb as u32,
+                    dictionary_size,
+                    None,
+                )
+                .map_err(|e| decompression_error(e.to_string()))?;
 
-                    reader.read_to_end(&mut dst).unwrap();
-                }
-                CompressionType::kLz4 => {
-                    let slice =
-                        &file_info._compressed_data[offset..(offset + compressed_size as usize)];
-                    dst.extend(
-                        lz4::block::decompress(slice, Some(decompressed_size as i32)).unwrap(),
-                    );
-                }
-                _ => panic!("Unsupported compression type {:?}", compression_type),
+                reader
+                    .read_to_end(&mut dst)
+                    .map_err(|e| decompression_error(e.to_string()))?;
+            }
+            CompressionType::kLz4 => {
+                let slice = block_data(offset, compressed_size as usize)?;
+                dst.extend(
+                    lz4::block::decompress(slice, Some(decompressed_size as i32))
+                        .map_err(|e| decompression_error(e.to_string()))?,
+                );
+            }
+            _ => {
+                return Err(decompression_error(format!(
+                    "{:?} blocks can't be decompressed",
+                    compression_type
+                )))
             }
         }
 
-        dst
+        Ok(dst)
     }
 
     /// Opens an archive
@@ -218,322 +503,1313 @@ impl igArchive {
         file_context: &igFileContext,
         ig_registry: &igRegistry,
         file_path: &str,
-    ) -> Result<igArchive, String> {
-        let mut file_descriptor = file_context.open(ig_registry, file_path, 0);
-        let _path = file_descriptor._path;
-        let mut header = Header {
-            endian: Endian::Little,
-            _magic_number: 0,
-            _version: 0,
-            _toc_size: 0,
-            _num_files: 0,
-            _sector_size: 0,
-            _hash_search_divider: 0,
-            _hash_search_slop: 0,
-            _num_large_file_blocks: 0,
-            _num_medium_file_blocks: 0,
-            _num_small_file_blocks: 0,
-            _name_table_offset: 0,
-            _name_table_size: 0,
-            _flags: 0,
+    ) -> Result<igArchive, igArchiveError> {
+        let (_path, source) = load_source(file_context, ig_registry, file_path)?;
+        let data = source.bytes();
+        let header = read_header(data, &_path)?;
+        let (mut _files, tables) = read_toc(data, &header, &_path)?;
+        if header._name_table_size == 0 {
+            // Some archives are built without a name table, so the names can only be looked up from their hash
+            for file in &mut _files {
+                recover_names(file);
+            }
+        } else {
+            let mut name_cursor = Cursor::new(get_name_table(data, &header, &_path)?.to_vec());
+            for (i, file) in _files.iter_mut().enumerate() {
+                read_names(&mut name_cursor, &header, ig_registry, i, file, &_path)?;
+                add_string(&file._logical_name);
+            }
+        }
+        for file in &mut _files {
+            read_blocks(&header, &tables, file, data.len() as u64)?;
+        }
+
+        Ok(igArchive {
+            next_processor: None,
+            _path,
+            _name: "".to_string(),
+            _load_name_table: false,
+            _sequential_read: false,
+            _loading_for_incremental_update: false,
+            _enable_cache: false,
+            _override: false,
+            _open: false,
+            _configured: false,
+            _needs_endian_swap: false,
+            _archive_header: RwLock::new(header),
+            _files: RwLock::new(_files),
+            block_tables: tables,
+            source: RwLock::new(source),
+            cache: Mutex::new(FileCache::new()),
+            _native_media: "".to_string(),
+            _native_path: "".to_string(),
+            _native_app_path: "".to_string(),
+        })
+    }
+
+    /// Checks an archive for corruption. Unlike [igArchive::open] this doesn't stop at the first problem, every problem found is listed in the report.
+    /// The header, hash order, hashes, block tables and name table are checked, and every block is decompressed to make sure it has the right size
+    pub fn verify(
+        file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        file_path: &str,
+    ) -> igArchiveReport {
+        let mut report = igArchiveReport::default();
+        let (path, source) = match load_source(file_context, ig_registry, file_path) {
+            Ok(source) => source,
+            Err(e) => {
+                report.archive_problems.push(e);
+                return report;
+            }
+        };
+        let data = source.bytes();
+        let header = match read_header(data, &path) {
+            Ok(header) => header,
+            Err(e) => {
+                report.archive_problems.push(e);
+                return report;
+            }
+        };
+        report.archive_problems.extend(verify_header(&header));
+        let (mut files, tables) = match read_toc(data, &header, &path) {
+            Ok(toc) => toc,
+            Err(e) => {
+                report.archive_problems.push(e);
+                return report;
+            }
         };
 
-        if let Some(mut cursor) = file_descriptor._handle {
-            cursor.seek(SeekFrom::Start(0)).unwrap();
-            header._magic_number = read_u32(&mut cursor, Endian::Little).unwrap();
+        let mut problems: Vec<Vec<igArchiveError>> = files.iter().map(|_| Vec::new()).collect();
+        let mut name_cursor = match get_name_table(data, &header, &path) {
+            Ok(_) if header._name_table_size == 0 => None,
+            Ok(name_tbl) => Some(Cursor::new(name_tbl.to_vec())),
+            Err(e) => {
+                report.archive_problems.push(e);
+                None
+            }
+        };
+        let mut readable = vec![true; files.len()];
+        for (i, file) in files.iter_mut().enumerate() {
+            if let Some(name_cursor) = &mut name_cursor {
+                match read_names(name_cursor, &header, ig_registry, i, file, &path) {
+                    Ok(()) => {
+                        let expected = hash_file_path(header._flags, &file._logical_name);
+                        if expected != file._hash {
+                            problems[i].push(igArchiveError::HashMismatch {
+                                file: file._logical_name.clone(),
+                                expected,
+                                actual: file._hash,
+                            });
+                        }
+                    }
+                    Err(e) => problems[i].push(e),
+                }
+            } else if header._name_table_size == 0 {
+                recover_names(file);
+            }
+            if file._logical_name.is_empty() {
+                file._logical_name = format!("{:08X}", file._hash);
+            }
+            if let Err(e) = read_blocks(&header, &tables, file, data.len() as u64) {
+                problems[i].push(e);
+                readable[i] = false;
+            }
+        }
 
-            if header._magic_number == u32::from_be_bytes(*b"IGA\x1A") {
-                header.endian = Endian::Big;
-            } else if header._magic_number != u32::from_le_bytes(*b"IGA\x1A") {
-                return Err(format!("{} is not a valid igArchive.", _path));
+        for (i, file) in files.iter().enumerate() {
+            if i > 0 && files[i - 1]._hash > file._hash {
+                problems[i].push(igArchiveError::UnsortedToc(file._logical_name.clone()));
+            }
+            let found = Self::hash_search(
+                &files,
+                header._hash_search_divider,
+                header._hash_search_slop,
+                file._hash,
+            );
+            if found.is_none_or(|x| files[x]._hash != file._hash) {
+                problems[i].push(igArchiveError::HashSearchFailed(file._logical_name.clone()));
             }
+        }
 
-            header._version = read_u32(&mut cursor, header.endian.clone()).unwrap();
-            match header._version {
-                // Crash Team Racing: Nitro Fueled, Crash NST, Trap Team, Superchargers, Imaginators
-                0x0A..=0x0D => {
-                    header._toc_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_files = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._sector_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._hash_search_divider = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._hash_search_slop = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_large_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_medium_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_small_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._name_table_offset = read_u64(&mut cursor, header.endian.clone()).unwrap();
-                    header._name_table_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._flags = read_u32(&mut cursor, header.endian.clone()).unwrap();
+        // Decompressing everything is the slow part, so each file is checked on its own thread
+        let block_problems: Vec<Vec<igArchiveError>> = files
+            .par_iter()
+            .zip(readable.par_iter())
+            .map(|(file, readable)| match readable {
+                true => match file.compressed_data(data) {
+                    Ok(compressed_data) => verify_blocks(&header, file, compressed_data),
+                    Err(e) => vec![e],
+                },
+                false => Vec::new(),
+            })
+            .collect();
+
+        for ((file, mut problems), block_problems) in files.iter().zip(problems).zip(block_problems) {
+            problems.extend(block_problems);
+            if !problems.is_empty() {
+                report.file_problems.push((file._logical_name.clone(), problems));
+            }
+        }
+
+        report
+    }
+
+    pub fn new() -> Self {
+        igArchive {
+            next_processor: None,
+            _path: "".to_string(),
+            _name: "".to_string(),
+            _load_name_table: false,
+            _sequential_read: false,
+            _loading_for_incremental_update: false,
+            _enable_cache: false,
+            _override: false,
+            _open: false,
+            _configured: false,
+            _needs_endian_swap: false,
+            _archive_header: RwLock::new(Header {
+                endian: Endian::Little,
+                _magic_number: 0,
+                _version: 0,
+                _toc_size: 0,
+                _num_files: 0,
+                _sector_size: 0,
+                _hash_search_divider: 0,
+                _hash_search_slop: 0,
+                _num_large_file_blocks: 0,
+                _num_medium_file_blocks: 0,
+                _num_small_file_blocks: 0,
+                _name_table_offset: 0,
+                _name_table_size: 0,
+                _flags: 0,
+            }),
+            _files: RwLock::new(vec![]),
+            block_tables: BlockTables::default(),
+            source: RwLock::new(ArchiveSource::None),
+            cache: Mutex::new(FileCache::new()),
+            _native_media: "".to_string(),
+            _native_path: "".to_string(),
+            _native_app_path: "".to_string(),
+        }
+    }
+
+    /// Will decompress a file from the archive based on the path provided. Returns None if the file isn't in the archive
+    pub fn read_file(&self, path: &str) -> Result<Option<Vec<u8>>, igArchiveError> {
+        let header = self._archive_header.read().unwrap();
+        let files = self._files.read().unwrap();
+        find_file(&header, &files, path)
+            .map(|idx| self.get_file_data(&header, &files[idx]))
+            .transpose()
+    }
+
+    /// Decompresses every file in the archive across multiple threads. Returns the logical name and data of each file
+    pub fn extract_all(&self) -> Result<Vec<(String, Vec<u8>)>, igArchiveError> {
+        let header = self._archive_header.read().unwrap();
+        let files = self._files.read().unwrap();
+        let source = self.source.read().unwrap();
+        let source = source.bytes();
+        files
+            .par_iter()
+            .map(|file| {
+                let data = Self::decompress(&header, file, file.compressed_data(source)?)?;
+                Ok((file._logical_name.clone(), data))
+            })
+            .collect()
+    }
+
+    /// Decompresses every file in the archive into out_dir across multiple threads. The logical name of each file is used as its path
+    pub fn extract_all_to(&self, out_dir: &Path) -> Result<(), String> {
+        let header = self._archive_header.read().unwrap();
+        let files = self._files.read().unwrap();
+        let source = self.source.read().unwrap();
+        let source = source.bytes();
+        files.par_iter().try_for_each(|file| {
+            // Logical names can start with a media name (ex: "cwd:") or go up a folder, none of which should end up outside of out_dir
+            let mut path = out_dir.to_path_buf();
+            for part in file._logical_name.split(['/', '\\']) {
+                if !matches!(part, "" | "." | "..") {
+                    path.push(part.replace(':', ""));
                 }
-                // TODO: lost islands/ssf (version 0x0A)
-                // SSA(WiiU), SG
-                0x08 => {
-                    header._toc_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_files = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._sector_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._hash_search_divider = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._hash_search_slop = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._name_table_offset =
-                        read_u32(&mut cursor, header.endian.clone()).unwrap() as u64;
-                    header._name_table_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_large_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_medium_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_small_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._flags = read_u32(&mut cursor, header.endian.clone()).unwrap();
+            }
+
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            let data = Self::decompress(&header, file, file.compressed_data(source)?)?;
+            std::fs::write(&path, data)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Keeps up to max_bytes of recently decompressed files around so reading them again is faster. 0 disables the cache, which is the default
+    pub fn set_cache_capacity(&self, max_bytes: usize) {
+        self.cache.lock().unwrap().set_capacity(max_bytes);
+    }
+
+    /// Adds a new file to the archive. Fails if a file with the same hash is already present
+    pub fn add_file(
+        &self,
+        logical_name: &str,
+        data: &[u8],
+        compression: CompressionType,
+    ) -> Result<(), igArchiveError> {
+        let mut header = self._archive_header.write().unwrap();
+        let mut files = self._files.write().unwrap();
+        let file = pack_file(&header, &files, logical_name, data, &compression)?;
+        // New files go after everything else in the archive
+        let _ordinal = files.iter().map(|x| x._ordinal + 1).max().unwrap_or_default();
+        files.push(FileInfo { _ordinal, ..file });
+        rebuild_toc(&mut header, &mut files);
+        Ok(())
+    }
+
+    /// Replaces the contents of a file in the archive. The file keeps the compression it already had
+    pub fn replace_file(&self, path: &str, data: &[u8]) -> Result<(), igArchiveError> {
+        let header = self._archive_header.read().unwrap();
+        let mut files = self._files.write().unwrap();
+        let idx = find_file(&header, &files, path)
+            .ok_or_else(|| igArchiveError::FileNotFound(path.to_string()))?;
+        let file = &mut files[idx];
+        if file._block_index == 0xFFFFFFFF {
+            file._compressed_data = Some(data.to_vec());
+            file._compressed_size = data.len() as u64;
+        } else {
+            let compression = CompressionType::from_index(file._block_index, header._version)?;
+            let (_blocks, _compressed_data) =
+                pack_blocks(path, data, header._sector_size, &compression, header._version)?;
+            file._blocks = Some(_blocks);
+            file._compressed_size = _compressed_data.len() as u64;
+            file._compressed_data = Some(_compressed_data);
+        }
+        file._length = data.len() as u32;
+        self.cache.lock().unwrap().remove(file._hash);
+        Ok(())
+    }
+
+    /// Removes a file from the archive
+    pub fn delete_file(&self, path: &str) -> Result<(), igArchiveError> {
+        let mut header = self._archive_header.write().unwrap();
+        let mut files = self._files.write().unwrap();
+        let idx = find_file(&header, &files, path)
+            .ok_or_else(|| igArchiveError::FileNotFound(path.to_string()))?;
+        self.cache.lock().unwrap().remove(files[idx]._hash);
+        files.remove(idx);
+        rebuild_toc(&mut header, &mut files);
+        Ok(())
+    }
+
+    /// Changes the logical name of a file, which also changes the hash used to find it
+    pub fn rename_file(&self, path: &str, new_path: &str) -> Result<(), igArchiveError> {
+        let mut header = self._archive_header.write().unwrap();
+        let mut files = self._files.write().unwrap();
+        let idx = find_file(&header, &files, path)
+            .ok_or_else(|| igArchiveError::FileNotFound(path.to_string()))?;
+        let _hash = hash_file_path(header._flags, new_path);
+        if find_file(&header, &files, new_path).is_some_and(|x| x != idx) {
+            return Err(igArchiveError::FileExists(new_path.to_string()));
+        }
+
+        let file = &mut files[idx];
+        self.cache.lock().unwrap().remove(file._hash);
+        file._hash = _hash;
+        file._name = new_path.to_string();
+        file._logical_name = new_path.to_string();
+        rebuild_toc(&mut header, &mut files);
+        Ok(())
+    }
+
+    /// Writes bytes into a file at the given offset, growing the file as needed. The file is created if it doesn't exist yet.
+    fn write_file(&self, path: &str, offset: u64, bytes: &[u8]) -> Result<(), igArchiveError> {
+        let mut data = self.read_file(path)?;
+        if data.is_none() {
+            self.add_file(path, &[], self.get_default_compression())?;
+            data = Some(vec![]);
+        }
+
+        let mut data = data.unwrap();
+        let end = offset as usize + bytes.len();
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[offset as usize..end].copy_from_slice(bytes);
+        self.replace_file(path, &data)
+    }
+
+    /// Changes the size of a file, cutting off or zero filling the end of it
+    fn truncate_file(&self, path: &str, size: u64) -> Result<(), igArchiveError> {
+        let mut data = self
+            .read_file(path)?
+            .ok_or_else(|| igArchiveError::FileNotFound(path.to_string()))?;
+        data.resize(size as usize, 0);
+        self.replace_file(path, &data)
+    }
+
+    /// The compression used by the first compressed file in the archive. New files will use this compression type
+    fn get_default_compression(&self) -> CompressionType {
+        let header = self._archive_header.read().unwrap();
+        self._files
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|x| x._block_index != 0xFFFFFFFF && x._block_index >> 28 != 0)
+            .find_map(|x| CompressionType::from_index(x._block_index, header._version).ok())
+            .unwrap_or(CompressionType::kUncompressed)
+    }
+
+    /// Builds a new archive from a list of (logical name, data) entries. Use [igArchive::save] to get the bytes of the archive.
+    /// flags are the same as the ones stored in the header (kCaseInsensitiveHash = 1, kHashNameAndExtensionOnly = 2)
+    /// compression is applied to every block. Blocks that don't get any smaller are stored uncompressed
+    pub fn build(
+        entries: Vec<(String, Vec<u8>)>,
+        version: u32,
+        endian: Endian,
+        flags: u32,
+        compression: CompressionType,
+    ) -> Result<igArchive, String> {
+        if !matches!(version, 0x04 | 0x08 | 0x0B..=0x0D) {
+            return Err(format!("igArchive version {} is not implemented.", version));
+        }
+
+        let archive = igArchive::new();
+        let mut header = archive._archive_header.write().unwrap();
+        let mut files = archive._files.write().unwrap();
+        header.endian = endian;
+        header._magic_number = u32::from_le_bytes(*b"IGA\x1A");
+        header._version = version;
+        header._sector_size = 0x800;
+        header._flags = flags;
+
+        for (ordinal, (logical_name, data)) in entries.into_iter().enumerate() {
+            let file = pack_file(&header, &files, &logical_name, &data, &compression)?;
+            files.push(FileInfo {
+                _ordinal: ordinal as u32,
+                ..file
+            });
+        }
+
+        rebuild_toc(&mut header, &mut files);
+        drop(header);
+        drop(files);
+        Ok(archive)
+    }
+
+    /// Writes the archive out. The layout of the file data will follow the ordinal of each file.
+    /// Use [igArchive::save_to] to write over the file the archive was opened from, as it may still be memory mapped
+    pub fn save(&self, ig_registry: &igRegistry) -> Result<Vec<u8>, String> {
+        self.write(ig_registry, false)
+    }
+
+    /// Writes the archive out while keeping the layout it was loaded with (file offsets, block table indices, toc size and name table offset).
+    /// An unmodified archive will be identical to the file it was loaded from. Fails if a file no longer fits in its original spot, use [igArchive::save] after editing an archive.
+    pub fn save_preserving_layout(&self, ig_registry: &igRegistry) -> Result<Vec<u8>, String> {
+        self.write(ig_registry, true)
+    }
+
+    /// Saves the archive to a file, keeping the layout it was loaded with when preserve_layout is set (see [igArchive::save_preserving_layout]).
+    /// The archive is copied out of its memory map first, so this is safe to use on the file the archive was opened from
+    pub fn save_to(&self, ig_registry: &igRegistry, path: &Path, preserve_layout: bool) -> Result<(), String> {
+        let data = self.write(ig_registry, preserve_layout)?;
+        self.unmap();
+        std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Copies the archive out of its memory map, after which the file it was opened from can be changed or deleted. [igArchive::save_to] does this on its own
+    pub fn unmap(&self) {
+        let mut source = self.source.write().unwrap();
+        if let ArchiveSource::Mapped(mapped) = &*source {
+            *source = ArchiveSource::Memory(mapped.to_vec());
+        }
+    }
+
+    fn write(&self, ig_registry: &igRegistry, preserve_layout: bool) -> Result<Vec<u8>, String> {
+        let header = self._archive_header.read().unwrap();
+        let files = self._files.read().unwrap();
+        let source = self.source.read().unwrap();
+        let endian = header.endian.clone();
+        let sector_size = header._sector_size;
+        let header_size = get_header_size(header._version)? as u64;
+        let file_info_size = get_file_info_size(header._version)? as u64;
+
+        // Block tables are rebuilt unless the layout is being preserved, as the order of the files may have changed
+        let mut large_block_tbl: Vec<u32> = Vec::new();
+        let mut medium_block_tbl: Vec<u16> = Vec::new();
+        let mut small_block_tbl: Vec<u8> = Vec::new();
+        // The sector each block table entry used by a file's blocks starts at, so entries shared with the file before it aren't overwritten
+        let mut file_blocks: HashMap<(u8, usize), u32> = HashMap::new();
+        if preserve_layout {
+            large_block_tbl.clone_from(&self.block_tables.large);
+            medium_block_tbl.clone_from(&self.block_tables.medium);
+            small_block_tbl.clone_from(&self.block_tables.small);
+            large_block_tbl.resize(header._num_large_file_blocks as usize, 0);
+            medium_block_tbl.resize(header._num_medium_file_blocks as usize, 0);
+            small_block_tbl.resize(header._num_small_file_blocks as usize, 0);
+            for file in files.iter() {
+                let first_block = (file._block_index & 0x0FFFFFFF) as usize;
+                for (i, block) in file._blocks.iter().flatten().enumerate() {
+                    file_blocks.insert(
+                        (file.get_block_type(sector_size) as u8, first_block + i),
+                        block & 0x7FFFFFFF,
+                    );
                 }
-                0x04 => {
-                    header._toc_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_files = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._sector_size = 0x0800;
-                    header._hash_search_divider = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._hash_search_slop = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._name_table_offset =
-                        read_u32(&mut cursor, header.endian.clone()).unwrap() as u64;
-                    header._name_table_size = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_large_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_medium_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._num_small_file_blocks = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    header._flags = read_u32(&mut cursor, header.endian.clone()).unwrap();
+            }
+        }
+        let mut block_indices: Vec<u32> = Vec::with_capacity(files.len());
+        for file in files.iter() {
+            let blocks = match &file._blocks {
+                Some(blocks) => blocks,
+                None => {
+                    block_indices.push(0xFFFFFFFF);
+                    continue;
                 }
-                _ => {
+            };
+            let first_block = if preserve_layout {
+                (file._block_index & 0x0FFFFFFF) as usize
+            } else {
+                match file.get_block_type(sector_size) {
+                    EBlockType::kSmall => small_block_tbl.len(),
+                    EBlockType::kMedium => medium_block_tbl.len(),
+                    _ => large_block_tbl.len(),
+                }
+            };
+            let last_block = first_block + blocks.len();
+            let sector_count = blocks.first().map(|x| x & 0x7FFFFFFF).unwrap_or_default()
+                + file._compressed_size as u32 / sector_size;
+            // The entry after a file's blocks is only its own when no other file's blocks start there
+            let write_end = match file_blocks.get(&(file.get_block_type(sector_size) as u8, last_block)) {
+                None => true,
+                Some(next_block) if *next_block == sector_count => false,
+                Some(_) => {
                     return Err(format!(
-                        "igArchive version {} is not implemented.",
-                        header._version
+                        "{} no longer ends where the block after it starts",
+                        file._logical_name
                     ))
                 }
+            };
+            match file.get_block_type(sector_size) {
+                EBlockType::kSmall => {
+                    if sector_count > 0x7F {
+                        return Err(format!(
+                            "{} does not fit in the small block table",
+                            file._logical_name
+                        ));
+                    }
+                    if !preserve_layout {
+                        small_block_tbl.resize(last_block + 1, 0);
+                    } else if last_block >= small_block_tbl.len() {
+                        return Err(format!(
+                            "{} is outside of the original small block table",
+                            file._logical_name
+                        ));
+                    }
+                    for (i, block) in blocks.iter().enumerate() {
+                        small_block_tbl[first_block + i] =
+                            ((block >> 24) & 0x80) as u8 | (block & 0x7F) as u8;
+                    }
+                    if write_end {
+                        small_block_tbl[last_block] = sector_count as u8;
+                    }
+                }
+                EBlockType::kMedium => {
+                    if sector_count > 0x7FFF {
+                        return Err(format!(
+                            "{} does not fit in the medium block table",
+                            file._logical_name
+                        ));
+                    }
+                    if !preserve_layout {
+                        medium_block_tbl.resize(last_block + 1, 0);
+                    } else if last_block >= medium_block_tbl.len() {
+                        return Err(format!(
+                            "{} is outside of the original medium block table",
+                            file._logical_name
+                        ));
+                    }
+                    for (i, block) in blocks.iter().enumerate() {
+                        medium_block_tbl[first_block + i] =
+                            ((block >> 16) & 0x8000) as u16 | (block & 0x7FFF) as u16;
+                    }
+                    if write_end {
+                        medium_block_tbl[last_block] = sector_count as u16;
+                    }
+                }
+                EBlockType::kLarge => {
+                    if !preserve_layout {
+                        large_block_tbl.resize(last_block + 1, 0);
+                    } else if last_block >= large_block_tbl.len() {
+                        return Err(format!(
+                            "{} is outside of the original large block table",
+                            file._logical_name
+                        ));
+                    }
+                    large_block_tbl[first_block..last_block].copy_from_slice(blocks);
+                    if write_end {
+                        large_block_tbl[last_block] = sector_count;
+                    }
+                }
+                EBlockType::kNone => unreachable!(),
             }
+            if first_block > 0x0FFFFFFF {
+                return Err("Too many blocks present in the archive".to_string());
+            }
+            block_indices.push((file._block_index & 0xF0000000) | first_block as u32);
+        }
 
-            // File entries are stored in three sections: one section stores the hash, the second gets offset and other general info, and the last has a second set of info relating to names
-            let mut _files: Vec<FileInfo> = Vec::with_capacity(header._num_files as usize);
-            for _i in 0..header._num_files {
-                _files.push(FileInfo {
-                    _offset: 0,
-                    _ordinal: 0,
-                    _length: 0,
-                    _block_index: 0,
-                    _name: "".to_string(),
-                    _logical_name: "".to_string(),
-                    _modification_time: 0,
-                    _blocks: None,
-                    _compressed_data: vec![],
-                    _hash: read_u32(&mut cursor, header.endian.clone()).unwrap(),
-                })
+        let block_tbl_end = header_size
+            + files.len() as u64 * (0x04 + file_info_size)
+            + large_block_tbl.len() as u64 * 0x04
+            + medium_block_tbl.len() as u64 * 0x02
+            + small_block_tbl.len() as u64;
+        let toc_size = if preserve_layout {
+            header._toc_size as u64
+        } else {
+            block_tbl_end - header_size
+        };
+
+        // File data is written in the order of each file's ordinal, each one starting on a new sector
+        let mut file_order: Vec<usize> = (0..files.len()).collect();
+        let mut file_offsets = vec![0u64; files.len()];
+        let mut data_end = align(block_tbl_end, sector_size as u64);
+        if preserve_layout {
+            // Empty files can start where the next file does
+            file_order.sort_by_key(|i| (files[*i]._offset, files[*i]._compressed_size));
+            for i in &file_order {
+                let file = &files[*i];
+                if file._offset < data_end {
+                    return Err(format!(
+                        "{} overlaps with the data before it",
+                        file._logical_name
+                    ));
+                }
+                file_offsets[*i] = file._offset;
+                data_end = file._offset + file._compressed_size;
             }
+        } else {
+            file_order.sort_by_key(|i| files[*i]._ordinal);
+            for i in &file_order {
+                file_offsets[*i] = data_end;
+                data_end = align(
+                    data_end + files[*i]._compressed_size,
+                    sector_size as u64,
+                );
+            }
+        }
+        let name_tbl_offset = if !preserve_layout {
+            data_end
+        } else if header._name_table_offset >= data_end {
+            header._name_table_offset
+        } else {
+            return Err("File data overlaps with the name table".to_string());
+        };
+        // Only newer versions have a 5 byte offset for file data, and a 8 byte offset for the name table
+        let max_file_offset = if header._version >= 0x0B {
+            0xFF_FFFF_FFFF
+        } else {
+            u32::MAX as u64
+        };
+        if file_offsets.iter().any(|x| *x > max_file_offset)
+            || (header._version < 0x0A && name_tbl_offset > u32::MAX as u64)
+        {
+            return Err(format!(
+                "File data does not fit in igArchive version {}",
+                header._version
+            ));
+        }
+        // The 5 byte offset leaves 3 bytes for the ordinal
+        if let Some(file) = files.iter().find(|x| header._version >= 0x0B && x._ordinal > 0xFF_FFFF) {
+            return Err(format!(
+                "The ordinal of {} does not fit in igArchive version {}",
+                file._logical_name, header._version
+            ));
+        }
 
-            for i in 0..header._num_files {
-                let file = &mut _files[i as usize];
-
-                match header._version {
-                    0x0B => {
-                        // technically the offset is 5 bytes and the ordinal is 3
-                        let tmp = read_u64(&mut cursor, header.endian.clone()).unwrap(); // Read all 8 bytes together at once
-                        file._ordinal = (tmp >> 40) as u32;
-                        file._offset = (tmp & 0xFFFFFFFF) as u32; // FIXME: this looks like its reading 4 bytes, not 5...
-                        file._length = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        file._block_index = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                    }
-                    0x08 => {
-                        file._offset = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        file._length = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        file._block_index = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        // giants doesn't store the ordinal of the file?
-                    }
-                    0x04 => {
-                        file._offset = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        file._length = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        file._block_index = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                        // giants doesn't store the ordinal of the file?
-                    }
-                    _ => todo!("Unsupported IGA version"),
+        let mut name_tbl = Cursor::new(Vec::<u8>::new());
+        name_tbl
+            .seek(SeekFrom::Start(files.len() as u64 * 0x04))
+            .unwrap();
+        let mut name_offsets: Vec<u32> = Vec::with_capacity(files.len());
+        for file in files.iter() {
+            name_offsets.push(name_tbl.position() as u32);
+            // Same ordering rules as igArchive::open
+            let (name1, name2) =
+                if header._version >= 0x0B || ig_registry.build_tool == BuildTool::TfbTool {
+                    (&file._name, &file._logical_name)
+                } else {
+                    (&file._logical_name, &file._name)
+                };
+            write_string(&mut name_tbl, name1).unwrap();
+            if header._version >= 0x0A {
+                write_string(&mut name_tbl, name2).unwrap();
+            }
+            if header._version >= 0x08 {
+                write_u32(&mut name_tbl, file._modification_time, endian.clone()).unwrap();
+            }
+        }
+        name_tbl.seek(SeekFrom::Start(0)).unwrap();
+        for name_offset in name_offsets {
+            write_u32(&mut name_tbl, name_offset, endian.clone()).unwrap();
+        }
+        let name_tbl = name_tbl.into_inner();
+        if preserve_layout && name_tbl.len() != header._name_table_size as usize {
+            return Err(format!(
+                "Name table size changed from {} to {}",
+                header._name_table_size,
+                name_tbl.len()
+            ));
+        }
+
+        let mut cursor = Cursor::new(Vec::<u8>::with_capacity(
+            name_tbl_offset as usize + name_tbl.len(),
+        ));
+        write_u32(&mut cursor, u32::from_le_bytes(*b"IGA\x1A"), endian.clone()).unwrap();
+        write_u32(&mut cursor, header._version, endian.clone()).unwrap();
+        write_u32(&mut cursor, toc_size as u32, endian.clone()).unwrap();
+        write_u32(&mut cursor, files.len() as u32, endian.clone()).unwrap();
+        match header._version {
+            0x0B..=0x0D => {
+                write_u32(&mut cursor, sector_size, endian.clone()).unwrap();
+                write_u32(&mut cursor, header._hash_search_divider, endian.clone()).unwrap();
+                write_u32(&mut cursor, header._hash_search_slop, endian.clone()).unwrap();
+                write_u32(&mut cursor, large_block_tbl.len() as u32, endian.clone()).unwrap();
+                write_u32(&mut cursor, medium_block_tbl.len() as u32, endian.clone()).unwrap();
+                write_u32(&mut cursor, small_block_tbl.len() as u32, endian.clone()).unwrap();
+                write_u64(&mut cursor, name_tbl_offset, endian.clone()).unwrap();
+                write_u32(&mut cursor, name_tbl.len() as u32, endian.clone()).unwrap();
+            }
+            0x08 | 0x04 => {
+                if header._version == 0x08 {
+                    write_u32(&mut cursor, sector_size, endian.clone()).unwrap();
+                }
+                write_u32(&mut cursor, header._hash_search_divider, endian.clone()).unwrap();
+                write_u32(&mut cursor, header._hash_search_slop, endian.clone()).unwrap();
+                write_u32(&mut cursor, name_tbl_offset as u32, endian.clone()).unwrap();
+                write_u32(&mut cursor, name_tbl.len() as u32, endian.clone()).unwrap();
+                write_u32(&mut cursor, large_block_tbl.len() as u32, endian.clone()).unwrap();
+                write_u32(&mut cursor, medium_block_tbl.len() as u32, endian.clone()).unwrap();
+                write_u32(&mut cursor, small_block_tbl.len() as u32, endian.clone()).unwrap();
+            }
+            _ => {
+                return Err(format!(
+                    "igArchive version {} is not implemented.",
+                    header._version
+                ))
+            }
+        }
+        write_u32(&mut cursor, header._flags, endian.clone()).unwrap();
+
+        for file in files.iter() {
+            write_u32(&mut cursor, file._hash, endian.clone()).unwrap();
+        }
+
+        for (i, file) in files.iter().enumerate() {
+            match header._version {
+                0x0B..=0x0D => {
+                    write_u64(
+                        &mut cursor,
+                        ((file._ordinal as u64) << 40) | file_offsets[i],
+                        endian.clone(),
+                    )
+                    .unwrap();
+                    write_u32(&mut cursor, file._length, endian.clone()).unwrap();
+                    write_u32(&mut cursor, block_indices[i], endian.clone()).unwrap();
+                }
+                _ => {
+                    write_u32(&mut cursor, file_offsets[i] as u32, endian.clone()).unwrap();
+                    write_u32(&mut cursor, file._length, endian.clone()).unwrap();
+                    write_u32(&mut cursor, block_indices[i], endian.clone()).unwrap();
                 }
             }
+        }
+
+        for block in large_block_tbl {
+            write_u32(&mut cursor, block, endian.clone()).unwrap();
+        }
+        for block in medium_block_tbl {
+            write_u16(&mut cursor, block, endian.clone()).unwrap();
+        }
+        cursor.write_all(&small_block_tbl).unwrap();
+
+        for i in file_order {
+            cursor.seek(SeekFrom::Start(file_offsets[i])).unwrap();
+            cursor.write_all(files[i].compressed_data(source.bytes())?).unwrap();
+        }
+
+        cursor.seek(SeekFrom::Start(name_tbl_offset)).unwrap();
+        cursor.write_all(&name_tbl).unwrap();
+
+        Ok(cursor.into_inner())
+    }
+}
+
+/// Splits file data up into 0x8000 byte blocks, each one starting on a new sector. Returns the block information and the data that should be written.
+fn pack_blocks(
+    logical_name: &str,
+    data: &[u8],
+    sector_size: u32,
+    compression: &CompressionType,
+    iga_version: u32,
+) -> Result<(Vec<u32>, Vec<u8>), igArchiveError> {
+    let mut blocks = Vec::with_capacity(data.len().div_ceil(0x8000));
+    let mut packed_data =
+        Vec::with_capacity(align(data.len() as u64, sector_size as u64) as usize);
+    for (i, block) in data.chunks(0x8000).enumerate() {
+        let sector = packed_data.len() as u32 / sector_size;
+        let compressed_block = compress_block(block, compression, iga_version).map_err(|reason| {
+            igArchiveError::Compression {
+                file: logical_name.to_string(),
+                block: i,
+                reason,
+            }
+        })?;
+        if let Some(compressed_block) = compressed_block {
+            blocks.push(0x80000000u32 | sector);
+            packed_data.extend(compressed_block);
+        } else {
+            blocks.push(sector);
+            packed_data.extend_from_slice(block);
+        }
+        packed_data.resize(align(packed_data.len() as u64, sector_size as u64) as usize, 0);
+    }
+
+    Ok((blocks, packed_data))
+}
 
-            let name_tbl_offset = header._name_table_offset;
+/// Compresses a single block the way [igArchive::decompress] expects to read it (size prefix, then the compressed data).
+/// Returns None when the compressed block would not be any smaller than the original data.
+fn compress_block(
+    block: &[u8],
+    compression: &CompressionType,
+    iga_version: u32,
+) -> Result<Option<Vec<u8>>, String> {
+    let mut header = Vec::<u8>::with_capacity(7);
+    let compressed = match compression {
+        CompressionType::kUncompressed => return Ok(None),
+        CompressionType::kZlib => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(block).map_err(|e| e.to_string())?;
+            encoder.finish().map_err(|e| e.to_string())?
+        }
+        CompressionType::kLzma => {
+            let mut options = LZMA2Options::with_preset(6);
+            options.dict_size = 0x8000;
+            let mut writer = LZMAWriter::new_no_header(Vec::new(), &options, false)
+                .map_err(|e| e.to_string())?;
+            writer.write_all(block).map_err(|e| e.to_string())?;
+            // The lzma properties come after the size, but are not included in it
+            header.push(writer.props());
+            header.extend(options.dict_size.to_le_bytes());
+            writer.finish().map_err(|e| e.to_string())?
+        }
+        CompressionType::kLz4 => {
+            lz4::block::compress(block, None, false).map_err(|e| e.to_string())?
+        }
+        _ => return Err(format!("Unsupported compression type {:?}", compression)),
+    };
+
+    if compressed.len() + header.len() + 2 >= block.len() {
+        return Ok(None);
+    }
+
+    let mut dst = Vec::<u8>::with_capacity(compressed.len() + header.len() + 2);
+    if iga_version <= 0x04 {
+        dst.write_u16::<BigEndian>(compressed.len() as u16).unwrap();
+    } else {
+        dst.write_u16::<LittleEndian>(compressed.len() as u16).unwrap();
+    }
+    dst.extend(header);
+    dst.extend(compressed);
+    Ok(Some(dst))
+}
+
+fn align(value: u64, alignment: u64) -> u64 {
+    value.div_ceil(alignment) * alignment
+}
+
+/// Creates the file info for a new file, splitting it up into blocks. Fails if the hash of the file is already used
+fn pack_file(
+    header: &Header,
+    files: &[FileInfo],
+    logical_name: &str,
+    data: &[u8],
+    compression: &CompressionType,
+) -> Result<FileInfo, igArchiveError> {
+    let _hash = hash_file_path(header._flags, logical_name);
+    if files.iter().any(|file| file._hash == _hash) {
+        return Err(igArchiveError::FileExists(logical_name.to_string()));
+    }
+
+    let (_blocks, _compressed_data) =
+        pack_blocks(logical_name, data, header._sector_size, compression, header._version)?;
+    Ok(FileInfo {
+        _offset: 0,
+        _ordinal: 0,
+        _length: data.len() as u32,
+        _block_index: compression.to_index(header._version)? << 28,
+        _name: logical_name.to_string(),
+        _logical_name: logical_name.to_string(),
+        _modification_time: 0,
+        _blocks: Some(_blocks),
+        _compressed_size: _compressed_data.len() as u64,
+        _compressed_data: Some(_compressed_data),
+        _hash,
+    })
+}
+
+/// Sorts the files by hash and recalculates the values used by [igArchive::hash_search]
+fn rebuild_toc(header: &mut Header, files: &mut [FileInfo]) {
+    files.sort_by_key(|file| file._hash);
+
+    header._num_files = files.len() as u32;
+    header._hash_search_divider = u32::MAX / header._num_files.max(1);
+    header._hash_search_slop = 0;
+    for (i, file) in files.iter().enumerate() {
+        let expected = (file._hash / header._hash_search_divider) as i64;
+        header._hash_search_slop = header
+            ._hash_search_slop
+            .max((expected - i as i64).unsigned_abs() as u32);
+    }
+}
+
+/// Looks up a file the same way the game does, returning its index in the list of files
+fn find_file(header: &Header, files: &[FileInfo], path: &str) -> Option<usize> {
+    igArchive::hash_search(
+        files,
+        header._hash_search_divider,
+        header._hash_search_slop,
+        hash_file_path(header._flags, path),
+    )
+}
+
+/// Hashes a path the same way the game looks up files in an archive. flags are the archive header's flags
+fn hash_file_path(flags: u32, file_path: &str) -> u32 {
+    let mut path_copy = file_path.to_string();
+
+    // kCaseInsensitiveHash
+    if (flags & 1u32) != 0 {
+        path_copy = path_copy.replace("\\", "/");
+        path_copy = path_copy.to_lowercase();
+    }
+
+    // kHashNameAndExtensionOnly
+    if (flags & 2u32) != 0 {
+        path_copy = Path::new(&path_copy)
+            .file_name()
+            .and_then(|os_str| os_str.to_str())
+            .unwrap_or("")
+            .to_string();
+    }
+
+    path_copy = path_copy.trim_start_matches(['/', '\\']).to_string();
+    ig_hash::hash(&path_copy)
+}
+
+/// Opens the archive file, memory mapping it when possible
+fn load_source(
+    file_context: &igFileContext,
+    ig_registry: &igRegistry,
+    file_path: &str,
+) -> Result<(String, ArchiveSource), igArchiveError> {
+    let file_descriptor = file_context.open(ig_registry, file_path, OPEN_FLAG_MEMORY_MAP);
+    let path = file_descriptor._path;
+    if let Some(mapped) = file_descriptor._mapped {
+        Ok((path, ArchiveSource::Mapped(mapped)))
+    } else if let Some(handle) = file_descriptor._handle {
+        Ok((path, ArchiveSource::Memory(handle.into_inner())))
+    } else {
+        Err(igArchiveError::OpenFailed(path))
+    }
+}
+
+/// The size the i-th block of a file should decompress to. Every block is 0x8000 bytes except for the last one
+fn get_block_size(file_info: &FileInfo, i: usize) -> u32 {
+    if file_info._length < ((i + 1) * 0x8000) as u32 {
+        file_info._length & 0x7FFF
+    } else {
+        0x8000
+    }
+}
+
+/// Checks that the values in the header agree with each other and the size of the archive
+fn verify_header(header: &Header) -> Vec<igArchiveError> {
+    let mut problems = Vec::new();
+    if header._sector_size == 0 || !header._sector_size.is_power_of_two() {
+        problems.push(igArchiveError::InconsistentHeader(format!(
+            "the sector size {:#X} is not a power of two",
+            header._sector_size
+        )));
+    }
+    if header._hash_search_divider == 0 {
+        problems.push(igArchiveError::InconsistentHeader(
+            "the hash search divider is 0".to_string(),
+        ));
+    }
+
+    let header_size = get_header_size(header._version).unwrap_or_default() as u64;
+    let file_info_size = get_file_info_size(header._version).unwrap_or_default() as u64;
+    let toc_size = header._num_files as u64 * (0x04 + file_info_size)
+        + header._num_large_file_blocks as u64 * 0x04
+        + header._num_medium_file_blocks as u64 * 0x02
+        + header._num_small_file_blocks as u64;
+    if (header._toc_size as u64) < toc_size {
+        problems.push(igArchiveError::InconsistentHeader(format!(
+            "the toc size {:#X} is smaller than the {:#X} bytes of file info and block tables",
+            header._toc_size, toc_size
+        )));
+    }
+    if header._name_table_size != 0 && header._name_table_offset < header_size + toc_size {
+        problems.push(igArchiveError::InconsistentHeader(format!(
+            "the name table at {:#X} overlaps with the table of contents",
+            header._name_table_offset
+        )));
+    }
+    if header._name_table_size != 0 && (header._name_table_size as u64) < header._num_files as u64 * 0x04 {
+        problems.push(igArchiveError::InconsistentHeader(format!(
+            "the name table is {:#X} bytes which is too small for {} files",
+            header._name_table_size, header._num_files
+        )));
+    }
+
+    problems
+}
+
+/// Decompresses every block of a file, checking that each one is the size it should be
+fn verify_blocks(header: &Header, file_info: &FileInfo, compressed_data: &[u8]) -> Vec<igArchiveError> {
+    let mut problems = Vec::new();
+    let Some(blocks) = &file_info._blocks else {
+        return problems;
+    };
+    for (i, block) in blocks.iter().enumerate() {
+        match igArchive::decompress_block(header, file_info, compressed_data, *block, i) {
+            Ok(data) => {
+                let expected = get_block_size(file_info, i) as usize;
+                if data.len() != expected {
+                    problems.push(igArchiveError::BadBlockSize {
+                        file: file_info._logical_name.clone(),
+                        block: i,
+                        expected,
+                        actual: data.len(),
+                    });
+                }
+            }
+            Err(e) => problems.push(e),
+        }
+    }
+    problems
+}
 
-            for i in 0..header._num_files {
-                let file = &mut _files[i as usize];
-                // pointer to a pointer to the name information
-                cursor
-                    .seek(SeekFrom::Start(name_tbl_offset + i as u64 * 0x04))
-                    .unwrap();
-                let inner_ptr = read_u32(&mut cursor, header.endian.clone()).unwrap() as u64;
-                cursor
-                    .seek(SeekFrom::Start(name_tbl_offset + inner_ptr))
-                    .unwrap();
+/// Reads the header at the start of an archive
+fn read_header(data: &[u8], path: &str) -> Result<Header, igArchiveError> {
+    let truncated_toc = |_| igArchiveError::TruncatedToc(path.to_string());
+    let mut header = Header {
+        endian: Endian::Little,
+        _magic_number: 0,
+        _version: 0,
+        _toc_size: 0,
+        _num_files: 0,
+        _sector_size: 0,
+        _hash_search_divider: 0,
+        _hash_search_slop: 0,
+        _num_large_file_blocks: 0,
+        _num_medium_file_blocks: 0,
+        _num_small_file_blocks: 0,
+        _name_table_offset: 0,
+        _name_table_size: 0,
+        _flags: 0,
+    };
+
+    let mut cursor = Cursor::new(data[..data.len().min(0x38)].to_vec());
+    header._magic_number = read_u32(&mut cursor, Endian::Little)
+        .map_err(|_| igArchiveError::BadMagic(path.to_string()))?;
+
+    if header._magic_number == u32::from_be_bytes(*b"IGA\x1A") {
+        header.endian = Endian::Big;
+    } else if header._magic_number != u32::from_le_bytes(*b"IGA\x1A") {
+        return Err(igArchiveError::BadMagic(path.to_string()));
+    }
 
-                let name1 = read_string(&mut cursor).unwrap();
-                let mut name2 = None;
+    header._version = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+    match header._version {
+        // Crash Team Racing: Nitro Fueled, Crash NST, Trap Team, Superchargers, Imaginators
+        0x0B..=0x0D => {
+            header._toc_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_files = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._sector_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._hash_search_divider = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._hash_search_slop = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_large_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_medium_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_small_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._name_table_offset = read_u64(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._name_table_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._flags = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+        }
+        // TODO: lost islands/ssf (version 0x0A)
+        // SSA(WiiU), SG
+        0x08 => {
+            header._toc_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_files = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._sector_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._hash_search_divider = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._hash_search_slop = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._name_table_offset =
+                read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)? as u64;
+            header._name_table_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_large_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_medium_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_small_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._flags = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+        }
+        0x04 => {
+            header._toc_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_files = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._sector_size = 0x0800;
+            header._hash_search_divider = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._hash_search_slop = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._name_table_offset =
+                read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)? as u64;
+            header._name_table_size = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_large_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_medium_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._num_small_file_blocks = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            header._flags = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+        }
+        _ => return Err(igArchiveError::UnsupportedVersion(header._version)),
+    }
 
-                if header._version >= 0x0A {
-                    name2 = Some(read_string(&mut cursor).unwrap());
-                }
+    Ok(header)
+}
 
-                if header._version >= 0x08 {
-                    file._modification_time = read_u32(&mut cursor, header.endian.clone()).unwrap();
-                }
+/// Reads the hash and file info of every file, along with the block tables
+fn read_toc(
+    data: &[u8],
+    header: &Header,
+    path: &str,
+) -> Result<(Vec<FileInfo>, BlockTables), igArchiveError> {
+    let truncated_toc = |_| igArchiveError::TruncatedToc(path.to_string());
+    // Only the table of contents is copied out of the archive, file data is read once it's needed
+    let block_info_start = get_header_size(header._version)? as u64
+        + header._num_files as u64 * (0x04 + get_file_info_size(header._version)?) as u64;
+    let toc_end = block_info_start as usize
+        + header._num_large_file_blocks as usize * 0x04
+        + header._num_medium_file_blocks as usize * 0x02
+        + header._num_small_file_blocks as usize;
+    if toc_end > data.len() {
+        return Err(igArchiveError::TruncatedToc(path.to_string()));
+    }
+    let mut cursor = Cursor::new(data[..toc_end].to_vec());
+    cursor.seek(SeekFrom::Start(get_header_size(header._version)? as u64)).unwrap();
+
+    // File entries are stored in three sections: one section stores the hash, the second gets offset and other general info, and the last has a second set of info relating to names
+    let mut _files: Vec<FileInfo> = Vec::with_capacity(header._num_files as usize);
+    for _i in 0..header._num_files {
+        _files.push(FileInfo {
+            _offset: 0,
+            _ordinal: 0,
+            _length: 0,
+            _block_index: 0,
+            _name: "".to_string(),
+            _logical_name: "".to_string(),
+            _modification_time: 0,
+            _blocks: None,
+            _compressed_data: None,
+            _compressed_size: 0,
+            _hash: read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?,
+        })
+    }
 
-                // Cauldron reorganizes the names for lower versions. As far as I know, this is wrong but just in case we will handle Tfb Games the newer way because that's what we expect.
-                if header._version >= 0x0B || ig_registry.build_tool == BuildTool::TfbTool {
-                    file._name = name1;
-                    file._logical_name = name2.unwrap_or_default();
-                } else {
-                    file._logical_name = name1;
-                    file._name = name2.unwrap_or_default();
-                }
+    for i in 0..header._num_files {
+        let file = &mut _files[i as usize];
+
+        match header._version {
+            // Trap Team, Superchargers, Imaginators, Crash NST, Crash Team Racing: Nitro Fueled
+            0x0B..=0x0D => {
+                // the offset is 5 bytes and the ordinal is 3
+                // Read all 8 bytes together at once
+                let tmp = read_u64(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+                file._ordinal = (tmp >> 40) as u32;
+                file._offset = tmp & 0xFF_FFFF_FFFF;
+                file._length = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+                file._block_index = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+            }
+            0x08 => {
+                file._offset = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)? as u64;
+                file._length = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+                file._block_index = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+                // giants doesn't store the ordinal of the file?
+            }
+            0x04 => {
+                file._offset = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)? as u64;
+                file._length = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+                file._block_index = read_u32(&mut cursor, header.endian.clone()).map_err(truncated_toc)?;
+                // giants doesn't store the ordinal of the file?
             }
+            _ => return Err(igArchiveError::UnsupportedVersion(header._version)),
+        }
+    }
 
-            let block_info_start = get_header_size(header._version) as u64
-                + header._num_files as u64 * (0x04 + get_file_info_size(header._version)) as u64;
+    cursor.seek(SeekFrom::Start(block_info_start)).unwrap();
+    let large = read_struct_array_u32(
+        &mut cursor,
+        header.endian.clone(),
+        header._num_large_file_blocks as usize,
+    )
+    .map_err(truncated_toc)?;
+    let medium = read_struct_array_u16(
+        &mut cursor,
+        header.endian.clone(),
+        header._num_medium_file_blocks as usize,
+    )
+    .map_err(truncated_toc)?;
+    let small = read_struct_array_u8(
+        &mut cursor,
+        header.endian.clone(),
+        header._num_small_file_blocks as usize,
+    )
+    .map_err(truncated_toc)?;
+
+    Ok((_files, BlockTables { large, medium, small }))
+}
 
-            cursor.seek(SeekFrom::Start(block_info_start)).unwrap();
-            let large_block_tbl = read_struct_array_u32(
-                &mut cursor,
-                header.endian.clone(),
-                header._num_large_file_blocks as usize,
-            )
-            .unwrap();
-            let medium_block_tbl = read_struct_array_u16(
-                &mut cursor,
-                header.endian.clone(),
-                header._num_medium_file_blocks as usize,
-            )
-            .unwrap();
-            let small_block_tbl = read_struct_array_u8_ref(
-                &mut cursor,
-                header.endian.clone(),
-                header._num_small_file_blocks as usize,
-            )
-            .unwrap();
+/// Gets the name table out of an archive
+fn get_name_table<'a>(data: &'a [u8], header: &Header, path: &str) -> Result<&'a [u8], igArchiveError> {
+    (header._name_table_offset as usize)
+        .checked_add(header._name_table_size as usize)
+        .and_then(|name_tbl_end| data.get(header._name_table_offset as usize..name_tbl_end))
+        .ok_or_else(|| igArchiveError::NameTableOutOfRange(path.to_string()))
+}
 
-            for file in &mut _files {
-                cursor.seek(SeekFrom::Start(file._offset as u64)).unwrap();
-                if file._block_index == 0xFFFFFFFF {
-                    file._compressed_data =
-                        read_struct_array_u8(&mut cursor, header.endian.clone(), file._length as usize)
-                            .unwrap();
-                    continue;
-                }
+/// Reads the names and modification time of the file at index in the name table
+fn read_names(
+    name_cursor: &mut Cursor<Vec<u8>>,
+    header: &Header,
+    ig_registry: &igRegistry,
+    index: usize,
+    file: &mut FileInfo,
+    path: &str,
+) -> Result<(), igArchiveError> {
+    let name_tbl_out_of_range = |_| igArchiveError::NameTableOutOfRange(path.to_string());
+    // pointer to a pointer to the name information
+    name_cursor.seek(SeekFrom::Start(index as u64 * 0x04)).unwrap();
+    let inner_ptr = read_u32(name_cursor, header.endian.clone()).map_err(name_tbl_out_of_range)? as u64;
+    name_cursor.seek(SeekFrom::Start(inner_ptr)).unwrap();
+
+    let name1 = read_string(name_cursor).map_err(name_tbl_out_of_range)?;
+    let mut name2 = None;
+
+    if header._version >= 0x0A {
+        name2 = Some(read_string(name_cursor).map_err(name_tbl_out_of_range)?);
+    }
 
-                let mut sector_count = 0;
-                let block_count = (file._length + 0x7FFF) >> 0xF;
-                let mut fixed_blocks: Vec<u32> = Vec::with_capacity(block_count as usize);
-                for _i in 0..block_count as usize {
-                    fixed_blocks.push(0);
-                }
+    if header._version >= 0x08 {
+        file._modification_time =
+            read_u32(name_cursor, header.endian.clone()).map_err(name_tbl_out_of_range)?;
+    }
 
-                for i in 0..block_count {
-                    let block_idx = ((file._block_index & 0x0FFFFFFF) + i) as usize;
-                    let is_compressed;
-                    let mut block;
-                    if 0x7F * header._sector_size < file._length {
-                        if 0x7FFF * header._sector_size < file._length {
-                            block = large_block_tbl[block_idx];
-                            is_compressed = (block >> 0x1F) == 1;
-                            block &= 0x7FFFFFFF;
-                            sector_count += (large_block_tbl[block_idx + 1] & 0x7FFFFFFF) - block;
-                        } else {
-                            block = medium_block_tbl[block_idx] as u32;
-                            is_compressed = (block >> 0x0F) == 1;
-                            block &= 0x7FFF;
-                            sector_count +=
-                                (medium_block_tbl[block_idx + 1] & 0x7FFF) as u32 - block;
-                        }
-                    } else {
-                        block = small_block_tbl[block_idx] as u32;
-                        is_compressed = (block >> 0x07) == 1;
-                        block &= 0x7F;
-                        sector_count += (small_block_tbl[block_idx + 1] & 0x7F) as u32 - block;
-                    }
+    // Cauldron reorganizes the names for lower versions. As far as I know, this is wrong but just in case we will handle Tfb Games the newer way because that's what we expect.
+    if header._version >= 0x0B || ig_registry.build_tool == BuildTool::TfbTool {
+        file._name = name1;
+        file._logical_name = name2.unwrap_or_default();
+    } else {
+        file._logical_name = name1;
+        file._name = name2.unwrap_or_default();
+    }
 
-                    fixed_blocks[i as usize] =
-                        if is_compressed { 0x80000000u32 } else { 0u32 } | block;
-                }
+    Ok(())
+}
 
-                file._blocks = Some(fixed_blocks);
-                file._compressed_data = read_struct_array_u8(
-                    &mut cursor,
-                    header.endian.clone(),
-                    (sector_count * header._sector_size) as usize,
-                )
-                .unwrap()
-            }
+/// Looks up the names of a file from its hash, for archives that have no name table
+fn recover_names(file: &mut FileInfo) {
+    if let Some(name) = find_string(file._hash) {
+        file._logical_name = name.clone();
+        file._name = name;
+    }
+}
 
-            // Hint to the compiler to drop this as soon as possible
-            file_descriptor._handle = None;
-
-            Ok(igArchive {
-                next_processor: None,
-                _path,
-                _name: "".to_string(),
-                _load_name_table: false,
-                _sequential_read: false,
-                _loading_for_incremental_update: false,
-                _enable_cache: false,
-                _override: false,
-                _open: false,
-                _configured: false,
-                _needs_endian_swap: false,
-                _archive_header: header,
-                _files,
-                _native_media: "".to_string(),
-                _native_path: "".to_string(),
-                _native_app_path: "".to_string(),
-            })
-        } else {
-            Err("file_descriptor._handle was not available".to_string())
+/// Works out which blocks make up a file and where its data is. data_len is the size of the archive
+fn read_blocks(
+    header: &Header,
+    tables: &BlockTables,
+    file: &mut FileInfo,
+    data_len: u64,
+) -> Result<(), igArchiveError> {
+    if file._block_index == 0xFFFFFFFF {
+        file._compressed_size = file._length as u64;
+        if file._offset.saturating_add(file._compressed_size) > data_len {
+            return Err(igArchiveError::FileDataOutOfRange(file._logical_name.clone()));
         }
+        return Ok(());
     }
 
-    pub fn new() -> Self {
-        igArchive {
-            next_processor: None,
-            _path: "".to_string(),
-            _name: "".to_string(),
-            _load_name_table: false,
-            _sequential_read: false,
-            _loading_for_incremental_update: false,
-            _enable_cache: false,
-            _override: false,
-            _open: false,
-            _configured: false,
-            _needs_endian_swap: false,
-            _archive_header: Header {
-                endian: Endian::Little,
-                _magic_number: 0,
-                _version: 0,
-                _toc_size: 0,
-                _num_files: 0,
-                _sector_size: 0,
-                _hash_search_divider: 0,
-                _hash_search_slop: 0,
-                _num_large_file_blocks: 0,
-                _num_medium_file_blocks: 0,
-                _num_small_file_blocks: 0,
-                _name_table_offset: 0,
-                _name_table_size: 0,
-                _flags: 0,
-            },
-            _files: vec![],
-            _native_media: "".to_string(),
-            _native_path: "".to_string(),
-            _native_app_path: "".to_string(),
-        }
+    let mut sector_count = 0u64;
+    let block_count = (file._length + 0x7FFF) >> 0xF;
+    let mut fixed_blocks: Vec<u32> = Vec::with_capacity(block_count as usize);
+    for _i in 0..block_count as usize {
+        fixed_blocks.push(0);
+    }
+
+    for i in 0..block_count {
+        let block_idx = ((file._block_index & 0x0FFFFFFF) + i) as usize;
+        let bad_block_index = || igArchiveError::BadBlockIndex {
+            file: file._logical_name.clone(),
+            index: block_idx,
+        };
+        let length = file._length as u64;
+        let sector_size = header._sector_size as u64;
+        // Each block table stores whether a block is compressed in its top bit
+        let (block, next_block, compressed_bit) = if 0x7FFF * sector_size < length {
+            (
+                tables.large.get(block_idx).copied(),
+                tables.large.get(block_idx + 1).copied(),
+                0x80000000u32,
+            )
+        } else if 0x7F * sector_size < length {
+            (
+                tables.medium.get(block_idx).map(|x| *x as u32),
+                tables.medium.get(block_idx + 1).map(|x| *x as u32),
+                0x8000,
+            )
+        } else {
+            (
+                tables.small.get(block_idx).map(|x| *x as u32),
+                tables.small.get(block_idx + 1).map(|x| *x as u32),
+                0x80,
+            )
+        };
+        let (Some(block), Some(next_block)) = (block, next_block) else {
+            return Err(bad_block_index());
+        };
+
+        let is_compressed = block & compressed_bit != 0;
+        let block = block & (compressed_bit - 1);
+        sector_count += (next_block & (compressed_bit - 1))
+            .checked_sub(block)
+            .ok_or_else(bad_block_index)? as u64;
+        fixed_blocks[i as usize] =
+            if is_compressed { 0x80000000u32 } else { 0u32 } | block;
     }
+
+    file._blocks = Some(fixed_blocks);
+    file._compressed_size = sector_count.saturating_mul(header._sector_size as u64);
+    if file._offset.saturating_add(file._compressed_size) > data_len {
+        return Err(igArchiveError::FileDataOutOfRange(file._logical_name.clone()));
+    }
+    Ok(())
 }
 
-fn get_header_size(version: u32) -> u8 {
+fn get_header_size(version: u32) -> Result<u8, igArchiveError> {
     match version {
-        0x0A..=0x0D => 0x38,
-        0x08 => 0x34,
-        0x04 => 0x30,
-        _ => panic!("IGA version {} is unsupported", version),
+        0x0B..=0x0D => Ok(0x38),
+        0x08 => Ok(0x34),
+        0x04 => Ok(0x30),
+        _ => Err(igArchiveError::UnsupportedVersion(version)),
     }
 }
 
-fn get_file_info_size(version: u32) -> u8 {
+fn get_file_info_size(version: u32) -> Result<u8, igArchiveError> {
     match version {
-        0x0B => 0x10,
-        0x08 | 0x04 => 0x0C,
-        _ => panic!("IGA version {} is unsupported", version),
+        0x0B..=0x0D => Ok(0x10),
+        0x08 | 0x04 => Ok(0x0C),
+        _ => Err(igArchiveError::UnsupportedVersion(version)),
     }
 }
 
@@ -586,50 +1862,13 @@ impl igStorageDevice for igArchive {
     }
 
     fn open(&self, this: Arc<Mutex<dyn igFileWorkItemProcessor>>, work_item: &mut igFileWorkItem) {
-        match work_item.ig_registry.build_tool {
-            BuildTool::AlchemyLaboratory => {
-                #[cfg(debug_assertions)]
-                debug!(
-                    "{} has hash {}",
-                    work_item._path,
-                    self.hash_file_path(&work_item._path)
-                );
-                if let Some(file_idx) = Self::hash_search(
-                    &self._files,
-                    self._archive_header._hash_search_divider,
-                    self._archive_header._hash_search_slop,
-                    self.hash_file_path(&work_item._path),
-                ) {
-                    let file = &self._files[file_idx];
-                    work_item._file._path = work_item._path.clone();
-                    work_item._file._size = file._length as u64;
-                    work_item._file._position = 0;
-                    work_item._file._device = Some(this.clone());
-                    work_item._file._handle = Some(self.decompress_as_handle(file));
-                    work_item._status = kStatusComplete;
-                } else {
-                    work_item._status = kStatusInvalidPath
-                }
-            }
-            BuildTool::TfbTool => {
-                let file_name = &work_item._path[(work_item._path.rfind('/').unwrap() + 1)..];
-                let archive_path = &work_item._path[..work_item._path.rfind('/').unwrap()];
-
-                if archive_path == self._path {
-                    for file in &self._files {
-                        if file._name == file_name {
-                            work_item._file._path = work_item._path.clone();
-                            work_item._file._size = file._length as u64;
-                            work_item._file._position = 0;
-                            work_item._file._device = Some(this.clone());
-                            work_item._file._handle = Some(self.decompress_as_handle(file));
-                            work_item._status = kStatusComplete;
-                        }
-                    }
-                }
-            }
-
-            _ => panic!("Unsupported Game Tooling"),
+        if let Err(e) = self.open_work_item(this, work_item) {
+            error!("Failed to open {}: {}", work_item._path, e);
+            work_item._status = match e {
+                igArchiveError::InvalidPath(_) => kStatusInvalidPath,
+                igArchiveError::UnsupportedBuildTool => kStatusUnsupported,
+                _ => kStatusGeneralError,
+            };
         }
     }
 
@@ -650,15 +1889,38 @@ impl igStorageDevice for igArchive {
         _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        let result = match &work_item._buffer {
+            WorkItemBuffer::Bytes(bytes) => {
+                self.write_file(&work_item._path, work_item._offset, bytes)
+            }
+            _ => {
+                work_item._status = kStatusBadParam;
+                return;
+            }
+        };
+        match result {
+            Ok(_) => work_item._status = kStatusComplete,
+            Err(e) => {
+                error!("{}", e);
+                work_item._status = kStatusGeneralError
+            }
+        }
     }
 
+    /// Truncates the file to the size stored in the work item's offset
     fn truncate(
         &self,
         _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        if self
+            .truncate_file(&work_item._path, work_item._offset)
+            .is_ok()
+        {
+            work_item._status = kStatusComplete
+        } else {
+            work_item._status = kStatusInvalidPath
+        }
     }
 
     fn mkdir(
@@ -684,7 +1946,7 @@ impl igStorageDevice for igArchive {
     ) {
         match &mut work_item._buffer {
             WorkItemBuffer::StringRefList(files) => {
-                for file_info in &self._files {
+                for file_info in self._files.read().unwrap().iter() {
                     files.push(file_info._logical_name.clone())
                 }
             }
@@ -707,19 +1969,31 @@ impl igStorageDevice for igArchive {
         _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        if delete(&work_item._path).is_ok() {
+        if self.delete_file(&work_item._path).is_ok() {
             work_item._status = kStatusComplete
         } else {
             work_item._status = kStatusInvalidPath
         }
     }
 
+    /// The new path is passed in as the only entry of the work item's buffer
     fn rename(
         &self,
         _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        let new_path = match &work_item._buffer {
+            WorkItemBuffer::StringRefList(paths) if paths.len() == 1 => &paths[0],
+            _ => {
+                work_item._status = kStatusBadParam;
+                return;
+            }
+        };
+        if self.rename_file(&work_item._path, new_path).is_ok() {
+            work_item._status = kStatusComplete
+        } else {
+            work_item._status = kStatusInvalidPath
+        }
     }
 
     fn prefetch(
@@ -757,197 +2031,142 @@ impl igFileWorkItemProcessor for Arc<igArchive> {
         this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        if let Some(processor) = self.next_processor.clone() {
-            let processor_lock = processor.read().unwrap();
-            processor_lock.process(this, work_item);
-        }
+        (**self).send_to_next_processor(this, work_item)
     }
 
     fn as_ig_storage(&self) -> &dyn igStorageDevice {
-        self
+        &**self
     }
 }
 
+/// Forwards everything to [igArchive] so both share the same logic
 impl igStorageDevice for Arc<igArchive> {
     fn get_path(&self) -> String {
-        self._path.clone()
+        igStorageDevice::get_path(&**self)
     }
 
     fn get_name(&self) -> String {
-        self._name.clone()
+        igStorageDevice::get_name(&**self)
     }
 
     fn exists(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        if self.has_file(&work_item._path) {
-            work_item._status = kStatusComplete;
-        } else {
-            work_item._status = kStatusInvalidPath;
-        }
+        igStorageDevice::exists(&**self, this, work_item)
     }
 
-    fn open(&self, this: Arc<Mutex<dyn igFileWorkItemProcessor>>, work_item: &mut igFileWorkItem) {
-        match work_item.ig_registry.build_tool {
-            BuildTool::AlchemyLaboratory => {
-                #[cfg(debug_assertions)]
-                debug!(
-                    "{} has hash {}",
-                    work_item._path,
-                    self.hash_file_path(&work_item._path)
-                );
-                if let Some(file_idx) = igArchive::hash_search(
-                    &self._files,
-                    self._archive_header._hash_search_divider,
-                    self._archive_header._hash_search_slop,
-                    self.hash_file_path(&work_item._path),
-                ) {
-                    let file = &self._files[file_idx];
-                    work_item._file._path = work_item._path.clone();
-                    work_item._file._size = file._length as u64;
-                    work_item._file._position = 0;
-                    work_item._file._device = Some(this.clone());
-                    work_item._file._handle = Some(self.decompress_as_handle(file));
-                    work_item._status = kStatusComplete;
-                } else {
-                    work_item._status = kStatusInvalidPath
-                }
-            }
-            BuildTool::TfbTool => {
-                let file_name = &work_item._path[(work_item._path.rfind('/').unwrap() + 1)..];
-                let archive_path = &work_item._path[..work_item._path.rfind('/').unwrap()];
-
-                if archive_path == self._path {
-                    for file in &self._files {
-                        if file._name == file_name {
-                            work_item._file._path = work_item._path.clone();
-                            work_item._file._size = file._length as u64;
-                            work_item._file._position = 0;
-                            work_item._file._device = Some(this.clone());
-                            work_item._file._handle = Some(self.decompress_as_handle(file));
-                            work_item._status = kStatusComplete;
-                            return;
-                        }
-                    }
-                }
-            }
-            _ => panic!("Unsupported Game Tooling"),
-        }
+    fn open(
+        &self,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        work_item: &mut igFileWorkItem,
+    ) {
+        igStorageDevice::open(&**self, this, work_item)
     }
 
     fn close(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::close(&**self, this, work_item)
     }
 
-    fn read(&self, _this: Arc<Mutex<dyn igFileWorkItemProcessor>>, work_item: &mut igFileWorkItem) {
-        work_item._status = kStatusUnsupported
+    fn read(
+        &self,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        work_item: &mut igFileWorkItem,
+    ) {
+        igStorageDevice::read(&**self, this, work_item)
     }
 
     fn write(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::write(&**self, this, work_item)
     }
 
     fn truncate(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::truncate(&**self, this, work_item)
     }
 
     fn mkdir(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::mkdir(&**self, this, work_item)
     }
 
     fn rmdir(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::rmdir(&**self, this, work_item)
     }
 
     fn get_file_list(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        match &mut work_item._buffer {
-            WorkItemBuffer::StringRefList(files) => {
-                for file_info in &self._files {
-                    files.push(file_info._logical_name.clone())
-                }
-            }
-            _ => {
-                work_item._status = kStatusGeneralError;
-            }
-        }
+        igStorageDevice::get_file_list(&**self, this, work_item)
     }
 
     fn get_file_list_with_sizes(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::get_file_list_with_sizes(&**self, this, work_item)
     }
 
     fn unlink(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        if delete(&work_item._path).is_ok() {
-            work_item._status = kStatusComplete
-        } else {
-            work_item._status = kStatusInvalidPath
-        }
+        igStorageDevice::unlink(&**self, this, work_item)
     }
 
     fn rename(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::rename(&**self, this, work_item)
     }
 
     fn prefetch(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::prefetch(&**self, this, work_item)
     }
 
     fn format(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::format(&**self, this, work_item)
     }
 
     fn commit(
         &self,
-        _this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
+        this: Arc<Mutex<dyn igFileWorkItemProcessor>>,
         work_item: &mut igFileWorkItem,
     ) {
-        work_item._status = kStatusUnsupported
+        igStorageDevice::commit(&**self, this, work_item)
     }
 }
 
@@ -972,7 +2191,7 @@ pub struct Header {
 /// <summary>
 /// Different compression formats
 /// </summary>
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[repr(usize)]
 pub enum CompressionType {
     kUncompressed = 0,
@@ -986,33 +2205,51 @@ pub enum CompressionType {
 }
 
 impl CompressionType {
-    fn from_index(block_index: u32, iga_version: u32) -> CompressionType {
+    fn from_index(block_index: u32, iga_version: u32) -> Result<CompressionType, igArchiveError> {
         let shift = block_index >> 28;
-        
+
         // FIXME: SSA does compression type a bit differently.
         if iga_version <= 0x04 {
             match shift {
-                0 => CompressionType::kUncompressed,
-                1 => CompressionType::kLzma,
-                2 => CompressionType::kLzma,
-                3 => CompressionType::kLz4,
-                28 => CompressionType::kCompressionFormatShift,
-                0xF0000000 => CompressionType::kCompressionFormatMask,
-                0x0FFFFFFF => CompressionType::kFirstBlockMask,
-                40 => CompressionType::kOffsetBits,
-                _ => panic!("Unknown compression type"),
+                0 => Ok(CompressionType::kUncompressed),
+                1 => Ok(CompressionType::kLzma),
+                2 => Ok(CompressionType::kLzma),
+                3 => Ok(CompressionType::kLz4),
+                _ => Err(igArchiveError::UnsupportedCompression(shift)),
             }
         } else {
             match shift {
-                0 => CompressionType::kUncompressed,
-                1 => CompressionType::kZlib,
-                2 => CompressionType::kLzma,
-                3 => CompressionType::kLz4,
-                28 => CompressionType::kCompressionFormatShift,
-                0xF0000000 => CompressionType::kCompressionFormatMask,
-                0x0FFFFFFF => CompressionType::kFirstBlockMask,
-                40 => CompressionType::kOffsetBits,
-                _ => panic!("Unknown compression type"),
+                0 => Ok(CompressionType::kUncompressed),
+                1 => Ok(CompressionType::kZlib),
+                2 => Ok(CompressionType::kLzma),
+                3 => Ok(CompressionType::kLz4),
+                _ => Err(igArchiveError::UnsupportedCompression(shift)),
+            }
+        }
+    }
+
+    /// The opposite of [CompressionType::from_index]. Returns the value stored in the top 4 bits of a file's block index
+    pub fn to_index(&self, iga_version: u32) -> Result<u32, igArchiveError> {
+        if iga_version <= 0x04 {
+            match self {
+                CompressionType::kUncompressed => Ok(0),
+                CompressionType::kLzma => Ok(1),
+                CompressionType::kLz4 => Ok(3),
+                _ => Err(igArchiveError::UnsupportedCompressionForVersion {
+                    compression: format!("{:?}", self),
+                    version: iga_version,
+                }),
+            }
+        } else {
+            match self {
+                CompressionType::kUncompressed => Ok(0),
+                CompressionType::kZlib => Ok(1),
+                CompressionType::kLzma => Ok(2),
+                CompressionType::kLz4 => Ok(3),
+                _ => Err(igArchiveError::UnsupportedCompressionForVersion {
+                    compression: format!("{:?}", self),
+                    version: iga_version,
+                }),
             }
         }
     }
@@ -1030,7 +2267,7 @@ pub enum EBlockType {
 
 pub struct FileInfo {
     /// The hash of the file
-    pub _offset: u32,
+    pub _offset: u64,
     /// The offset within the file (do we really need to store this?)
     pub _ordinal: u32,
     /// The ordinal, this represents the order of how the compressed data is written (could this be inferred?)
@@ -1046,12 +2283,103 @@ pub struct FileInfo {
     /// The modification time of the file (for some reason this is never accurate)
     pub _blocks: Option<Vec<u32>>,
     /// The block information
-    pub _compressed_data: Vec<u8>,
-    /// The actual compressed data
+    pub _compressed_data: Option<Vec<u8>>,
+    /// The actual compressed data of a file that was edited. [None] for files that haven't been, as they're read from the archive when needed
+    pub _compressed_size: u64,
+    /// The size of the compressed data
     pub _hash: u32,
 }
 
+/// The block tables of an archive. Each entry is the sector a block starts at, relative to the start of the file's data
+#[derive(Default)]
+struct BlockTables {
+    large: Vec<u32>,
+    medium: Vec<u16>,
+    small: Vec<u8>,
+}
+
+/// Where the file data of an opened archive is read from
+enum ArchiveSource {
+    None,
+    Memory(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl ArchiveSource {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            ArchiveSource::None => &[],
+            ArchiveSource::Memory(data) => data,
+            ArchiveSource::Mapped(mapped) => mapped,
+        }
+    }
+}
+
+/// Least recently used cache of decompressed files, keyed by the hash of the file
+struct FileCache {
+    capacity: usize,
+    size: usize,
+    /// The most recently used file is last
+    entries: Vec<(u32, Vec<u8>)>,
+}
+
+impl FileCache {
+    fn new() -> Self {
+        FileCache {
+            capacity: 0,
+            size: 0,
+            entries: vec![],
+        }
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    fn get(&mut self, hash: u32) -> Option<Vec<u8>> {
+        let idx = self.entries.iter().position(|x| x.0 == hash)?;
+        let entry = self.entries.remove(idx);
+        let data = entry.1.clone();
+        self.entries.push(entry);
+        Some(data)
+    }
+
+    fn insert(&mut self, hash: u32, data: &[u8]) {
+        if data.len() > self.capacity {
+            return;
+        }
+        self.remove(hash);
+        self.size += data.len();
+        self.entries.push((hash, data.to_vec()));
+        self.evict();
+    }
+
+    fn remove(&mut self, hash: u32) {
+        if let Some(idx) = self.entries.iter().position(|x| x.0 == hash) {
+            self.size -= self.entries.remove(idx).1.len();
+        }
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.capacity {
+            self.size -= self.entries.remove(0).1.len();
+        }
+    }
+}
+
 impl FileInfo {
+    /// The compressed data of the file. Files that haven't been edited are read straight out of the archive they were loaded from
+    fn compressed_data<'a>(&'a self, source: &'a [u8]) -> Result<&'a [u8], igArchiveError> {
+        if let Some(data) = &self._compressed_data {
+            return Ok(data);
+        }
+        let start = self._offset as usize;
+        source
+            .get(start..start.saturating_add(self._compressed_size as usize))
+            .ok_or_else(|| igArchiveError::FileDataOutOfRange(self._logical_name.clone()))
+    }
+
     pub fn get_block_type(&self, sector_size: u32) -> EBlockType {
         if self._blocks.is_none() {
             return EBlockType::kNone;
@@ -1,5 +1,5 @@
 use crate::core::ig_fs::{igFileWorkItemProcessor, igStorageDevice};
-use crate::core::ig_archive::igArchive;
+use crate::core::ig_archive::{igArchive, igArchiveError};
 use crate::core::ig_file_context::WorkStatus::kStatusComplete;
 use crate::core::ig_file_context::{igFileContext, igFileWorkItem, WorkType};
 use crate::core::ig_custom::igArchiveList;
@@ -29,20 +29,20 @@ impl igArchiveManager {
         ig_file_context: &igFileContext,
         ig_registry: &igRegistry,
         path: &str,
-    ) -> Arc<igArchive> {
+    ) -> Result<Arc<igArchive>, igArchiveError> {
         if let Ok(archive_manager) = archive_manager.read() {
             if let Some(archive) = archive_manager.try_get_archive(path) {
-                return archive
+                return Ok(archive)
             }
         }
 
-        let arc = Arc::new(igArchive::open(ig_file_context, ig_registry, path).unwrap());
+        let arc = Arc::new(igArchive::open(ig_file_context, ig_registry, path)?);
 
         if let Ok(archive_manager) = archive_manager.write() {
             archive_manager._archive_list.push(arc.clone());
         }
 
-        arc
+        Ok(arc)
     }
 
     pub fn try_get_archive(&self, path: &str) -> Option<Arc<igArchive>> {
@@ -6,12 +6,19 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use serde::Serialize;
 use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::meta::field::r#impl::ig_array_meta_field::igArrayMetaField;
+use crate::core::meta::field::r#impl::ig_bit_field_meta_field::igBitFieldMetaField;
+use crate::core::meta::field::r#impl::ig_enum_meta_field::igEnumMetaField;
+use crate::core::meta::field::r#impl::ig_inline_meta_fields::*;
 use crate::core::meta::field::r#impl::ig_int_meta_field::igIntMetaField;
 use crate::core::meta::field::r#impl::ig_memory_ref_meta_field::igMemoryRefMetaField;
 use crate::core::meta::field::r#impl::ig_object_ref_meta_field::igObjectRefMetaField;
 use crate::core::meta::field::r#impl::ig_size_type_meta_field::igSizeTypeMetaField;
 use crate::core::meta::field::r#impl::ig_string_meta_field::igStringMetaField;
+use crate::core::meta::field::r#impl::ig_vector_meta_field::igVectorMetaField;
+use crate::util::ig_hash_dictionary::{load_dictionary, DICTIONARY_PATH};
 use crate::util::ig_name::igNameMetaField;
+use log::error;
 
 /// Contains reflection metadata information. Stands for Application Runtime Kernel.
 pub struct igArkCore {
@@ -24,17 +31,42 @@ impl igArkCore {
         let xml_metadata = load_xml_metadata(&metadata_path).unwrap_or_else(|_| panic!("Failed to find metadata at path {}", metadata_path.display()));
         let mut metadata_manager = igMetadataManager::new(xml_metadata.0, xml_metadata.1, xml_metadata.2, platform);
         register_metafields(&mut metadata_manager);
+
+        let dictionary_path = PathBuf::from(DICTIONARY_PATH);
+        if dictionary_path.exists() {
+            if let Err(err) = load_dictionary(&dictionary_path) {
+                error!("Failed to load hash dictionary at {}: {}", dictionary_path.display(), err);
+            }
+        }
+
         igArkCore { metadata_manager }
     }
 }
 
 /// Registers all built in meta fields to the [core::meta::field::ig_metafield_registry::igMetafieldRegistry]
-fn register_metafields(imm: &mut igMetadataManager) {
+pub(crate) fn register_metafields(imm: &mut igMetadataManager) {
     imm.meta_field_registry.register::<igIntMetaField>(Arc::from("igIntMetaField"), Arc::new(igIntMetaField));
     imm.meta_field_registry.register::<igStringMetaField>(Arc::from("igStringMetaField"), Arc::new(igStringMetaField));
     imm.meta_field_registry.register::<igNameMetaField>(Arc::from("igNameMetaField"), Arc::new(igNameMetaField));
     imm.meta_field_registry.register::<igSizeTypeMetaField>(Arc::from("igSizeTypeMetaField"), Arc::new(igSizeTypeMetaField));
     imm.meta_field_registry.register::<igObjectRefMetaField>(Arc::from("igObjectRefMetaField"), Arc::new(igObjectRefMetaField));
+    imm.meta_field_registry.register::<igBoolMetaField>(Arc::from("igBoolMetaField"), Arc::new(igBoolMetaField::new()));
+    imm.meta_field_registry.register::<igCharMetaField>(Arc::from("igCharMetaField"), Arc::new(igCharMetaField::new()));
+    imm.meta_field_registry.register::<igUnsignedCharMetaField>(Arc::from("igUnsignedCharMetaField"), Arc::new(igUnsignedCharMetaField::new()));
+    imm.meta_field_registry.register::<igShortMetaField>(Arc::from("igShortMetaField"), Arc::new(igShortMetaField::new()));
+    imm.meta_field_registry.register::<igUnsignedShortMetaField>(Arc::from("igUnsignedShortMetaField"), Arc::new(igUnsignedShortMetaField::new()));
+    imm.meta_field_registry.register::<igUnsignedIntMetaField>(Arc::from("igUnsignedIntMetaField"), Arc::new(igUnsignedIntMetaField::new()));
+    imm.meta_field_registry.register::<igLongMetaField>(Arc::from("igLongMetaField"), Arc::new(igLongMetaField::new()));
+    imm.meta_field_registry.register::<igUnsignedLongMetaField>(Arc::from("igUnsignedLongMetaField"), Arc::new(igUnsignedLongMetaField::new()));
+    imm.meta_field_registry.register::<igFloatMetaField>(Arc::from("igFloatMetaField"), Arc::new(igFloatMetaField::new()));
+    imm.meta_field_registry.register::<igTimeMetaField>(Arc::from("igTimeMetaField"), Arc::new(igTimeMetaField::new()));
+    imm.meta_field_registry.register::<igVec2fMetaField>(Arc::from("igVec2fMetaField"), Arc::new(igVec2fMetaField::new()));
+    imm.meta_field_registry.register::<igVec3fMetaField>(Arc::from("igVec3fMetaField"), Arc::new(igVec3fMetaField::new()));
+    imm.meta_field_registry.register::<igVec3fAlignedMetaField>(Arc::from("igVec3fAlignedMetaField"), Arc::new(igVec3fAlignedMetaField::new()));
+    imm.meta_field_registry.register::<igVec4fMetaField>(Arc::from("igVec4fMetaField"), Arc::new(igVec4fMetaField::new()));
+    imm.meta_field_registry.register::<igQuaternionfMetaField>(Arc::from("igQuaternionfMetaField"), Arc::new(igQuaternionfMetaField::new()));
+    imm.meta_field_registry.register::<igMatrix44fMetaField>(Arc::from("igMatrix44fMetaField"), Arc::new(igMatrix44fMetaField::new()));
+    imm.meta_field_registry.register::<igRandomMetaField>(Arc::from("igRandomMetaField"), Arc::new(igRandomMetaField::new()));
     imm.meta_field_registry.register_complex::<igMemoryRefMetaField>(Arc::from("igMemoryRefMetaField"), |ark_field, imm, _metafield_registry, platform| {
         let raw_internal_metafield = &ark_field.ark_info.read().unwrap().clone().ig_memory_ref_info.unwrap();
         // TODO: i need a better system for this. so many types here it really is ugly but the oop side of this makes it hard to work through
@@ -48,6 +80,15 @@ fn register_metafields(imm: &mut igMetadataManager) {
         };
         Arc::new(igMemoryRefMetaField(Arc::new(updated_internal_metafield)))
     });
+    imm.meta_field_registry.register_complex::<igVectorMetaField>(Arc::from("igVectorMetaField"), igVectorMetaField::from_field);
+    imm.meta_field_registry.register_complex::<igEnumMetaField>(Arc::from("igEnumMetaField"), igEnumMetaField::from_field);
+    imm.meta_field_registry.register_complex::<igBitFieldMetaField>(Arc::from("igBitFieldMetaField"), igBitFieldMetaField::from_field);
+
+    // Every fixed size array type works the same way, only the elements differ
+    let array_types: Vec<Arc<str>> = imm.meta_field_names().filter(|x| x.ends_with("ArrayMetaField")).cloned().collect();
+    for array_type in array_types {
+        imm.meta_field_registry.register_complex::<igArrayMetaField>(array_type, igArrayMetaField::from_field);
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize)]
@@ -1,9 +1,10 @@
-/// Custom implementations of objects to make usage more ergonomic. Currently just focused around objects using igDataList
+/// Custom implementations of objects to make usage more ergonomic. Currently just focused around objects using igDataList and igHashTable
 use crate::core::ig_archive::igArchive;
 use crate::core::ig_memory::igMemoryPool;
 use crate::core::ig_objects::{igAny, igObject, igObjectDirectory, ObjectExt};
-use crate::core::memory::igMemory;
-use crate::core::meta::ig_metadata_manager::{__internalObjectBase, igMetaInstantiationError, igMetaObject, igMetadataManager, FieldDoesntExist, SetObjectFieldError};
+use crate::core::memory::{igMemory, igNullElement};
+use crate::core::meta::ig_metadata_manager::{__internalObjectBase, igConstructedField, igMetaInstantiationError, igMetaObject, igMetadataManager, FieldDoesntExist, SetObjectFieldError};
+use crate::core::meta::ig_xml_metadata::HashTableInfo;
 use crate::util::ig_name::igName;
 use log::{error, warn};
 use std::any::Any;
@@ -148,6 +149,10 @@ impl<T: Send + Sync + 'static + Clone> __internalObjectBase for igDataList<T> {
                     let mut data_writer = self.list.write().unwrap();
                     for value in memory.data.iter() {
                         let ig_any = value.read().unwrap();
+                        if ig_any.is::<igNullElement>() {
+                            warn!("{} has a null element, which igDataList<T> can't store. It was skipped", self.object_name);
+                            continue;
+                        }
                         let correct_type_val= ig_any.downcast_ref::<T>().expect("igMemory generic does not match _data. TODO: generate these with macros and have an error message that says what the generic is");
                         data_writer.push(correct_type_val.clone());
                     }
@@ -169,16 +174,28 @@ impl<T: Send + Sync + 'static + Clone> __internalObjectBase for igDataList<T> {
     }
 
     #[inline]
-    fn get_non_null_field(&self, _name: &str) -> Result<igAny, FieldDoesntExist> {
-        todo!()
+    fn get_non_null_field(&self, name: &str) -> Result<igAny, FieldDoesntExist> {
+        self.get_field(name)?.ok_or(FieldDoesntExist)
     }
 
     #[inline]
     fn get_field(
         &self,
-        _name: &str,
+        name: &str,
     ) -> Result<Option<Arc<RwLock<(dyn Any + Send + Sync + 'static)>>>, FieldDoesntExist> {
-        todo!()
+        match name {
+            "_data" => {
+                let mut memory: igMemory<igAny> = igMemory::new();
+                memory.pool = self.pool;
+                memory.data = self.list.read().unwrap().iter().map(|value| {
+                    let value: igAny = Arc::new(RwLock::new(value.clone()));
+                    value
+                }).collect();
+                Ok(Some(Arc::new(RwLock::new(memory))))
+            }
+            "_count" | "_capacity" => Ok(Some(Arc::new(RwLock::new(self.len() as i32)))),
+            &_ => Err(FieldDoesntExist),
+        }
     }
 
     fn as_any(&self) -> &(dyn Any + Send + Sync) {
@@ -324,3 +341,189 @@ impl<'a, T: Clone> Iterator for QueryGuardIter<'a, T> {
         }
     }
 }
+
+/// An igHashTable or any type extending it. The keys and values are two memory blocks of the same length where every index is a slot of the table, and a slot is empty when its key is the invalid key of the table.
+/// Every slot is kept so the table is saved laid out the way the game hashed it, while the map functions only see the filled slots
+pub struct igHashTable {
+    object_name: Arc<str>,
+    pool: igMemoryPool,
+    hash_table_info: Option<HashTableInfo>,
+    keys: Vec<igAny>,
+    values: Vec<igAny>,
+    /// The item count the table was read with. [None] until one is set, in which case it's worked out from the filled slots
+    hash_item_count: Option<i32>,
+    pub auto_rehash: bool,
+    pub load_factor: f32,
+    /// Fields added by types extending igHashTable, kept the way [igGenericObject](crate::core::meta::ig_metadata_manager::igGenericObject) keeps them
+    other_fields: Vec<igConstructedField>,
+}
+
+/// The fields of igHashTable itself, which are stored as part of the table
+const HASH_TABLE_FIELDS: [&str; 5] = ["_keys", "_values", "_hashItemCount", "_autoRehash", "_loadFactor"];
+
+/// Prints a key the way Alchemy prints the invalid key of a table. [None] when the type isn't one that can be compared this way
+fn print_slot_key(key: &(dyn Any + Send + Sync)) -> Option<String> {
+    macro_rules! print {
+        ($($type:ty),*) => {
+            $(if let Some(key) = key.downcast_ref::<$type>() {
+                return Some(key.to_string());
+            })*
+        };
+    }
+    print!(i8, u8, i16, u16, i32, u32, i64, u64);
+    key.downcast_ref::<f32>().map(|key| format!("{:.8}", key))
+}
+
+impl igHashTable {
+    pub fn construct(
+        meta: &igMetaObject,
+        pool: igMemoryPool,
+    ) -> Result<Arc<RwLock<dyn __internalObjectBase>>, igMetaInstantiationError> {
+        Ok(Arc::new(RwLock::new(igHashTable {
+            object_name: meta.name.clone(),
+            pool,
+            hash_table_info: meta.hash_table_info.clone(),
+            keys: Vec::new(),
+            values: Vec::new(),
+            hash_item_count: None,
+            auto_rehash: true,
+            load_factor: 0.5,
+            other_fields: meta
+                .field_storage
+                .name_lookup
+                .keys()
+                .filter(|name| !HASH_TABLE_FIELDS.contains(&name.as_ref()))
+                .map(|name| igConstructedField { name: name.clone(), value: None })
+                .collect(),
+        })))
+    }
+
+    fn is_filled(&self, key: &igAny) -> bool {
+        let key = key.read().unwrap();
+        if key.is::<igNullElement>() {
+            return false;
+        }
+        match (&self.hash_table_info, print_slot_key(&*key)) {
+            (Some(info), Some(key)) => key != info.invalid_key,
+            _ => true,
+        }
+    }
+
+    /// The amount of filled slots
+    pub fn len(&self) -> usize {
+        self.keys.iter().filter(|key| self.is_filled(key)).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The amount of slots, filled or not
+    pub fn capacity(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Every filled slot as (key, value)
+    pub fn iter(&self) -> impl Iterator<Item = (igAny, igAny)> + '_ {
+        self.keys
+            .iter()
+            .zip(self.values.iter())
+            .filter(|(key, _)| self.is_filled(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    /// Finds the value stored with the key. Keys of a different type than `K` never match
+    pub fn get<K: PartialEq + 'static>(&self, key: &K) -> Option<igAny> {
+        self.iter()
+            .find(|(slot_key, _)| slot_key.read().unwrap().downcast_ref::<K>() == Some(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn contains_key<K: PartialEq + 'static>(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Swaps the value stored with a key already in the table, returning the old one. New keys can't be added as the slot they belong in depends on the hash function of the game
+    pub fn replace<K: PartialEq + 'static>(&mut self, key: &K, value: igAny) -> Option<igAny> {
+        let index = (0..self.keys.len()).find(|&i| {
+            self.is_filled(&self.keys[i]) && self.keys[i].read().unwrap().downcast_ref::<K>() == Some(key)
+        })?;
+        Some(std::mem::replace(&mut self.values[index], value))
+    }
+
+    fn slots_memory(&self, slots: &[igAny]) -> igAny {
+        let mut memory: igMemory<igAny> = igMemory::new();
+        memory.pool = self.pool;
+        memory.data = slots.to_vec();
+        Arc::new(RwLock::new(memory))
+    }
+}
+
+impl __internalObjectBase for igHashTable {
+    fn object_name(&self) -> Arc<str> {
+        self.object_name.clone()
+    }
+
+    fn meta_type(&self, metadata_manager: &mut igMetadataManager) -> Arc<RwLock<igMetaObject>> {
+        metadata_manager.get_or_create_meta(self.object_name.as_ref()).unwrap()
+    }
+
+    fn internal_pool(&self) -> &igMemoryPool {
+        &self.pool
+    }
+
+    fn set_pool(&mut self, pool: igMemoryPool) {
+        self.pool = pool;
+    }
+
+    fn set_field(&mut self, name: &str, value: Option<igAny>) -> Result<(), SetObjectFieldError> {
+        if !HASH_TABLE_FIELDS.contains(&name) {
+            let field = self.other_fields.iter_mut().find(|field| field.name.as_ref() == name).ok_or(SetObjectFieldError::FieldDoesntExist)?;
+            field.value = value;
+            return Ok(());
+        }
+
+        if let Some(value) = value {
+            let guard = value.read().unwrap();
+            match name {
+                "_keys" | "_values" => {
+                    let memory = guard.downcast_ref::<igMemory<igAny>>().ok_or(SetObjectFieldError::InvalidValueType)?;
+                    match name {
+                        "_keys" => self.keys = memory.data.clone(),
+                        _ => self.values = memory.data.clone(),
+                    }
+                }
+                "_hashItemCount" => self.hash_item_count = Some(*guard.downcast_ref::<i32>().ok_or(SetObjectFieldError::InvalidValueType)?),
+                "_autoRehash" => self.auto_rehash = *guard.downcast_ref::<bool>().ok_or(SetObjectFieldError::InvalidValueType)?,
+                "_loadFactor" => self.load_factor = *guard.downcast_ref::<f32>().ok_or(SetObjectFieldError::InvalidValueType)?,
+                &_ => unreachable!("every other field is kept in other_fields"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn get_non_null_field(&self, name: &str) -> Result<igAny, FieldDoesntExist> {
+        self.get_field(name)?.ok_or(FieldDoesntExist)
+    }
+
+    fn get_field(&self, name: &str) -> Result<Option<igAny>, FieldDoesntExist> {
+        match name {
+            "_keys" => Ok(Some(self.slots_memory(&self.keys))),
+            "_values" => Ok(Some(self.slots_memory(&self.values))),
+            "_hashItemCount" => Ok(Some(Arc::new(RwLock::new(self.hash_item_count.unwrap_or(self.len() as i32))))),
+            "_autoRehash" => Ok(Some(Arc::new(RwLock::new(self.auto_rehash)))),
+            "_loadFactor" => Ok(Some(Arc::new(RwLock::new(self.load_factor)))),
+            &_ => self.other_fields.iter().find(|field| field.name.as_ref() == name).map(|field| field.value.clone()).ok_or(FieldDoesntExist),
+        }
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut (dyn Any + Send + Sync) {
+        self
+    }
+}
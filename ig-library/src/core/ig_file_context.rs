@@ -1,4 +1,4 @@
-use crate::core::ig_archive::igArchive;
+use crate::core::ig_archive::{igArchive, igArchiveError};
 use crate::core::ig_archive_manager::igArchiveManager;
 use crate::core::ig_archive_mount_manager::igArchiveMountManager;
 use crate::core::ig_file_context::WorkItemBuffer::Invalid;
@@ -38,6 +38,14 @@ static VIRTUAL_DEVICES: phf::Map<&'static str, &'static str> = phf_map! {
     "vfx"               => "vfx",
 };
 
+/// Flag for [igFileContext::open]. Asks the storage device to memory map the file instead of reading the whole thing in to memory.
+/// Devices that can't do this will read the file like normal, so both _handle and _mapped need to be checked.
+pub const OPEN_FLAG_MEMORY_MAP: u32 = 0x1000_0000;
+
+/// Flag for [igFileContext::open]. Tells the storage device only the start of the file will be read, so a device that has to decompress files can stop after the first block.
+/// _size is still the size of the whole file. Devices that don't need this will open the whole file
+pub const OPEN_FLAG_HEADER_ONLY: u32 = 0x2000_0000;
+
 /// File context stores information related to the currently loaded game instance. It cannot be shared between instances like most other types. It stores all file processors and the [igArchiveManager]
 pub struct igFileContext {
     pub _root: String,
@@ -133,6 +141,7 @@ impl igFileContext {
                 _size: 0,
                 _device: None,
                 _handle: None,
+                _mapped: None,
                 _flags: 0,
                 _work_item_active_count: 0,
                 endianness: Endian::Unknown,
@@ -161,7 +170,11 @@ impl igFileContext {
         work_item._file
     }
 
-    pub fn load_archive(&self, ig_registry: &igRegistry, path: &str) -> Arc<igArchive> {
+    pub fn load_archive(
+        &self,
+        ig_registry: &igRegistry,
+        path: &str,
+    ) -> Result<Arc<igArchive>, igArchiveError> {
         igArchiveManager::load_archive(self.archive_manager.clone(), self, ig_registry, path)
     }
 
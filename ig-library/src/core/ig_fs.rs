@@ -1,5 +1,6 @@
 use crate::core::ig_file_context::{igFileWorkItem, WorkType};
 use log::error;
+use memmap2::Mmap;
 use std::io::Cursor;
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -11,6 +12,8 @@ pub struct igFileDescriptor {
     pub _device: Option<Arc<Mutex<dyn igFileWorkItemProcessor>>>,
     // Sacrificing memory for simplicity. Could be using a Cursor<File> here. Tried union but looks like a mess. need a good rust solution here...
     pub _handle: Option<Cursor<Vec<u8>>>,
+    /// Set instead of _handle when the file was opened with [crate::core::ig_file_context::OPEN_FLAG_MEMORY_MAP] and the device supports it
+    pub _mapped: Option<Mmap>,
     pub _flags: u32,
     pub _work_item_active_count: i32,
     /// Exists only as a utility for reading. Does not exist in VV Alchemy
@@ -33,6 +36,7 @@ impl igFileDescriptor {
             _size: 0,
             _device: None,
             _handle: None,
+            _mapped: None,
             _flags: 0,
             _work_item_active_count: 0,
             endianness: Endian::Little,
@@ -46,6 +50,7 @@ impl igFileDescriptor {
             _size: 0,
             _device: None,
             _handle: Some(data),
+            _mapped: None,
             _flags: 0,
             _work_item_active_count: 0,
             endianness,
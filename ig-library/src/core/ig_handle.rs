@@ -51,8 +51,8 @@ impl igHandle {
                     }
                 }
             }
-            
-            None
+
+            self.object.clone()
         } else {
             error!(
             "get_object_alias failed to load {}.{}",
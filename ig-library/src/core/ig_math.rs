@@ -0,0 +1,55 @@
+/// Value of an igVec2fMetaField
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct igVec2f {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Value of an igVec3fMetaField or igVec3fAlignedMetaField. The aligned variant is padded to 16 bytes when stored
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct igVec3f {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Value of an igVec4fMetaField
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct igVec4f {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Value of an igQuaternionfMetaField
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct igQuaternionf {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Value of an igMatrix44fMetaField. Stored row by row
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct igMatrix44f {
+    pub m: [[f32; 4]; 4],
+}
+
+impl igMatrix44f {
+    pub fn identity() -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        igMatrix44f { m }
+    }
+}
+
+/// Value of an igRandomMetaField. The state of a random number generator, kept as the two 32-bit words it is stored as
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct igRandom {
+    pub seed: u32,
+    pub state: u32,
+}
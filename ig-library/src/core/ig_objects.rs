@@ -3,12 +3,11 @@ use crate::core::ig_external_ref::igExternalReferenceSystem;
 use crate::core::ig_file_context::{get_native_path, igFileContext};
 use crate::core::ig_registry::igRegistry;
 use crate::core::load::ig_igz_loader::igIGZObjectLoader;
-use crate::core::load::ig_loader;
-use crate::core::load::ig_loader::igObjectLoader;
+use crate::core::load::ig_loader::{igObjectLoader, igObjectLoaderRegistry, LoadReport, LoaderError, LoaderMatch};
 use crate::core::meta::ig_metadata_manager::{__internalObjectBase, igMetadataManager};
 use crate::util::ig_hash::hash_lower;
 use crate::util::ig_name::igName;
-use log::warn;
+use log::{info, warn};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::mem;
@@ -60,6 +59,10 @@ pub struct igObjectDirectory {
     /// Only filled when use_name_list is equal to true and length should match the object list
     pub name_list: Arc<RwLock<igNameList>>,
     pub loader: Arc<RwLock<dyn igObjectLoader>>,
+    /// Problems skipped over while loading the directory. Only lenient loading (see [igObjectStreamManager::lenient]) will skip over broken objects
+    pub load_report: LoadReport,
+    /// What made the [igObjectLoaderRegistry] pick the loader. [None] when no loader could be found for the file, or the directory wasn't loaded from one
+    pub loader_match: Option<LoaderMatch>,
 }
 
 impl igObjectDirectory {
@@ -77,6 +80,8 @@ impl igObjectDirectory {
             object_list: Arc::new(RwLock::new(igObjectList::new())),
             name_list: Arc::new(RwLock::new(igNameList::new())),
             loader,
+            load_report: LoadReport::default(),
+            loader_match: None,
         }
     }
 }
@@ -84,13 +89,23 @@ impl igObjectDirectory {
 pub struct igObjectStreamManager {
     pub name_to_directory_lookup: HashMap<u32, igObjectDirectoryList>,
     pub path_to_directory_lookup: HashMap<u32, Arc<RwLock<igObjectDirectory>>>,
+    /// When set, objects that fail to load are replaced by igNull and reported in [igObjectDirectory::load_report] instead of failing the whole directory. Problems with the file itself will still fail it
+    pub lenient: bool,
+    /// Loaders files are matched against. Shared with [igAlchemy](crate::util::ig_common::igAlchemy) so loaders registered there are used here
+    pub loaders: Arc<RwLock<igObjectLoaderRegistry>>,
 }
 
 impl igObjectStreamManager {
     pub fn new() -> igObjectStreamManager {
+        Self::with_loaders(Arc::new(RwLock::new(igObjectLoaderRegistry::new())))
+    }
+
+    pub fn with_loaders(loaders: Arc<RwLock<igObjectLoaderRegistry>>) -> igObjectStreamManager {
         igObjectStreamManager {
             name_to_directory_lookup: HashMap::new(),
             path_to_directory_lookup: HashMap::new(),
+            lenient: false,
+            loaders,
         }
     }
 
@@ -101,7 +116,7 @@ impl igObjectStreamManager {
         ig_metadata_manager: &mut igMetadataManager,
         ig_ext_ref_system: &mut igExternalReferenceSystem,
         path: String,
-    ) -> Result<Arc<RwLock<igObjectDirectory>>, String> {
+    ) -> Result<Arc<RwLock<igObjectDirectory>>, LoaderError> {
         self.load_with_namespace(
             ig_file_context,
             ig_registry,
@@ -120,20 +135,28 @@ impl igObjectStreamManager {
         ig_ext_ref_system: &mut igExternalReferenceSystem,
         path: String,
         namespace: igName,
-    ) -> Result<Arc<RwLock<igObjectDirectory>>, String> {
+    ) -> Result<Arc<RwLock<igObjectDirectory>>, LoaderError> {
         let file_path = get_native_path(path);
         let file_path_hash = hash_lower(&file_path);
 
         if self.path_to_directory_lookup.contains_key(&file_path_hash) {
             Ok(self.path_to_directory_lookup[&file_path_hash].clone())
         } else {
-            let dir = Arc::new(RwLock::new(igObjectDirectory::new(&file_path, namespace)));
+            let choice = self.loaders.read().unwrap().detect(ig_file_context, ig_registry, &file_path);
+            let dir = match &choice {
+                Some(choice) => igObjectDirectory {
+                    loader_match: Some(choice.matched_by),
+                    ..igObjectDirectory::with_loader(&file_path, namespace, choice.loader.clone())
+                },
+                None => igObjectDirectory::new(&file_path, namespace),
+            };
+            let dir = Arc::new(RwLock::new(dir));
             self.push_dir(dir.clone());
-            let loader_result = ig_loader::get_loader(&file_path);
-            if let Some(loader) = loader_result {
-                let loader_guard = loader.read().unwrap();
+            if let Some(choice) = choice {
+                let loader_guard = choice.loader.read().unwrap();
+                info!("Loading {} with the {} loader (matched by {:?})", file_path, loader_guard.get_name(), choice.matched_by);
                 let mut dir_guard = dir.write().unwrap();
-                loader_guard.read_file(
+                let result = loader_guard.read_file(
                     ig_file_context,
                     ig_registry,
                     self,
@@ -142,6 +165,13 @@ impl igObjectStreamManager {
                     &mut dir_guard,
                     &file_path,
                 );
+                drop(dir_guard);
+
+                if let Err(e) = result {
+                    // Don't hand out the half loaded directory to anyone asking for it later
+                    self.remove_dir(&dir);
+                    return Err(e);
+                }
                 // todo!("igObjectHandleManager.Singleton.AddDirectory(objDir);");
             } else {
                 warn!("No loader found for file {}", file_path);
@@ -165,4 +195,15 @@ impl igObjectStreamManager {
         self.path_to_directory_lookup
             .insert(hash_lower(&file_path), dir);
     }
+
+    fn remove_dir(&mut self, dir: &Arc<RwLock<igObjectDirectory>>) {
+        let hash = dir.read().unwrap().name.hash;
+        let file_path = dir.read().unwrap().path.clone();
+
+        if let Some(list) = self.name_to_directory_lookup.get(&hash) {
+            list.list.write().unwrap().retain(|x| !Arc::ptr_eq(x, dir));
+        }
+
+        self.path_to_directory_lookup.remove(&hash_lower(&file_path));
+    }
 }
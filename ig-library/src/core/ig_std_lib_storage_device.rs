@@ -1,7 +1,8 @@
 use crate::core::ig_file_context::WorkStatus::*;
-use crate::core::ig_file_context::{igFileWorkItem, WorkItemBuffer};
+use crate::core::ig_file_context::{igFileWorkItem, WorkItemBuffer, OPEN_FLAG_MEMORY_MAP};
 use crate::core::ig_fs::{igFileWorkItemProcessor, igStorageDevice};
 use log::error;
+use memmap2::Mmap;
 use std::fs;
 use std::fs::File;
 use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
@@ -85,8 +86,22 @@ impl igStorageDevice for igStdLibStorageDevice {
         if let Ok(Some(path)) = find_case_insensitive_path(path_buf) {
             let result = File::open(path);
             if result.is_ok() {
+                let mut file = result.unwrap();
+                if work_item.flags & OPEN_FLAG_MEMORY_MAP != 0 {
+                    // SAFETY: the mapping is only valid while nothing writes to or truncates the file. Only igArchive keeps a mapping around, and it copies
+                    // itself out of the mapping in igArchive::save_to before saving over its own file. Another program changing the file while it's mapped
+                    // can't be guarded against and is undefined behaviour, so game files must not be edited outside of ig-library while they are open
+                    if let Ok(mapped) = unsafe { Mmap::map(&file) } {
+                        work_item._file._size = mapped.len() as u64;
+                        work_item._file._device = Some(this);
+                        work_item._file._mapped = Some(mapped);
+                        work_item._status = kStatusComplete;
+                        return;
+                    }
+                }
+
                 let mut buffer = Vec::new();
-                result.unwrap().read_to_end(&mut buffer).unwrap();
+                file.read_to_end(&mut buffer).unwrap();
 
                 work_item._file._device = Some(this);
                 work_item._file._handle = Some(Cursor::new(buffer));
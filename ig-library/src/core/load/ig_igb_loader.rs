@@ -1 +1,742 @@
-pub struct IgbLoaderContext;
\ No newline at end of file
+use crate::core::ig_custom::{igNull, igObjectList, CastTo};
+use crate::core::ig_external_ref::igExternalReferenceSystem;
+use crate::core::ig_file_context::igFileContext;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_memory::igMemoryPool;
+use crate::core::ig_objects::{igObject, igObjectDirectory, igObjectStreamManager};
+use crate::core::ig_registry::igRegistry;
+use crate::core::load::ig_loader::{igObjectLoader, LoaderError};
+use crate::core::meta::ig_metadata_manager::{igMetaInstantiationError, igMetadataManager, SetObjectFieldError};
+use crate::util::byteorder_fixes::{read_struct_array_u16, read_struct_array_u32, read_struct_array_u8, read_u32};
+use crate::util::ig_name::igName;
+use log::{debug, error, warn};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Cursor;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+/// Value of [IgbHeader::magic_cookie] when read in the endian of the file
+const IGB_MAGIC_COOKIE: u32 = 0xFADA;
+/// Size of the header. Twelve u32s
+const IGB_HEADER_SIZE: u64 = 0x30;
+/// Ref index stored for a "null" object or memory
+pub(crate) const IGB_NULL_INDEX: u32 = 0xFFFFFFFF;
+
+pub struct igIGBObjectLoader;
+
+/// Describes why an igb failed to load and where in the file it happened
+#[derive(Debug)]
+pub struct IgbLoaderError {
+    /// Path of the igb that failed to load
+    pub path: String,
+    /// The part of the igb that was being read
+    pub location: IgbLoaderLocation,
+    /// Position in the file where the failure was noticed
+    pub offset: u64,
+    /// What went wrong
+    pub kind: IgbLoaderErrorKind,
+}
+
+impl Display for IgbLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}, offset {:#x}): {}", self.path, self.location, self.offset, self.kind)
+    }
+}
+
+impl std::error::Error for IgbLoaderError {}
+
+/// The parts of an igb a [IgbLoaderError] can come from
+#[derive(Debug, Clone)]
+pub enum IgbLoaderLocation {
+    /// The igb header
+    Header,
+    /// One of the tables between the header and the objects, named by what it lists
+    Table(&'static str),
+    /// The object with the ref index given
+    Object(u32),
+    /// A field of the object with the ref index given
+    Field { object: u32, field: Arc<str> },
+}
+
+impl Display for IgbLoaderLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgbLoaderLocation::Header => write!(f, "header"),
+            IgbLoaderLocation::Table(name) => write!(f, "{} table", name),
+            IgbLoaderLocation::Object(index) => write!(f, "object {}", index),
+            IgbLoaderLocation::Field { object, field } => write!(f, "field {} of object {}", field, object),
+        }
+    }
+}
+
+/// Describes everything that can go wrong while reading an igb
+#[derive(Debug)]
+pub enum IgbLoaderErrorKind {
+    /// Returned when the file could not be opened
+    FileUnreadable,
+    /// Returned when the magic cookie isn't 0xFADA in either endian
+    InvalidMagic(u32),
+    /// Returned when an index points past the end of the list it indexes
+    InvalidIndex { list: &'static str, index: u64 },
+    /// Returned when an object's type has no metadata
+    UnknownType(String),
+    /// Returned when a directory entry's type is not one the loader knows how to read
+    UnknownEntry(String),
+    /// Returned when a ref index points to an entry of the wrong kind, such as an object field pointing at memory
+    InvalidRef { expected: &'static str, index: u32 },
+    /// Returned when the igb lists a different metafield for a field than the metadata does. The fields after it can't be read as their size isn't known
+    FieldMismatch { expected: Arc<str>, found: String },
+    /// Returned when a memory pool name isn't one of [igMemoryPool]
+    InvalidMemoryPool(String),
+    /// Returned when an object could not be created from its metadata
+    InstantiationFailed(igMetaInstantiationError),
+    /// Returned when an object refused a value read for one of its fields
+    SetField(SetObjectFieldError),
+    /// Returned when reading from the file failed, usually because it ended early
+    Io(std::io::Error),
+}
+
+impl Display for IgbLoaderErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgbLoaderErrorKind::FileUnreadable => write!(f, "file could not be read"),
+            IgbLoaderErrorKind::InvalidMagic(magic) => write!(f, "invalid magic cookie {:#x}", magic),
+            IgbLoaderErrorKind::InvalidIndex { list, index } => write!(f, "index {} is out of bounds for the {}", index, list),
+            IgbLoaderErrorKind::UnknownType(name) => write!(f, "no metadata exists for the type {}", name),
+            IgbLoaderErrorKind::UnknownEntry(name) => write!(f, "directory entries of type {} can't be read", name),
+            IgbLoaderErrorKind::InvalidRef { expected, index } => write!(f, "ref {} does not point to {}", index, expected),
+            IgbLoaderErrorKind::FieldMismatch { expected, found } => write!(f, "expected a field of type {} but the igb has {}", expected, found),
+            IgbLoaderErrorKind::InvalidMemoryPool(name) => write!(f, "invalid memory pool name '{}'", name),
+            IgbLoaderErrorKind::InstantiationFailed(e) => write!(f, "failed to instantiate object: {:?}", e),
+            IgbLoaderErrorKind::SetField(e) => write!(f, "failed to set field: {:?}", e),
+            IgbLoaderErrorKind::Io(e) => write!(f, "failed to read igb: {}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for IgbLoaderErrorKind {
+    fn from(value: std::io::Error) -> Self {
+        IgbLoaderErrorKind::Io(value)
+    }
+}
+
+/// The fixed size header at the start of every igb. Each table after it is described by its size in bytes and the amount of records in it
+#[derive(Debug, Clone)]
+pub struct IgbHeader {
+    pub entry_buffer_size: u32,
+    pub entry_count: u32,
+    pub meta_object_buffer_size: u32,
+    pub meta_object_count: u32,
+    pub object_buffer_size: u32,
+    pub object_count: u32,
+    pub memory_buffer_size: u32,
+    pub memory_count: u32,
+    pub meta_field_buffer_size: u32,
+    pub meta_field_count: u32,
+    pub magic_cookie: u32,
+    /// The version is in the low 16 bits, the flags below are in the high bits
+    pub version: u32,
+}
+
+impl IgbHeader {
+    /// Set when the igb ends its tables with the ref index of the igInfoList holding every root object
+    pub const HAS_INFO: u32 = 0x80000000;
+    /// Set when the igb references objects in other igb files and lists those files after the meta fields
+    pub const HAS_EXTERNAL: u32 = 0x40000000;
+    /// Set when objects of the same type share a directory entry
+    pub const SHARED_ENTRIES: u32 = 0x20000000;
+    /// Set when the igb lists memory pool names after the meta fields and memory entries name the pool they are allocated from
+    pub const HAS_MEMORY_POOL_NAMES: u32 = 0x10000000;
+
+    pub fn version(&self) -> u32 {
+        self.version & 0xFFFF
+    }
+
+    pub fn has_flag(&self, flag: u32) -> bool {
+        self.version & flag != 0
+    }
+}
+
+/// A meta field as listed in the igb. Versions are of the metafield when the igb was written
+#[derive(Debug, Clone)]
+pub struct IgbMetaField {
+    pub name: String,
+    pub major_version: u32,
+    pub minor_version: u32,
+}
+
+/// A field of a meta object as listed in the igb
+#[derive(Debug, Clone)]
+pub struct IgbMetaObjectField {
+    /// Index into [IgbLoaderContext::meta_fields]
+    pub meta_field: u16,
+    /// Fields are written in the order of their slot
+    pub slot: u16,
+    pub size: u16,
+}
+
+/// A meta object as listed in the igb. Only the fields the type had when the igb was written are listed, inherited ones included
+#[derive(Debug, Clone)]
+pub struct IgbMetaObject {
+    pub name: String,
+    pub major_version: u32,
+    pub minor_version: u32,
+    pub fields: Vec<IgbMetaObjectField>,
+}
+
+/// An entry of the igb's directory. Every object and memory block in the igb is described by one
+#[derive(Debug, Clone)]
+pub enum IgbEntry {
+    /// igObjectDirEntry. An object of the meta object at the index given
+    Object { meta_object: u32 },
+    /// igMemoryDirEntry. A block of memory of the size given. The element type is an index into the meta fields, or [None] for raw bytes
+    Memory { size: u32, element: Option<u32>, pool: igMemoryPool },
+    /// igExternalIndexedEntry. The object at the index given in the object list of an external igb
+    External { directory: u32, index: u32 },
+}
+
+/// Where the data of a memory ref lives in the igb
+#[derive(Debug, Clone)]
+pub struct IgbMemoryBlock {
+    pub offset: u64,
+    pub size: u32,
+    /// Index into [IgbLoaderContext::meta_fields], or [None] for raw bytes
+    pub element: Option<u32>,
+    pub pool: igMemoryPool,
+}
+
+/// Internal type to store while jumping around to other methods. Also shared with loading metafields
+pub struct IgbLoaderContext {
+    pub header: IgbHeader,
+    /// endianness of the igb, worked out from the magic cookie
+    pub endian: Endian,
+    pub meta_fields: Vec<IgbMetaField>,
+    pub meta_objects: Vec<IgbMetaObject>,
+    /// Names of the memory pools memory entries can be allocated from
+    pub memory_pools: Vec<String>,
+    /// Paths of the igb files external entries point into
+    pub external_directories: Vec<String>,
+    pub entries: Vec<IgbEntry>,
+    /// What every ref index used by fields points to, as an index into [IgbLoaderContext::entries]
+    pub refs: Vec<u32>,
+    /// Every object in the igb by its ref index
+    pub objects: HashMap<u32, igObject>,
+    /// Every block of memory in the igb by its ref index
+    pub memory: HashMap<u32, IgbMemoryBlock>,
+    /// The loaded igb files external entries point into, in the order of [IgbLoaderContext::external_directories]
+    pub dependencies: Vec<Option<Arc<RwLock<igObjectDirectory>>>>,
+    /// Path of the igb being loaded
+    pub path: String,
+    /// The part of the igb currently being read. Used to say where an [IgbLoaderError] came from
+    pub location: IgbLoaderLocation,
+    /// When set, objects and fields that fail to load are replaced by igNull and put in [IgbLoaderContext::warnings] instead of failing the igb
+    pub lenient: bool,
+    /// Problems skipped over so far. Becomes the [LoadReport](crate::core::load::ig_loader::LoadReport) of the directory
+    pub warnings: Vec<LoaderError>,
+}
+
+impl IgbLoaderContext {
+    /// Returns the object a ref index points to. Objects in external igb files are looked up in the object list of the loaded file
+    pub fn get_object(&self, index: u32) -> Result<igObject, IgbLoaderErrorKind> {
+        if let Some(object) = self.objects.get(&index) {
+            return Ok(object.clone());
+        }
+
+        match self.get_entry(index)? {
+            IgbEntry::External { directory, index: object_index } => {
+                let dependency = self.dependencies
+                    .get(*directory as usize)
+                    .ok_or(IgbLoaderErrorKind::InvalidIndex { list: "external directory list", index: *directory as u64 })?
+                    .as_ref()
+                    .ok_or(IgbLoaderErrorKind::InvalidRef { expected: "a loaded external igb", index })?;
+                let object = dependency.read().unwrap().object_list.read().unwrap().get(*object_index as usize);
+                object.ok_or(IgbLoaderErrorKind::InvalidIndex { list: "external object list", index: *object_index as u64 })
+            }
+            _ => Err(IgbLoaderErrorKind::InvalidRef { expected: "an object", index }),
+        }
+    }
+
+    /// Returns where the memory a ref index points to can be found
+    pub fn get_memory(&self, index: u32) -> Result<IgbMemoryBlock, IgbLoaderErrorKind> {
+        match self.memory.get(&index) {
+            Some(memory) => Ok(memory.clone()),
+            None => {
+                self.get_entry(index)?;
+                Err(IgbLoaderErrorKind::InvalidRef { expected: "memory", index })
+            }
+        }
+    }
+
+    fn get_entry(&self, index: u32) -> Result<&IgbEntry, IgbLoaderErrorKind> {
+        let entry = *self.refs.get(index as usize).ok_or(IgbLoaderErrorKind::InvalidIndex { list: "ref list", index: index as u64 })?;
+        self.entries.get(entry as usize).ok_or(IgbLoaderErrorKind::InvalidIndex { list: "entry list", index: entry as u64 })
+    }
+
+    /// Builds an [IgbLoaderError] for the part of the igb currently being read
+    pub fn error(&self, offset: u64, kind: IgbLoaderErrorKind) -> IgbLoaderError {
+        IgbLoaderError {
+            path: self.path.clone(),
+            location: self.location.clone(),
+            offset,
+            kind,
+        }
+    }
+
+    fn io_error(&self, handle: &Cursor<Vec<u8>>, error: std::io::Error) -> IgbLoaderError {
+        self.error(handle.position(), IgbLoaderErrorKind::Io(error))
+    }
+
+    /// Records a problem that was skipped over while loading leniently
+    pub fn warn(&mut self, error: IgbLoaderError) {
+        warn!("Skipping over a broken part of an igb: {}", error);
+        self.warnings.push(LoaderError::Igb(error));
+    }
+}
+
+impl igObjectLoader for igIGBObjectLoader {
+    fn can_read(&self, file_name: &str) -> bool {
+        file_name.ends_with(".igb")
+    }
+
+    fn can_read_magic(&self, header: &[u8]) -> bool {
+        match header.get(0x28..0x2C) {
+            Some(magic) => {
+                let magic = u32::from_le_bytes(magic.try_into().unwrap());
+                magic == IGB_MAGIC_COOKIE || magic.swap_bytes() == IGB_MAGIC_COOKIE
+            }
+            None => false,
+        }
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Alchemy Binary"
+    }
+
+    fn get_type(&self) -> &'static str {
+        "Alchemy"
+    }
+
+    fn read_file(
+        &self,
+        ig_file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        ig_object_stream_manager: &mut igObjectStreamManager,
+        ig_ext_ref_system: &mut igExternalReferenceSystem,
+        ig_metadata_manager: &mut igMetadataManager,
+        dir: &mut igObjectDirectory,
+        file_path: &str,
+    ) -> Result<(), LoaderError> {
+        let lenient = ig_object_stream_manager.lenient;
+        igIGBLoader::read(
+            ig_file_context,
+            ig_registry,
+            ig_object_stream_manager,
+            ig_ext_ref_system,
+            ig_metadata_manager,
+            dir,
+            file_path,
+            lenient,
+        )?;
+        Ok(())
+    }
+}
+
+/// Reads the legacy binary format used before igz, laid out as
+/// - the [IgbHeader]
+/// - the meta field table. A (name length, major version, minor version) record per meta field followed by the names
+/// - the memory pool names when [IgbHeader::HAS_MEMORY_POOL_NAMES] is set and the external directories when [IgbHeader::HAS_EXTERNAL] is set. Both are a size, a count, a name length per name and the names
+/// - the meta object table. A (name length, major version, minor version, field count) record per meta object, each followed by a (meta field, slot, size) record per field, followed by the names
+/// - the entry table. A (meta object, size) record per entry followed by what the entry describes
+/// - the ref list. A count followed by the entry each ref index points to
+/// - the ref index of the igInfoList when [IgbHeader::HAS_INFO] is set
+/// - the objects, each being its size followed by its fields in slot order
+/// - the memory, each block directly following the last
+pub struct igIGBLoader;
+
+impl igIGBLoader {
+    #[allow(clippy::too_many_arguments)]
+    fn read(
+        ig_file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        ig_object_stream_manager: &mut igObjectStreamManager,
+        ig_ext_ref_system: &mut igExternalReferenceSystem,
+        imm: &mut igMetadataManager,
+        dir: &mut igObjectDirectory,
+        file_path: &str,
+        lenient: bool,
+    ) -> Result<(), IgbLoaderError> {
+        let fd = ig_file_context.open(ig_registry, file_path, 0);
+        let Some(mut handle) = fd._handle else {
+            error!("Failed to load igb {}. File could not be read.", file_path);
+            return Err(IgbLoaderError {
+                path: file_path.to_string(),
+                location: IgbLoaderLocation::Header,
+                offset: 0,
+                kind: IgbLoaderErrorKind::FileUnreadable,
+            });
+        };
+
+        let mut ctx = igIGBLoader::read_header(&mut handle, file_path)?;
+        ctx.lenient = lenient;
+        debug!("igb version {} with flags {:#x}", ctx.header.version(), ctx.header.version & 0xFFFF0000);
+
+        igIGBLoader::read_meta_fields(&mut handle, &mut ctx)?;
+        if ctx.header.has_flag(IgbHeader::HAS_MEMORY_POOL_NAMES) {
+            ctx.location = IgbLoaderLocation::Table("memory pool");
+            ctx.memory_pools = read_name_table(&mut handle, ctx.endian.clone()).map_err(|e| ctx.io_error(&handle, e))?;
+        }
+        if ctx.header.has_flag(IgbHeader::HAS_EXTERNAL) {
+            ctx.location = IgbLoaderLocation::Table("external directory");
+            ctx.external_directories = read_name_table(&mut handle, ctx.endian.clone()).map_err(|e| ctx.io_error(&handle, e))?;
+            igIGBLoader::load_external_directories(ig_file_context, ig_registry, ig_object_stream_manager, ig_ext_ref_system, imm, dir, &mut ctx);
+        }
+        igIGBLoader::read_meta_objects(&mut handle, &mut ctx)?;
+        igIGBLoader::read_entries(&mut handle, &mut ctx)?;
+        igIGBLoader::read_refs(&mut handle, &mut ctx)?;
+
+        let info = if ctx.header.has_flag(IgbHeader::HAS_INFO) {
+            Some(read_u32(&mut handle, ctx.endian.clone()).map_err(|e| ctx.io_error(&handle, e))?)
+        } else {
+            None
+        };
+
+        let object_start = handle.position();
+        let memory_start = object_start + ctx.header.object_buffer_size as u64;
+        igIGBLoader::layout_memory(&mut ctx, memory_start);
+        igIGBLoader::instantiate_objects(imm, &handle, &mut ctx)?;
+        igIGBLoader::read_objects(imm, ig_object_stream_manager, &mut handle, object_start, &mut ctx)?;
+
+        ctx.location = IgbLoaderLocation::Header;
+        let root = match info {
+            Some(index) if index != IGB_NULL_INDEX => Some(ctx.get_object(index).map_err(|kind| ctx.error(handle.position(), kind))?),
+            _ => None,
+        };
+        let root_list: Option<Result<Arc<RwLock<igObjectList>>, _>> = root.clone().map(|root| root.cast_to());
+        dir.object_list = match root_list {
+            Some(Ok(object_list)) => object_list,
+            _ => {
+                // Without an igInfoList to use, every object in the igb is treated as a root object
+                let object_list = igObjectList::new();
+                match root {
+                    Some(root) => object_list.push(root),
+                    None => {
+                        let mut objects: Vec<_> = ctx.objects.iter().collect();
+                        objects.sort_by_key(|(index, _)| **index);
+                        for (_, object) in objects {
+                            object_list.push(object.clone());
+                        }
+                    }
+                }
+                Arc::new(RwLock::new(object_list))
+            }
+        };
+
+        dir.load_report.warnings.append(&mut ctx.warnings);
+        Ok(())
+    }
+
+    fn read_header(handle: &mut Cursor<Vec<u8>>, file_path: &str) -> Result<IgbLoaderContext, IgbLoaderError> {
+        let error = |handle: &Cursor<Vec<u8>>, kind: IgbLoaderErrorKind| IgbLoaderError {
+            path: file_path.to_string(),
+            location: IgbLoaderLocation::Header,
+            offset: handle.position(),
+            kind,
+        };
+
+        handle.set_position(0x28);
+        let magic_cookie = read_u32(handle, Endian::Little).map_err(|e| error(handle, e.into()))?;
+        let endian = if magic_cookie == IGB_MAGIC_COOKIE {
+            Endian::Little
+        } else if magic_cookie.swap_bytes() == IGB_MAGIC_COOKIE {
+            Endian::Big
+        } else {
+            return Err(error(handle, IgbLoaderErrorKind::InvalidMagic(magic_cookie)));
+        };
+
+        handle.set_position(0);
+        let values = read_struct_array_u32(handle, endian.clone(), 12).map_err(|e| error(handle, e.into()))?;
+        let header = IgbHeader {
+            entry_buffer_size: values[0],
+            entry_count: values[1],
+            meta_object_buffer_size: values[2],
+            meta_object_count: values[3],
+            object_buffer_size: values[4],
+            object_count: values[5],
+            memory_buffer_size: values[6],
+            memory_count: values[7],
+            meta_field_buffer_size: values[8],
+            meta_field_count: values[9],
+            magic_cookie: values[10],
+            version: values[11],
+        };
+        debug_assert_eq!(handle.position(), IGB_HEADER_SIZE);
+
+        Ok(IgbLoaderContext {
+            header,
+            endian,
+            meta_fields: vec![],
+            meta_objects: vec![],
+            memory_pools: vec![],
+            external_directories: vec![],
+            entries: vec![],
+            refs: vec![],
+            objects: HashMap::new(),
+            memory: HashMap::new(),
+            dependencies: vec![],
+            path: file_path.to_string(),
+            location: IgbLoaderLocation::Header,
+            lenient: false,
+            warnings: vec![],
+        })
+    }
+
+    fn read_meta_fields(handle: &mut Cursor<Vec<u8>>, ctx: &mut IgbLoaderContext) -> Result<(), IgbLoaderError> {
+        ctx.location = IgbLoaderLocation::Table("meta field");
+        let start = handle.position();
+        let endian = ctx.endian.clone();
+
+        let mut records = Vec::with_capacity(ctx.header.meta_field_count as usize);
+        for _ in 0..ctx.header.meta_field_count {
+            let record = read_struct_array_u32(handle, endian.clone(), 3).map_err(|e| ctx.io_error(handle, e))?;
+            records.push(record);
+        }
+        for record in records {
+            let name = read_name(handle, record[0]).map_err(|e| ctx.io_error(handle, e))?;
+            ctx.meta_fields.push(IgbMetaField {
+                name,
+                major_version: record[1],
+                minor_version: record[2],
+            });
+        }
+
+        handle.set_position(start + ctx.header.meta_field_buffer_size as u64);
+        Ok(())
+    }
+
+    fn read_meta_objects(handle: &mut Cursor<Vec<u8>>, ctx: &mut IgbLoaderContext) -> Result<(), IgbLoaderError> {
+        ctx.location = IgbLoaderLocation::Table("meta object");
+        let start = handle.position();
+        let endian = ctx.endian.clone();
+
+        let mut records = Vec::with_capacity(ctx.header.meta_object_count as usize);
+        for _ in 0..ctx.header.meta_object_count {
+            let record = read_struct_array_u32(handle, endian.clone(), 4).map_err(|e| ctx.io_error(handle, e))?;
+            let mut fields = Vec::with_capacity(record[3] as usize);
+            for _ in 0..record[3] {
+                let field = read_struct_array_u16(handle, endian.clone(), 3).map_err(|e| ctx.io_error(handle, e))?;
+                if field[0] as usize >= ctx.meta_fields.len() {
+                    return Err(ctx.error(handle.position(), IgbLoaderErrorKind::InvalidIndex { list: "meta field list", index: field[0] as u64 }));
+                }
+                fields.push(IgbMetaObjectField {
+                    meta_field: field[0],
+                    slot: field[1],
+                    size: field[2],
+                });
+            }
+            fields.sort_by_key(|field| field.slot);
+            records.push((record, fields));
+        }
+        for (record, fields) in records {
+            let name = read_name(handle, record[0]).map_err(|e| ctx.io_error(handle, e))?;
+            ctx.meta_objects.push(IgbMetaObject {
+                name,
+                major_version: record[1],
+                minor_version: record[2],
+                fields,
+            });
+        }
+
+        handle.set_position(start + ctx.header.meta_object_buffer_size as u64);
+        Ok(())
+    }
+
+    fn read_entries(handle: &mut Cursor<Vec<u8>>, ctx: &mut IgbLoaderContext) -> Result<(), IgbLoaderError> {
+        ctx.location = IgbLoaderLocation::Table("entry");
+        let start = handle.position();
+        let endian = ctx.endian.clone();
+
+        for _ in 0..ctx.header.entry_count {
+            let entry_start = handle.position();
+            let meta_object = read_u32(handle, endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
+            let size = read_u32(handle, endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
+            let name = ctx.meta_objects
+                .get(meta_object as usize)
+                .ok_or_else(|| ctx.error(entry_start, IgbLoaderErrorKind::InvalidIndex { list: "meta object list", index: meta_object as u64 }))?
+                .name
+                .clone();
+
+            let mut read = || -> std::io::Result<u32> { read_u32(handle, endian.clone()) };
+            let entry = match name.as_str() {
+                "igObjectDirEntry" => IgbEntry::Object {
+                    meta_object: read().map_err(|e| ctx.error(entry_start, e.into()))?,
+                },
+                "igMemoryDirEntry" => {
+                    let size = read().map_err(|e| ctx.error(entry_start, e.into()))?;
+                    let element = read().map_err(|e| ctx.error(entry_start, e.into()))?;
+                    let pool = if ctx.header.has_flag(IgbHeader::HAS_MEMORY_POOL_NAMES) {
+                        let index = read().map_err(|e| ctx.error(entry_start, e.into()))?;
+                        let name = ctx.memory_pools
+                            .get(index as usize)
+                            .ok_or_else(|| ctx.error(entry_start, IgbLoaderErrorKind::InvalidIndex { list: "memory pool list", index: index as u64 }))?;
+                        igMemoryPool::from_str(name).map_err(|_| ctx.error(entry_start, IgbLoaderErrorKind::InvalidMemoryPool(name.clone())))?
+                    } else {
+                        igMemoryPool::Default
+                    };
+                    IgbEntry::Memory {
+                        size,
+                        element: (element != IGB_NULL_INDEX).then_some(element),
+                        pool,
+                    }
+                }
+                "igExternalIndexedEntry" => IgbEntry::External {
+                    directory: read().map_err(|e| ctx.error(entry_start, e.into()))?,
+                    index: read().map_err(|e| ctx.error(entry_start, e.into()))?,
+                },
+                _ => return Err(ctx.error(entry_start, IgbLoaderErrorKind::UnknownEntry(name))),
+            };
+            ctx.entries.push(entry);
+            handle.set_position(entry_start + size as u64);
+        }
+
+        handle.set_position(start + ctx.header.entry_buffer_size as u64);
+        Ok(())
+    }
+
+    fn read_refs(handle: &mut Cursor<Vec<u8>>, ctx: &mut IgbLoaderContext) -> Result<(), IgbLoaderError> {
+        ctx.location = IgbLoaderLocation::Table("ref");
+        let count = read_u32(handle, ctx.endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
+        ctx.refs = read_struct_array_u32(handle, ctx.endian.clone(), count as usize).map_err(|e| ctx.io_error(handle, e))?;
+        if let Some(entry) = ctx.refs.iter().find(|entry| **entry as usize >= ctx.entries.len()) {
+            return Err(ctx.error(handle.position(), IgbLoaderErrorKind::InvalidIndex { list: "entry list", index: *entry as u64 }));
+        }
+        Ok(())
+    }
+
+    /// Loads the igb files external entries point into. A broken dependency only breaks the references into it, so it never fails this file
+    fn load_external_directories(
+        ig_file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        ig_object_stream_manager: &mut igObjectStreamManager,
+        ig_ext_ref_system: &mut igExternalReferenceSystem,
+        imm: &mut igMetadataManager,
+        dir: &mut igObjectDirectory,
+        ctx: &mut IgbLoaderContext,
+    ) {
+        for path in ctx.external_directories.clone() {
+            let name = igName::new(path.clone());
+            match ig_object_stream_manager.load_with_namespace(ig_file_context, ig_registry, imm, ig_ext_ref_system, path.clone(), name) {
+                Ok(dependency) => {
+                    dir.dependencies.push(dependency.clone());
+                    ctx.dependencies.push(Some(dependency));
+                }
+                Err(e) => {
+                    error!("Failed to load dependency {}: {}", path, e);
+                    ctx.warnings.push(e);
+                    ctx.dependencies.push(None);
+                }
+            }
+        }
+    }
+
+    /// Memory blocks are stored one after the other in the order of the refs pointing to them
+    fn layout_memory(ctx: &mut IgbLoaderContext, memory_start: u64) {
+        let mut offset = memory_start;
+        for (index, entry) in ctx.refs.iter().enumerate() {
+            if let IgbEntry::Memory { size, element, pool } = &ctx.entries[*entry as usize] {
+                ctx.memory.insert(index as u32, IgbMemoryBlock {
+                    offset,
+                    size: *size,
+                    element: *element,
+                    pool: *pool,
+                });
+                offset += *size as u64;
+            }
+        }
+    }
+
+    /// Creates every object ahead of reading any fields so references can point forward. When loading leniently, objects that can't be created are replaced by igNull
+    fn instantiate_objects(imm: &mut igMetadataManager, handle: &Cursor<Vec<u8>>, ctx: &mut IgbLoaderContext) -> Result<(), IgbLoaderError> {
+        for index in 0..ctx.refs.len() as u32 {
+            let IgbEntry::Object { meta_object } = ctx.entries[ctx.refs[index as usize] as usize] else {
+                continue;
+            };
+
+            ctx.location = IgbLoaderLocation::Object(index);
+            let object = match igIGBLoader::instantiate_object(imm, ctx, meta_object) {
+                Ok(object) => object,
+                Err(kind) => {
+                    let error = ctx.error(handle.position(), kind);
+                    if !ctx.lenient {
+                        return Err(error);
+                    }
+                    ctx.warn(error);
+                    Arc::new(RwLock::new(igNull))
+                }
+            };
+            ctx.objects.insert(index, object);
+        }
+
+        Ok(())
+    }
+
+    fn instantiate_object(imm: &mut igMetadataManager, ctx: &IgbLoaderContext, meta_object: u32) -> Result<igObject, IgbLoaderErrorKind> {
+        let name = &ctx.meta_objects
+            .get(meta_object as usize)
+            .ok_or(IgbLoaderErrorKind::InvalidIndex { list: "meta object list", index: meta_object as u64 })?
+            .name;
+        if !imm.has_meta(name) {
+            return Err(IgbLoaderErrorKind::UnknownType(name.clone()));
+        }
+
+        let meta = imm.get_or_create_meta(name).unwrap();
+        let object = meta.read().unwrap().raw_instantiate(igMemoryPool::Default, false);
+        object.map_err(IgbLoaderErrorKind::InstantiationFailed)
+    }
+
+    /// Objects are stored one after the other in the order of the refs pointing to them. Each starts with its size so objects that can't be read can be skipped
+    fn read_objects(
+        imm: &mut igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        object_start: u64,
+        ctx: &mut IgbLoaderContext,
+    ) -> Result<(), IgbLoaderError> {
+        handle.set_position(object_start);
+        for index in 0..ctx.refs.len() as u32 {
+            let IgbEntry::Object { meta_object } = ctx.entries[ctx.refs[index as usize] as usize] else {
+                continue;
+            };
+
+            ctx.location = IgbLoaderLocation::Object(index);
+            let start = handle.position();
+            let size = read_u32(handle, ctx.endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
+            let object = ctx.objects[&index].clone();
+            if !object.read().unwrap().as_any().is::<igNull>() {
+                let fields = ctx.meta_objects[meta_object as usize].fields.clone();
+                imm.read_igb_fields(object_stream_manager, handle, ctx, &fields, object)?;
+            }
+            handle.set_position(start + size as u64);
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a name from one of the tables. The length includes the null terminator
+fn read_name(handle: &mut Cursor<Vec<u8>>, length: u32) -> std::io::Result<String> {
+    let bytes = read_struct_array_u8(handle, Endian::Little, length as usize)?;
+    let end = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+    Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Reads a table of names stored as its size, a count, the length of each name and then the names
+fn read_name_table(handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Vec<String>> {
+    let start = handle.position();
+    let size = read_u32(handle, endian.clone())?;
+    let count = read_u32(handle, endian.clone())?;
+    let lengths = read_struct_array_u32(handle, endian, count as usize)?;
+    let names = lengths.into_iter().map(|length| read_name(handle, length)).collect();
+    handle.set_position(start + size as u64);
+    names
+}
@@ -1 +1,500 @@
-pub struct IgxLoaderContext;
\ No newline at end of file
+use crate::core::ig_custom::{igNull, CastTo};
+use crate::core::ig_external_ref::igExternalReferenceSystem;
+use crate::core::ig_file_context::igFileContext;
+use crate::core::ig_handle::{igHandle, igHandleName};
+use crate::core::ig_memory::igMemoryPool;
+use crate::core::ig_objects::{igObject, igObjectDirectory, igObjectStreamManager};
+use crate::core::ig_registry::igRegistry;
+use crate::core::load::ig_loader::{igObjectLoader, LoaderError};
+use crate::core::meta::ig_metadata_manager::{igMetaInstantiationError, igMetadataManager, SetObjectFieldError};
+use crate::util::ig_name::igName;
+use log::{error, warn};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+/// Name of the element every igx starts with
+pub(crate) const IGX_ROOT: &str = "igObjectDirectory";
+
+pub struct igIGXObjectLoader;
+
+/// Describes why an igx failed to load and where in the file it happened
+#[derive(Debug)]
+pub struct IgxLoaderError {
+    /// Path of the igx that failed to load
+    pub path: String,
+    /// The part of the igx that was being read
+    pub location: IgxLoaderLocation,
+    /// What went wrong
+    pub kind: IgxLoaderErrorKind,
+}
+
+impl Display for IgxLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.path, self.location, self.kind)
+    }
+}
+
+impl std::error::Error for IgxLoaderError {}
+
+/// The parts of an igx a [IgxLoaderError] can come from
+#[derive(Debug, Clone)]
+pub enum IgxLoaderLocation {
+    /// The igObjectDirectory element and anything directly inside it that isn't an object
+    Directory,
+    /// The object with the id given
+    Object(String),
+    /// A field of the object with the id given
+    Field { object: String, field: Arc<str> },
+}
+
+impl Display for IgxLoaderLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgxLoaderLocation::Directory => write!(f, "directory"),
+            IgxLoaderLocation::Object(id) => write!(f, "object {}", id),
+            IgxLoaderLocation::Field { object, field } => write!(f, "field {} of object {}", field, object),
+        }
+    }
+}
+
+/// Describes everything that can go wrong while reading an igx
+#[derive(Debug)]
+pub enum IgxLoaderErrorKind {
+    /// Returned when the file could not be opened
+    FileUnreadable,
+    /// Returned when the file isn't well-formed xml
+    Xml(quick_xml::Error),
+    /// Returned when the file doesn't start with an igObjectDirectory element
+    InvalidRoot(String),
+    /// Returned when an element is missing an attribute it can't be read without
+    MissingAttribute { element: String, attribute: &'static str },
+    /// Returned when an object's type has no metadata
+    UnknownType(String),
+    /// Returned when a reference points to an object that doesn't exist
+    UnknownObject(String),
+    /// Returned when the text of a value can't be parsed as the type of the field
+    InvalidValue { expected: &'static str, value: String },
+    /// Returned when a memory pool name isn't one of [igMemoryPool]
+    InvalidMemoryPool(String),
+    /// Returned when a fixed size array has a different amount of items than its metadata lists
+    InvalidArrayLength { expected: usize, found: usize },
+    /// Returned when an object could not be created from its metadata
+    InstantiationFailed(igMetaInstantiationError),
+    /// Returned when an object refused a value read for one of its fields
+    SetField(SetObjectFieldError),
+}
+
+impl Display for IgxLoaderErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgxLoaderErrorKind::FileUnreadable => write!(f, "file could not be read"),
+            IgxLoaderErrorKind::Xml(e) => write!(f, "invalid xml: {}", e),
+            IgxLoaderErrorKind::InvalidRoot(name) => write!(f, "expected a {} element but found {}", IGX_ROOT, name),
+            IgxLoaderErrorKind::MissingAttribute { element, attribute } => write!(f, "{} is missing the attribute {}", element, attribute),
+            IgxLoaderErrorKind::UnknownType(name) => write!(f, "no metadata exists for the type {}", name),
+            IgxLoaderErrorKind::UnknownObject(reference) => write!(f, "no object exists for the reference {}", reference),
+            IgxLoaderErrorKind::InvalidValue { expected, value } => write!(f, "'{}' is not a valid {}", value, expected),
+            IgxLoaderErrorKind::InvalidMemoryPool(name) => write!(f, "invalid memory pool name '{}'", name),
+            IgxLoaderErrorKind::InvalidArrayLength { expected, found } => write!(f, "array expected {} items but found {}", expected, found),
+            IgxLoaderErrorKind::InstantiationFailed(e) => write!(f, "failed to instantiate object: {:?}", e),
+            IgxLoaderErrorKind::SetField(e) => write!(f, "failed to set field: {:?}", e),
+        }
+    }
+}
+
+impl From<quick_xml::Error> for IgxLoaderErrorKind {
+    fn from(value: quick_xml::Error) -> Self {
+        IgxLoaderErrorKind::Xml(value)
+    }
+}
+
+/// A single element of an igx. Values are kept in the text of the element, references and memory settings in its attributes
+#[derive(Debug, Clone, Default)]
+pub struct IgxNode {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    /// Only kept for elements without children
+    pub text: String,
+    pub children: Vec<IgxNode>,
+}
+
+impl IgxNode {
+    pub fn new(name: &str) -> Self {
+        IgxNode {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Same as [IgxNode::attribute] but a missing attribute is an error
+    pub fn required_attribute(&self, name: &'static str) -> Result<&str, IgxLoaderErrorKind> {
+        self.attribute(name).ok_or_else(|| IgxLoaderErrorKind::MissingAttribute {
+            element: self.name.clone(),
+            attribute: name,
+        })
+    }
+
+    pub fn set_attribute(&mut self, name: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.attributes.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = value,
+            None => self.attributes.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn child(&self, name: &str) -> Option<&IgxNode> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Values that are "null" are written as an element with null="true"
+    pub fn is_null(&self) -> bool {
+        self.attribute("null") == Some("true")
+    }
+
+    pub fn set_null(&mut self) {
+        self.set_attribute("null", "true");
+    }
+
+    /// Reads the root element of an xml document
+    pub(crate) fn parse(bytes: &[u8]) -> Result<IgxNode, IgxLoaderErrorKind> {
+        let mut reader = Reader::from_reader(bytes);
+        reader.config_mut().trim_text(false);
+
+        let mut stack: Vec<IgxNode> = vec![];
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => stack.push(IgxNode::from_start(&reader, &e)?),
+                Event::Empty(e) => {
+                    let node = IgxNode::from_start(&reader, &e)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => return Ok(node),
+                    }
+                }
+                Event::Text(e) => {
+                    if let Some(node) = stack.last_mut() {
+                        node.text.push_str(&e.unescape()?);
+                    }
+                }
+                Event::CData(e) => {
+                    if let Some(node) = stack.last_mut() {
+                        node.text.push_str(&reader.decoder().decode(&e).map_err(quick_xml::Error::from)?);
+                    }
+                }
+                Event::End(_) => {
+                    let mut node = stack.pop().unwrap();
+                    if !node.children.is_empty() {
+                        // Whatever is left is the indentation between the children
+                        node.text.clear();
+                    }
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => return Ok(node),
+                    }
+                }
+                Event::Eof => {
+                    return Err(IgxLoaderErrorKind::InvalidRoot("the end of the file".to_string()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn from_start(reader: &Reader<&[u8]>, e: &BytesStart) -> Result<IgxNode, IgxLoaderErrorKind> {
+        let mut node = IgxNode::new(&reader.decoder().decode(e.name().as_ref()).map_err(quick_xml::Error::from)?);
+        for attribute in e.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            let key = reader.decoder().decode(attribute.key.as_ref()).map_err(quick_xml::Error::from)?.to_string();
+            let value = attribute.unescape_value()?.to_string();
+            node.attributes.push((key, value));
+        }
+        Ok(node)
+    }
+}
+
+/// Parses text written by [format_hex](crate::core::save::ig_igx_saver::format_hex)
+pub(crate) fn parse_hex(text: &str) -> Result<Vec<u8>, IgxLoaderErrorKind> {
+    let text = text.trim();
+    let invalid = || IgxLoaderErrorKind::InvalidValue { expected: "hex string", value: text.to_string() };
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Parses the text of a value element, reporting the type expected when it can't be
+pub(crate) fn parse_text<T: FromStr>(node: &IgxNode, expected: &'static str) -> Result<T, IgxLoaderErrorKind> {
+    node.text.trim().parse().map_err(|_| IgxLoaderErrorKind::InvalidValue {
+        expected,
+        value: node.text.clone(),
+    })
+}
+
+/// Internal type to store while jumping around to other methods. Also shared with loading metafields
+pub struct IgxLoaderContext {
+    /// Path of the igx being loaded
+    pub path: String,
+    /// Every object in the igx by its id
+    pub objects: HashMap<String, igObject>,
+    /// Meta objects referenced by the igx. Looked up ahead of time as metafields only get to read the [igMetadataManager]
+    pub meta_objects: HashMap<String, igObject>,
+    /// The part of the igx currently being read. Used to say where an [IgxLoaderError] came from
+    pub location: IgxLoaderLocation,
+    /// When set, objects and fields that fail to load are replaced by igNull and put in [IgxLoaderContext::warnings] instead of failing the igx
+    pub lenient: bool,
+    /// Problems skipped over so far. Becomes the [LoadReport](crate::core::load::ig_loader::LoadReport) of the directory
+    pub warnings: Vec<LoaderError>,
+}
+
+impl IgxLoaderContext {
+    pub(crate) fn new(path: &str, lenient: bool) -> Self {
+        IgxLoaderContext {
+            path: path.to_string(),
+            objects: HashMap::new(),
+            meta_objects: HashMap::new(),
+            location: IgxLoaderLocation::Directory,
+            lenient,
+            warnings: vec![],
+        }
+    }
+
+    /// Resolves the object an object reference element points to. Objects in this igx are referenced by id, meta objects by name and objects in other directories by their namespace and name
+    pub fn get_object(&self, node: &IgxNode, object_stream_manager: &igObjectStreamManager) -> Result<igObject, IgxLoaderErrorKind> {
+        if let Some(id) = node.attribute("ref") {
+            return self.objects.get(id).cloned().ok_or_else(|| IgxLoaderErrorKind::UnknownObject(id.to_string()));
+        }
+        if let Some(name) = node.attribute("metaobject") {
+            return self.meta_objects.get(name).cloned().ok_or_else(|| IgxLoaderErrorKind::UnknownType(name.to_string()));
+        }
+
+        let namespace = read_name(node, "namespace", "namespaceHash")?;
+        let name = read_name(node, "name", "nameHash")?;
+        let reference = format!("{}.{}", namespace.string.clone().unwrap_or_else(|| format!("{:#x}", namespace.hash)), name.string.clone().unwrap_or_else(|| format!("{:#x}", name.hash)));
+        let handle = igHandle::from_handle_name(&igHandleName::new(name, namespace));
+        let object = handle.write().unwrap().get_object_alias(object_stream_manager);
+        object.ok_or(IgxLoaderErrorKind::UnknownObject(reference))
+    }
+
+    /// Builds an [IgxLoaderError] for the part of the igx currently being read
+    pub fn error(&self, kind: IgxLoaderErrorKind) -> IgxLoaderError {
+        IgxLoaderError {
+            path: self.path.clone(),
+            location: self.location.clone(),
+            kind,
+        }
+    }
+
+    /// Records a problem that was skipped over while loading leniently
+    pub fn warn(&mut self, error: IgxLoaderError) {
+        warn!("Skipping over a broken part of an igx: {}", error);
+        self.warnings.push(LoaderError::Igx(error));
+    }
+}
+
+/// Reads an igName from its string, its hash or both when the hash wasn't made from the string
+fn read_name(node: &IgxNode, string: &'static str, hash: &'static str) -> Result<igName, IgxLoaderErrorKind> {
+    let string = node.attribute(string);
+    if let (Some(string), None) = (string, node.attribute(hash)) {
+        return Ok(igName::new(string.to_string()));
+    }
+    let value = node.required_attribute(hash)?;
+    let parsed = u32::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|_| IgxLoaderErrorKind::InvalidValue {
+        expected: "hash",
+        value: value.to_string(),
+    })?;
+    Ok(match string {
+        Some(string) => igName::with_hash(string.to_string(), parsed),
+        None => igName::from_hash(parsed),
+    })
+}
+
+impl igObjectLoader for igIGXObjectLoader {
+    fn can_read(&self, file_name: &str) -> bool {
+        file_name.ends_with(".igx")
+    }
+
+    fn can_read_magic(&self, header: &[u8]) -> bool {
+        // Any xml could come first, so look for the root element anywhere in what was read
+        let root = format!("<{}", IGX_ROOT);
+        header.windows(root.len()).any(|x| x == root.as_bytes())
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Alchemy XML"
+    }
+
+    fn get_type(&self) -> &'static str {
+        "Alchemy"
+    }
+
+    fn read_file(
+        &self,
+        ig_file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        ig_object_stream_manager: &mut igObjectStreamManager,
+        ig_ext_ref_system: &mut igExternalReferenceSystem,
+        ig_metadata_manager: &mut igMetadataManager,
+        dir: &mut igObjectDirectory,
+        file_path: &str,
+    ) -> Result<(), LoaderError> {
+        let lenient = ig_object_stream_manager.lenient;
+        igIGXLoader::read(
+            ig_file_context,
+            ig_registry,
+            ig_object_stream_manager,
+            ig_ext_ref_system,
+            ig_metadata_manager,
+            dir,
+            file_path,
+            lenient,
+        )?;
+        Ok(())
+    }
+}
+
+pub struct igIGXLoader;
+
+impl igIGXLoader {
+    #[allow(clippy::too_many_arguments)]
+    fn read(
+        ig_file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        ig_object_stream_manager: &mut igObjectStreamManager,
+        ig_ext_ref_system: &mut igExternalReferenceSystem,
+        imm: &mut igMetadataManager,
+        dir: &mut igObjectDirectory,
+        file_path: &str,
+        lenient: bool,
+    ) -> Result<(), IgxLoaderError> {
+        let mut ctx = IgxLoaderContext::new(file_path, lenient);
+        let fd = ig_file_context.open(ig_registry, file_path, 0);
+        let Some(handle) = fd._handle else {
+            error!("Failed to load igx {}. File could not be read.", file_path);
+            return Err(ctx.error(IgxLoaderErrorKind::FileUnreadable));
+        };
+
+        let root = IgxNode::parse(handle.get_ref()).map_err(|kind| ctx.error(kind))?;
+        if root.name != IGX_ROOT {
+            return Err(ctx.error(IgxLoaderErrorKind::InvalidRoot(root.name)));
+        }
+
+        for dependency in root.children.iter().filter(|child| child.name == "dependency") {
+            let name = dependency.required_attribute("name").map_err(|kind| ctx.error(kind))?;
+            let path = dependency.required_attribute("path").map_err(|kind| ctx.error(kind))?;
+            match ig_object_stream_manager.load_with_namespace(
+                ig_file_context,
+                ig_registry,
+                imm,
+                ig_ext_ref_system,
+                path.to_string(),
+                igName::new(name.to_string()),
+            ) {
+                Ok(dependency) => dir.dependencies.push(dependency),
+                Err(e) => {
+                    // A broken dependency only breaks the references into it, so it never fails this file
+                    error!("Failed to load dependency {}: {}", path, e);
+                    ctx.warnings.push(e);
+                }
+            }
+        }
+
+        let objects: Vec<&IgxNode> = root.children.iter().filter(|child| child.name == "object").collect();
+        igIGXLoader::instantiate_objects(imm, &objects, &mut ctx)?;
+        igIGXLoader::resolve_meta_objects(imm, &root, &mut ctx);
+
+        for node in &objects {
+            let id = node.required_attribute("id").map_err(|kind| ctx.error(kind))?;
+            let object = ctx.objects[id].clone();
+            if object.read().unwrap().as_any().is::<igNull>() {
+                // Stand-in for an object that failed to instantiate, there is nothing to read into
+                continue;
+            }
+
+            ctx.location = IgxLoaderLocation::Object(id.to_string());
+            imm.read_igx_fields(ig_object_stream_manager, node, &mut ctx, object)?;
+        }
+
+        ctx.location = IgxLoaderLocation::Directory;
+        let object_list = root.required_attribute("objectList").map_err(|kind| ctx.error(kind))?;
+        dir.object_list = ctx.objects
+            .get(object_list)
+            .cloned()
+            .ok_or_else(|| ctx.error(IgxLoaderErrorKind::UnknownObject(object_list.to_string())))?
+            .cast_to()
+            .map_err(|e| ctx.error(IgxLoaderErrorKind::InstantiationFailed(e)))?;
+        if let Some(name_list) = root.attribute("nameList") {
+            dir.use_name_list = true;
+            dir.name_list = ctx.objects
+                .get(name_list)
+                .cloned()
+                .ok_or_else(|| ctx.error(IgxLoaderErrorKind::UnknownObject(name_list.to_string())))?
+                .cast_to()
+                .map_err(|e| ctx.error(IgxLoaderErrorKind::InstantiationFailed(e)))?;
+        }
+
+        dir.load_report.warnings.append(&mut ctx.warnings);
+        Ok(())
+    }
+
+    /// Creates every object ahead of reading any fields so references can point forward. When loading leniently, objects that can't be created are replaced by igNull
+    fn instantiate_objects(imm: &mut igMetadataManager, objects: &[&IgxNode], ctx: &mut IgxLoaderContext) -> Result<(), IgxLoaderError> {
+        for node in objects {
+            let id = node.required_attribute("id").map_err(|kind| ctx.error(kind))?;
+            ctx.location = IgxLoaderLocation::Object(id.to_string());
+            let object = match igIGXLoader::instantiate_object(imm, node) {
+                Ok(object) => object,
+                Err(kind) => {
+                    let error = ctx.error(kind);
+                    if !ctx.lenient {
+                        return Err(error);
+                    }
+                    ctx.warn(error);
+                    Arc::new(RwLock::new(igNull))
+                }
+            };
+            ctx.objects.insert(id.to_string(), object);
+        }
+
+        ctx.location = IgxLoaderLocation::Directory;
+        Ok(())
+    }
+
+    fn instantiate_object(imm: &mut igMetadataManager, node: &IgxNode) -> Result<igObject, IgxLoaderErrorKind> {
+        let type_name = node.required_attribute("type")?;
+        if !imm.has_meta(type_name) {
+            return Err(IgxLoaderErrorKind::UnknownType(type_name.to_string()));
+        }
+        let pool = match node.attribute("pool") {
+            Some(pool) => igMemoryPool::from_str(pool).map_err(|_| IgxLoaderErrorKind::InvalidMemoryPool(pool.to_string()))?,
+            None => igMemoryPool::Default,
+        };
+
+        let meta = imm.get_or_create_meta(type_name).unwrap();
+        let object = meta.read().unwrap().raw_instantiate(pool, false);
+        object.map_err(IgxLoaderErrorKind::InstantiationFailed)
+    }
+
+    /// Looks up every meta object referenced anywhere in the igx. Unknown types are left out and reported by the field referencing them
+    fn resolve_meta_objects(imm: &mut igMetadataManager, node: &IgxNode, ctx: &mut IgxLoaderContext) {
+        if let Some(name) = node.attribute("metaobject") {
+            if !ctx.meta_objects.contains_key(name) && imm.has_meta(name) {
+                let meta: igObject = imm.get_or_create_meta(name).unwrap();
+                ctx.meta_objects.insert(name.to_string(), meta);
+            }
+        }
+        for child in &node.children {
+            igIGXLoader::resolve_meta_objects(imm, child, ctx);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::ig_file_context::igFileContext;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_registry::igRegistry;
+use crate::core::load::ig_igz_loader::{
+    fixup_data_size, get_attribute_location, get_chunk_descriptor_start, igIGZLoader, read_padded_string,
+    unpack_compressed_ints, Fixup, IgzLoaderContext, IgzLoaderError, IgzLoaderErrorKind,
+    IgzLoaderLocation, RuntimeFields,
+};
+use crate::core::meta::ig_metadata_manager::igMetadataManager;
+use crate::util::byteorder_fixes::{read_ptr, read_string, read_struct_array_u8, read_u32};
+use serde::Serialize;
+use std::io::Cursor;
+
+/// The raw structure of an igz, read without creating any of its objects. Meant for looking at igz files from games that aren't fully understood yet, so no metafields are needed to build it
+#[derive(Debug, Serialize)]
+pub struct IgzDump {
+    /// igz version
+    pub version: u32,
+    /// unsure on what this is for
+    pub meta_object_version: u32,
+    /// platform the igz targets
+    pub platform: IG_CORE_PLATFORM,
+    /// true when the igz was written for a big endian platform
+    pub big_endian: bool,
+    /// amount of fixups present
+    pub fixup_count: u32,
+    /// Every used entry of the section table. Section 0 holds the fixups
+    pub sections: Vec<IgzSectionDump>,
+    /// Every fixup in the order they are stored
+    pub fixups: Vec<IgzFixupDump>,
+    /// The type names from TMET. Objects refer to these by index
+    pub types: Vec<String>,
+    /// The strings from TSTR
+    pub strings: Vec<String>,
+    /// The dependencies from TDEP
+    pub dependencies: Vec<IgzDependencyDump>,
+    /// The decoded runtime fixups. Like the loader, vtables and object lists stay serialized offsets while the rest are positions in the file
+    pub runtime_fields: RuntimeFields,
+    /// Every object listed in RVTB
+    pub objects: Vec<IgzObjectDump>,
+}
+
+/// An entry of the section table
+#[derive(Debug, Serialize)]
+pub struct IgzSectionDump {
+    /// Index of the section in the table
+    pub index: u32,
+    /// Name of the memory pool the section is loaded into
+    pub name: String,
+    /// Position of the section in the file
+    pub offset: u32,
+    pub length: u32,
+    pub alignment: u32,
+}
+
+/// The header of a fixup
+#[derive(Debug, Serialize)]
+pub struct IgzFixupDump {
+    /// Magic of the fixup for igz versioned 7 or above, or its id below that
+    pub magic: String,
+    /// What the loader understands the fixup as. [None] when the loader skips it
+    pub fixup: Option<String>,
+    /// Position of the fixup in the file
+    pub position: u64,
+    pub count: u32,
+    pub length: u32,
+    /// Where the data starts, relative to the position
+    pub start: u32,
+}
+
+/// An entry of TDEP
+#[derive(Debug, Serialize)]
+pub struct IgzDependencyDump {
+    pub name: String,
+    pub path: String,
+}
+
+/// An object listed in RVTB
+#[derive(Debug, Serialize)]
+pub struct IgzObjectDump {
+    /// Serialized offset of the object. This is what pointers to the object store
+    pub offset: u64,
+    /// Position of the object in the file
+    pub position: u64,
+    /// Memory pool of the section the object is in
+    pub pool: String,
+    /// Index of the type in [IgzDump::types]
+    pub vtable_index: u64,
+    /// Name of the type. [None] when the index is outside of TMET
+    pub type_name: Option<String>,
+}
+
+impl IgzDump {
+    /// Opens the igz at the path given and dumps its structure
+    pub fn open(
+        ig_file_context: &igFileContext,
+        ig_registry: &igRegistry,
+        imm: &igMetadataManager,
+        file_path: &str,
+    ) -> Result<IgzDump, IgzLoaderError> {
+        let fd = ig_file_context.open(ig_registry, file_path, 0);
+        let Some(handle) = fd._handle else {
+            return Err(IgzLoaderError {
+                path: file_path.to_string(),
+                location: IgzLoaderLocation::Header,
+                offset: 0,
+                kind: IgzLoaderErrorKind::FileUnreadable,
+            });
+        };
+
+        IgzDump::from_bytes(imm, file_path, handle.into_inner())
+    }
+
+    /// Dumps the structure of an igz already in memory. The path is only used for errors. The metadata manager is only used to know the platform
+    pub fn from_bytes(imm: &igMetadataManager, file_path: &str, data: Vec<u8>) -> Result<IgzDump, IgzLoaderError> {
+        let mut handle = Cursor::new(data);
+        let mut ctx = igIGZLoader::read_header(&mut handle, imm, file_path)?;
+        let endian = ctx.endian.clone();
+        igIGZLoader::parse_sections(&mut handle, endian.clone(), &mut ctx)?;
+
+        let mut dump = IgzDump {
+            version: ctx.version,
+            meta_object_version: ctx.meta_object_version,
+            platform: ctx.platform.clone(),
+            big_endian: matches!(endian, Endian::Big),
+            fixup_count: ctx.fixup_count,
+            sections: read_sections(&mut handle, &mut ctx)?,
+            fixups: vec![],
+            types: vec![],
+            strings: vec![],
+            dependencies: vec![],
+            runtime_fields: RuntimeFields::new(),
+            objects: vec![],
+        };
+
+        read_fixups(&mut handle, &mut ctx, &mut dump)?;
+        dump.runtime_fields = ctx.runtime_fields.clone();
+
+        for offset in &ctx.runtime_fields.vtables {
+            ctx.location = IgzLoaderLocation::Object(*offset);
+            let position = ctx.deserialize_offset(*offset).map_err(|kind| ctx.error(handle.position(), kind))?;
+            handle.set_position(position);
+            let vtable_index = read_ptr(&mut handle, ctx.platform.clone(), endian.clone()).map_err(|e| ctx.error(position, e.into()))?;
+            dump.objects.push(IgzObjectDump {
+                offset: *offset,
+                position,
+                pool: format!("{:?}", ctx.get_pool_from_serialized_offset(*offset).map_err(|kind| ctx.error(position, kind))?),
+                vtable_index,
+                type_name: dump.types.get(vtable_index as usize).cloned(),
+            });
+        }
+
+        Ok(dump)
+    }
+
+    /// Serializes the dump as pretty printed json
+    pub fn to_json(&self) -> String {
+        sonic_rs::to_string_pretty(self).unwrap()
+    }
+}
+
+fn read_sections(handle: &mut Cursor<Vec<u8>>, ctx: &mut IgzLoaderContext) -> Result<Vec<IgzSectionDump>, IgzLoaderError> {
+    let mut sections = vec![];
+    let descriptor_start = get_chunk_descriptor_start(ctx.version).map_err(|kind| ctx.error(0x4, kind))?;
+    let attribute_location = get_attribute_location(ctx.version).map_err(|kind| ctx.error(0x4, kind))?;
+    for i in 0..ctx.section_count {
+        ctx.location = IgzLoaderLocation::Section(i);
+        handle.set_position(descriptor_start + 0x10 * i as u64);
+        let name_ptr = read_u32(handle, ctx.endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        let offset = read_u32(handle, ctx.endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        let length = read_u32(handle, ctx.endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        let alignment = read_u32(handle, ctx.endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+
+        handle.set_position((attribute_location + name_ptr) as u64);
+        let name = read_string(handle).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        sections.push(IgzSectionDump { index: i, name, offset, length, alignment });
+    }
+
+    Ok(sections)
+}
+
+/// Walks the fixups the same way the loader does, but only keeps what they describe instead of acting on it
+fn read_fixups(handle: &mut Cursor<Vec<u8>>, ctx: &mut IgzLoaderContext, dump: &mut IgzDump) -> Result<(), IgzLoaderError> {
+    let endian = ctx.endian.clone();
+    // Fixup headers before 0x07 are 0x18 bytes long and start after a second header, see process_legacy_fixup_sections
+    let mut bytes_processed = if ctx.version > 0x06 { 0 } else { 0x1C };
+
+    for _i in 0..ctx.fixup_count {
+        let position = (ctx.fixup_offset + bytes_processed) as u64;
+        handle.set_position(position);
+        ctx.location = IgzLoaderLocation::Section(0);
+        let raw_magic = read_u32(handle, endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        let (magic, fixup) = if ctx.version > 0x06 {
+            (String::from_utf8_lossy(&raw_magic.to_le_bytes()).to_string(), Fixup::try_from(raw_magic))
+        } else {
+            let _padding = read_u32(handle, endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+            let _padding = read_u32(handle, endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+            (format!("{:#04x}", raw_magic as u8), Fixup::try_from(raw_magic as u8))
+        };
+        let count = read_u32(handle, endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        let length = read_u32(handle, endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        let start = read_u32(handle, endian.clone()).map_err(|e| ctx.error(handle.position(), e.into()))?;
+        handle.set_position(position + start as u64);
+        ctx.location = IgzLoaderLocation::Fixup(magic.clone());
+
+        if let Ok(fixup) = &fixup {
+            let runtime_list = |ctx: &mut IgzLoaderContext, handle: &mut Cursor<Vec<u8>>, deserialize: bool| {
+                let size = fixup_data_size(length, start).map_err(|kind| ctx.error(handle.position(), kind))?;
+                let bytes = read_struct_array_u8(handle, endian.clone(), size).map_err(|e| ctx.error(handle.position(), e.into()))?;
+                unpack_compressed_ints(ctx, handle.position(), &bytes, count, deserialize)
+            };
+
+            match fixup {
+                Fixup::T_METADATA => {
+                    for _ in 0..count {
+                        dump.types.push(read_padded_string(handle, ctx.version).map_err(|e| ctx.error(handle.position(), e.into()))?);
+                    }
+                }
+                Fixup::T_STRING_LIST => {
+                    for _ in 0..count {
+                        dump.strings.push(read_padded_string(handle, ctx.version).map_err(|e| ctx.error(handle.position(), e.into()))?);
+                    }
+                }
+                Fixup::T_DEPENDENCIES => {
+                    for _ in 0..count {
+                        let name = read_string(handle).map_err(|e| ctx.error(handle.position(), e.into()))?;
+                        let path = read_string(handle).map_err(|e| ctx.error(handle.position(), e.into()))?;
+                        dump.dependencies.push(IgzDependencyDump { name, path });
+                    }
+                }
+                Fixup::RUNTIME_V_TABLES => ctx.runtime_fields.vtables = runtime_list(ctx, handle, false)?,
+                Fixup::RUNTIME_OBJECT_LISTS => ctx.runtime_fields.object_lists = runtime_list(ctx, handle, false)?,
+                Fixup::RUNTIME_OFFSETS => ctx.runtime_fields.offsets = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_POOL_IDS => ctx.runtime_fields.pool_ids = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_STRING_TABLES => ctx.runtime_fields.string_tables = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_STRING_REFERENCES => ctx.runtime_fields.string_references = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_MEMORY_HANDLES => ctx.runtime_fields.memory_handles = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_EXTERNALS => ctx.runtime_fields.externals = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_NAMED_EXTERNALS => ctx.runtime_fields.named_externals = runtime_list(ctx, handle, true)?,
+                Fixup::RUNTIME_HANDLES => ctx.runtime_fields.handles = runtime_list(ctx, handle, true)?,
+                // Only the header of these is dumped
+                Fixup::EXTERNAL_DEPENDENCIES_BY_ID
+                | Fixup::EXTERNAL_DEPENDENCIES_BY_NAME
+                | Fixup::THUMBNAIL
+                | Fixup::OPTION_NAMED_LIST
+                | Fixup::METADATA_SIZES => {}
+            }
+        }
+
+        dump.fixups.push(IgzFixupDump {
+            magic,
+            fixup: fixup.ok().map(|x| format!("{:?}", x)),
+            position,
+            count,
+            length,
+            start,
+        });
+        bytes_processed += length;
+    }
+
+    Ok(())
+}
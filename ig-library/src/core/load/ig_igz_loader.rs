@@ -8,29 +8,140 @@ use crate::core::ig_handle::{igHandle, igHandleName};
 use crate::core::ig_memory::igMemoryPool;
 use crate::core::ig_objects::{igObject, igObjectDirectory, igObjectStreamManager};
 use crate::core::ig_registry::igRegistry;
-use crate::core::load::ig_loader::igObjectLoader;
+use crate::core::load::ig_loader::{igObjectLoader, LoaderError};
 use crate::core::meta::ig_metadata_manager::{__internalObjectBase, igMetaObject};
-use crate::core::meta::ig_metadata_manager::{igMetaInstantiationError, igMetadataManager};
+use crate::core::meta::ig_metadata_manager::{igMetaInstantiationError, igMetadataManager, SetObjectFieldError};
 use crate::util::byteorder_fixes::{
     read_ptr, read_string, read_struct_array_u8, read_u32, read_u64,
 };
-use crate::util::ig_hash::{hash, hash_lower};
+use crate::util::ig_hash_dictionary::add_string;
 use crate::util::ig_name::igName;
-use log::{debug, error, info};
+use log::{debug, error, warn};
+use serde::Serialize;
 use std::collections::HashMap;
-use std::io::Cursor;
-use std::io::Seek;
-use std::io::SeekFrom;
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, ErrorKind};
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
-const IGZ_LITTLE_ENDIAN_MAGIC: u32 = u32::from_be_bytes([b'I', b'G', b'Z', 0x01]);
+pub(crate) const IGZ_LITTLE_ENDIAN_MAGIC: u32 = u32::from_be_bytes([b'I', b'G', b'Z', 0x01]);
 const IGZ_BIG_ENDIAN_MAGIC: u32 = u32::from_le_bytes([b'I', b'G', b'Z', 0x01]);
 
 pub struct igIGZObjectLoader;
 
+/// Describes why an igz failed to load and where in the file it happened
 #[derive(Debug)]
-enum Fixup {
+pub struct IgzLoaderError {
+    /// Path of the igz that failed to load
+    pub path: String,
+    /// The part of the igz that was being read
+    pub location: IgzLoaderLocation,
+    /// Position in the file where the failure was noticed
+    pub offset: u64,
+    /// What went wrong
+    pub kind: IgzLoaderErrorKind,
+}
+
+impl Display for IgzLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}, offset {:#x}): {}", self.path, self.location, self.offset, self.kind)
+    }
+}
+
+impl std::error::Error for IgzLoaderError {}
+
+/// The parts of an igz a [IgzLoaderError] can come from
+#[derive(Debug, Clone)]
+pub enum IgzLoaderLocation {
+    /// The igz header
+    Header,
+    /// The section descriptor with the index given. Section 0 holds the fixups
+    Section(u32),
+    /// A fixup, named by its magic for igz versioned 7 or above or by its id below that
+    Fixup(String),
+    /// The object at the serialized offset given
+    Object(u64),
+    /// A field of the object at the file position given
+    Field { object: u64, field: Arc<str> },
+}
+
+impl Display for IgzLoaderLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgzLoaderLocation::Header => write!(f, "header"),
+            IgzLoaderLocation::Section(index) => write!(f, "section {}", index),
+            IgzLoaderLocation::Fixup(name) => write!(f, "fixup {}", name),
+            IgzLoaderLocation::Object(offset) => write!(f, "object {:#x}", offset),
+            IgzLoaderLocation::Field { object, field } => write!(f, "field {} of the object at {:#x}", field, object),
+        }
+    }
+}
+
+/// Describes everything that can go wrong while reading an igz
+#[derive(Debug)]
+pub enum IgzLoaderErrorKind {
+    /// Returned when the file could not be opened
+    FileUnreadable,
+    /// Returned when the file doesn't start with either igz magic value
+    InvalidMagic(u32),
+    /// Returned when the igz version has no known layout
+    UnsupportedVersion(u32),
+    /// Returned when the platform index is missing from the IG_CORE_PLATFORM meta enum
+    UnknownPlatform(u32),
+    /// Returned when a section is named after a memory pool that doesn't exist
+    InvalidMemoryPool(String),
+    /// Returned when a serialized offset points into a section the igz doesn't have
+    MissingSection(u64),
+    /// Returned when TMET names a type that has no metadata
+    UnknownType(String),
+    /// Returned when an object's vtable index is outside of TMET
+    InvalidVtableIndex(u64),
+    /// Returned when an object couldn't be created from its metadata, or wasn't the type it was expected to be
+    InstantiationFailed(igMetaInstantiationError),
+    /// Returned when a serialized offset doesn't point at any object in the igz
+    MissingObject(u64),
+    /// Returned when an index into one of the lists built by the fixups is out of range
+    InvalidIndex { list: &'static str, index: u64 },
+    /// Returned when an igObjectRefMetaField is not null but isn't described by any runtime fixup
+    UnresolvedObjectRef(u64),
+    /// Returned when a value was read but the object refused it
+    SetField(SetObjectFieldError),
+    /// Returned when an external reference points into a directory, named by its namespace, that is still being written to. Usually caused by a dependency cycle
+    DirectoryLocked(String),
+    /// Returned when reading from the file failed, usually because it is truncated
+    Io(std::io::Error),
+}
+
+impl Display for IgzLoaderErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgzLoaderErrorKind::FileUnreadable => write!(f, "file could not be read"),
+            IgzLoaderErrorKind::InvalidMagic(magic) => write!(f, "magic value was wrong. Got: {:#x}", magic),
+            IgzLoaderErrorKind::UnsupportedVersion(version) => write!(f, "igz version {:#x} can't be loaded", version),
+            IgzLoaderErrorKind::UnknownPlatform(index) => write!(f, "platform {} is not in the IG_CORE_PLATFORM meta enum", index),
+            IgzLoaderErrorKind::InvalidMemoryPool(name) => write!(f, "invalid memory pool name '{}'", name),
+            IgzLoaderErrorKind::MissingSection(offset) => write!(f, "serialized offset {:#x} points into a section that doesn't exist", offset),
+            IgzLoaderErrorKind::UnknownType(name) => write!(f, "no metadata exists for the type {}", name),
+            IgzLoaderErrorKind::InvalidVtableIndex(index) => write!(f, "vtable index {} is not in TMET", index),
+            IgzLoaderErrorKind::InstantiationFailed(e) => write!(f, "failed to instantiate object: {:?}", e),
+            IgzLoaderErrorKind::MissingObject(offset) => write!(f, "no object exists at the serialized offset {:#x}", offset),
+            IgzLoaderErrorKind::InvalidIndex { list, index } => write!(f, "index {} is out of range for the {}", index, list),
+            IgzLoaderErrorKind::UnresolvedObjectRef(raw) => write!(f, "igObjectRefMetaField value {:#x} isn't described by any fixup", raw),
+            IgzLoaderErrorKind::SetField(e) => write!(f, "failed to set field: {:?}", e),
+            IgzLoaderErrorKind::DirectoryLocked(namespace) => write!(f, "the directory of namespace {} is locked and can't be searched for external references", namespace),
+            IgzLoaderErrorKind::Io(e) => write!(f, "failed to read igz: {}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for IgzLoaderErrorKind {
+    fn from(value: std::io::Error) -> Self {
+        IgzLoaderErrorKind::Io(value)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum Fixup {
     T_METADATA,
     T_DEPENDENCIES,
     T_STRING_LIST,
@@ -66,19 +177,25 @@ impl Fixup {
         ig_object_stream_manager: &mut igObjectStreamManager,
         ig_ext_ref_system: &mut igExternalReferenceSystem,
         ctx: &mut IgzLoaderContext,
-    ) {
+    ) -> Result<(), IgzLoaderError> {
+        let runtime_list = |ctx: &mut IgzLoaderContext, handle: &mut Cursor<Vec<u8>>, deserialize: bool| {
+            let size = fixup_data_size(length, start).map_err(|kind| ctx.error(handle.position(), kind))?;
+            let bytes = read_struct_array_u8(handle, endian.clone(), size).map_err(|e| ctx.io_error(handle, e))?;
+            unpack_compressed_ints(ctx, handle.position(), &bytes, count, deserialize)
+        };
+
         match self {
             Fixup::T_DEPENDENCIES => {
                 if ctx.read_dependencies {
                     for _i in 0..count {
-                        let name = read_string(handle).unwrap();
-                        let path = read_string(handle).unwrap();
+                        let name = read_string(handle).map_err(|e| ctx.io_error(handle, e))?;
+                        let path = read_string(handle).map_err(|e| ctx.io_error(handle, e))?;
                         if path.starts_with("<build>") {
                             // Unsure on why cauldron does this
                             continue;
                         }
                         let name = igName::new(name);
-                        if let Ok(dependency) = ig_object_stream_manager.load_with_namespace(
+                        match ig_object_stream_manager.load_with_namespace(
                             ig_file_context,
                             ig_registry,
                             imm,
@@ -86,9 +203,12 @@ impl Fixup {
                             path.clone(),
                             name,
                         ) {
-                            dir.dependencies.push(dependency)
-                        } else {
-                            error!("Failed to find dependency {}", path);
+                            Ok(dependency) => dir.dependencies.push(dependency),
+                            Err(e) => {
+                                // A broken dependency only breaks the references into it, so it never fails this file
+                                error!("Failed to load dependency {}: {}", path, e);
+                                ctx.warnings.push(e);
+                            }
                         }
                     }
                 }
@@ -96,43 +216,36 @@ impl Fixup {
             Fixup::T_METADATA => {
                 for _i in 0..count {
                     let base_pos = handle.position();
-                    let vtbl_name = read_string(handle).unwrap();
-                    ctx.vtbl_list
-                        .push(imm.get_or_create_meta(&vtbl_name).unwrap());
-                    debug!("IGZ contains igObject of type {}", vtbl_name);
-
-                    let bits: i32 = if ctx.version > 7 { 2 } else { 1 };
-                    handle
-                        .seek(SeekFrom::Start(
-                            base_pos
-                                + bits as u64
-                                + ((handle.position() - base_pos - 1) & ((-bits) as u32) as u64),
-                        ))
-                        .unwrap();
+                    let vtbl_name = read_padded_string(handle, ctx.version).map_err(|e| ctx.io_error(handle, e))?;
+                    if imm.has_meta(&vtbl_name) {
+                        ctx.vtbl_list.push(Some(imm.get_or_create_meta(&vtbl_name).unwrap()));
+                        debug!("IGZ contains igObject of type {}", vtbl_name);
+                    } else {
+                        let error = ctx.error(base_pos, IgzLoaderErrorKind::UnknownType(vtbl_name));
+                        if !ctx.lenient {
+                            return Err(error);
+                        }
+                        // Every object of this type will become igNull
+                        ctx.warn(error);
+                        ctx.vtbl_list.push(None);
+                    }
                 }
             }
 
             Fixup::T_STRING_LIST => {
                 for _i in 0..count {
-                    let base_pos = handle.position();
-                    let data = read_string(handle).unwrap();
+                    let data = read_padded_string(handle, ctx.version).map_err(|e| ctx.io_error(handle, e))?;
+                    add_string(&data);
                     ctx.string_list.push(data);
-
-                    let bits: i32 = if ctx.version > 7 { 2 } else { 1 };
-                    handle
-                        .seek(SeekFrom::Start(
-                            base_pos
-                                + bits as u64
-                                + ((handle.position() - base_pos - 1) & ((-bits) as u32) as u64),
-                        ))
-                        .unwrap();
                 }
             }
             Fixup::EXTERNAL_DEPENDENCIES_BY_ID => {
                 for _i in 0..count {
+                    let name = read_u32(handle, endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
+                    let namespace = read_u32(handle, endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
                     let dependency_name = igHandleName::new(
-                        igName::from_hash(read_u32(handle, endian.clone()).unwrap()), // name
-                        igName::from_hash(read_u32(handle, endian.clone()).unwrap()), // namespace
+                        igName::from_hash(name),
+                        igName::from_hash(namespace),
                     );
 
                     let mut obj = None;
@@ -146,7 +259,7 @@ impl Fixup {
                                     let name_list = dependent_dir.name_list.read().unwrap();
                                     for i in 0..name_list.len() {
                                         let name = &name_list.query()[i];
-                                        if name.hash == dependency_name.namespace.hash {
+                                        if name.hash == dependency_name.name.hash {
                                             obj = Some(
                                                 dependent_dir.object_list.read().unwrap().query()
                                                     [i]
@@ -161,57 +274,34 @@ impl Fixup {
                                     }
                                 }
                             } else {
-                                error!("Failed to get read lock on igObjectDirectory");
-                                panic!("Alchemy Error! Check the logs.")
+                                let error = ctx.error(handle.position(), IgzLoaderErrorKind::DirectoryLocked(namespace_label(&dependency_name.namespace)));
+                                if !ctx.lenient {
+                                    return Err(error);
+                                }
+                                // The reference stays unresolved
+                                ctx.warn(error);
                             }
                         }
                     } else {
-                        let trial = [
-                            "tfbScript",
-                            "other",
-                            "global",
-                            "tfb",
-                            "system",
-                            "game",
-                            "interface",
-                            "client",
-                            "custom",
-                            "app",
-                            "application",
-                            "internal",
-                        ];
-                        for x in trial {
-                            let normal = hash(x);
-                            let lower = hash_lower(x);
-
-                            if normal == dependency_name.namespace.hash {
-                                info!(
-                                    "We got a normal match {} == {}",
-                                    dependency_name.namespace.hash, x
-                                )
-                            }
-
-                            if lower == dependency_name.namespace.hash {
-                                info!(
-                                    "We got a lower match {} == {}",
-                                    dependency_name.namespace.hash,
-                                    x.to_lowercase()
-                                )
-                            }
-                        }
-
-                        error!("EXID Fixup load failed: Failed to find namespace {:#01}, referenced in {}. This WILL cause issues.", dependency_name.namespace.hash, dir.path);
+                        let namespace = namespace_label(&dependency_name.namespace);
+                        error!("EXID Fixup load failed: Failed to find namespace {}, referenced in {}. This WILL cause issues.", namespace, dir.path);
                     }
+
+                    ctx.external_list.push(igHandle {
+                        namespace: dependency_name.namespace,
+                        alias: dependency_name.name,
+                        object: obj,
+                    });
                 }
             }
             Fixup::EXTERNAL_DEPENDENCIES_BY_NAME => {
                 for _i in 0..count {
-                    let raw_handle = read_u64(handle, endian.clone()).unwrap();
+                    let raw_handle = read_u64(handle, endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
                     let ns_str_index = (raw_handle >> 32) as u32 & 0x7FFF_FFFF;
                     let name_str_index = raw_handle as u32 & 0x7FFF_FFFF;
                     let dependency_handle_name = igHandleName::new(
-                        igName::new(ctx.string_list[name_str_index as usize].clone()),
-                        igName::new(ctx.string_list[ns_str_index as usize].clone()),
+                        igName::new(ctx.get_string(handle.position(), name_str_index as u64)?),
+                        igName::new(ctx.get_string(handle.position(), ns_str_index as u64)?),
                     );
 
                     let mut obj = None;
@@ -238,8 +328,11 @@ impl Fixup {
                                 }
                             }
                         } else {
-                            error!("Failed to get read lock on igObjectDirectory");
-                            panic!("Alchemy Error! Check the logs.")
+                            let error = ctx.error(handle.position(), IgzLoaderErrorKind::DirectoryLocked(namespace_label(&dependency_handle_name.namespace)));
+                            if !ctx.lenient {
+                                return Err(error);
+                            }
+                            ctx.warn(error);
                         }
                     }
 
@@ -266,85 +359,108 @@ impl Fixup {
             }
             Fixup::THUMBNAIL => {
                 for _i in 0..count {
-                    let size = read_ptr(handle, ctx.platform.clone(), endian.clone()).unwrap();
-                    let raw = read_ptr(handle, ctx.platform.clone(), endian.clone()).unwrap();
+                    let size = read_ptr(handle, ctx.platform.clone(), endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
+                    let raw = read_ptr(handle, ctx.platform.clone(), endian.clone()).map_err(|e| ctx.io_error(handle, e))?;
                     ctx.thumbnails.push((size, raw))
                 }
             }
             Fixup::RUNTIME_V_TABLES => {
-                let vec = read_struct_array_u8(handle, endian.clone(), (length - start) as usize).unwrap();
-                ctx.runtime_fields.vtables = unpack_compressed_ints(ctx, &vec, count, false);
-                instantiate_and_append_objects(ctx, handle, endian.clone());
+                ctx.runtime_fields.vtables = runtime_list(ctx, handle, false)?;
+                instantiate_and_append_objects(ctx, handle, endian.clone())?;
             }
             Fixup::RUNTIME_OBJECT_LISTS => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.object_lists = unpack_compressed_ints(ctx, &vec, count, false);
-                let ig_object_list_idx = ctx.runtime_fields.object_lists[0];
-                dir.object_list = ctx.offset_object_list[&ig_object_list_idx]
-                    .clone()
-                    .cast_to()
-                    .unwrap()
+                ctx.runtime_fields.object_lists = runtime_list(ctx, handle, false)?;
+                if let Some(ig_object_list_idx) = ctx.runtime_fields.object_lists.first() {
+                    dir.object_list = ctx.get_object(handle.position(), *ig_object_list_idx)?
+                        .cast_to()
+                        .map_err(|e| ctx.error(handle.position(), IgzLoaderErrorKind::InstantiationFailed(e)))?;
+                }
             }
             Fixup::RUNTIME_OFFSETS => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.offsets = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.offsets = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_POOL_IDS => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.pool_ids = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.pool_ids = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_STRING_TABLES => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.string_tables = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.string_tables = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_STRING_REFERENCES => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.string_references =
-                    unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.string_references = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_MEMORY_HANDLES => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.memory_handles = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.memory_handles = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_EXTERNALS => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.externals = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.externals = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_NAMED_EXTERNALS => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.named_externals = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.named_externals = runtime_list(ctx, handle, true)?;
             }
             Fixup::RUNTIME_HANDLES => {
-                let vec = read_struct_array_u8(handle, endian, (length - start) as usize).unwrap();
-                ctx.runtime_fields.handles = unpack_compressed_ints(ctx, &vec, count, true);
+                ctx.runtime_fields.handles = runtime_list(ctx, handle, true)?;
             }
             Fixup::OPTION_NAMED_LIST => {
                 dir.use_name_list = true;
-                let name_list_idx = read_u32(handle, endian).unwrap() as u64;
+                let name_list_idx = read_u32(handle, endian).map_err(|e| ctx.io_error(handle, e))? as u64;
                 // pull out the generic object (trait-object)
                 let generic_obj: Arc<RwLock<dyn __internalObjectBase>> =
-                    ctx.offset_object_list[&name_list_idx].clone();
+                    ctx.get_object(handle.position(), name_list_idx)?;
 
                 // assign into your field
-                dir.name_list = generic_obj.cast_to().unwrap();
+                dir.name_list = generic_obj
+                    .cast_to()
+                    .map_err(|e| ctx.error(handle.position(), IgzLoaderErrorKind::InstantiationFailed(e)))?;
             },
             Fixup::METADATA_SIZES => {}
         }
+
+        Ok(())
     }
 }
 
+/// Reads a string from TMET or TSTR, skipping the padding that keeps every string at an even length from version 0x08 onward
+pub(crate) fn read_padded_string(handle: &mut Cursor<Vec<u8>>, version: u32) -> std::io::Result<String> {
+    let base_pos = handle.position();
+    let string = read_string(handle)?;
+
+    let bits: i32 = if version > 7 { 2 } else { 1 };
+    handle.set_position(
+        base_pos
+            + bits as u64
+            + ((handle.position() - base_pos - 1) & ((-bits) as u32) as u64),
+    );
+    Ok(string)
+}
+
+/// Creates every object listed in RVTB. When loading leniently, objects that can't be created are replaced by igNull
 fn instantiate_and_append_objects(
     ctx: &mut IgzLoaderContext,
     handle: &mut Cursor<Vec<u8>>,
     endian: Endian,
-) {
+) -> Result<(), IgzLoaderError> {
     let vtables = ctx.runtime_fields.vtables.clone();
-    
+    let fixup_location = ctx.location.clone();
+
     for vtable in vtables {
-        let obj = instantiate_object(ctx, handle, endian.clone(), &vtable);
+        ctx.location = IgzLoaderLocation::Object(vtable);
+        let obj = match instantiate_object(ctx, handle, endian.clone(), &vtable) {
+            Ok(obj) => obj,
+            Err(kind) => {
+                let error = ctx.error(handle.position(), kind);
+                if !ctx.lenient {
+                    return Err(error);
+                }
+                ctx.warn(error);
+                Arc::new(RwLock::new(igNull))
+            }
+        };
         ctx.offset_object_list
             .insert(vtable, obj);
     }
+
+    ctx.location = fixup_location;
+    Ok(())
 }
 
 fn instantiate_object(
@@ -352,96 +468,99 @@ fn instantiate_object(
     handle: &mut Cursor<Vec<u8>>,
     endian: Endian,
     offset: &u64,
-) -> Arc<RwLock<dyn __internalObjectBase>> {
-    let deserialize_offset = ctx.deserialize_offset(*offset);
-
-    handle.seek(SeekFrom::Start(deserialize_offset)).unwrap();
-    let index = read_ptr(handle, ctx.platform.clone(), endian).unwrap();
-    let return_value = ctx.vtbl_list[index as usize]
-        .clone()
+) -> Result<Arc<RwLock<dyn __internalObjectBase>>, IgzLoaderErrorKind> {
+    let deserialize_offset = ctx.deserialize_offset(*offset)?;
+
+    handle.set_position(deserialize_offset);
+    let index = read_ptr(handle, ctx.platform.clone(), endian)?;
+    let meta = match ctx.vtbl_list.get(index as usize) {
+        Some(Some(meta)) => meta.clone(),
+        Some(None) => {
+            // The type is missing from the metadata, which was already reported by TMET
+            return Ok(Arc::new(RwLock::new(igNull)));
+        }
+        None => return Err(IgzLoaderErrorKind::InvalidVtableIndex(index)),
+    };
+    let return_value = meta
         .read()
         .unwrap()
-        .raw_instantiate(get_mem_pool_from_serialized_offset(ctx, *offset), false);
+        .raw_instantiate(ctx.get_pool_from_serialized_offset(*offset)?, false);
 
-    match return_value {
-        Ok(value) => {
-            value
-        }
-        Err(igMetaInstantiationError::TypeMismatchError(expected_type)) => {
-            error!(
-                "Instantiation when loading IGZ failed the real type returned was {}",
-                expected_type
-            );
-            panic!("Alchemy Error! Check the logs.")
-        }
-        Err(igMetaInstantiationError::SetupDefaultFieldsError) => todo!(),
-    }
+    return_value.map_err(IgzLoaderErrorKind::InstantiationFailed)
 }
 
-fn get_mem_pool_from_serialized_offset(ctx: &IgzLoaderContext, offset: u64) -> igMemoryPool {
-    if ctx.version <= 6 {
-        ctx.loaded_pools[(offset >> 0x18) as usize]
-    } else {
-        ctx.loaded_pools[(offset >> 0x1B) as usize]
-    }
-}
-
-fn unpack_compressed_ints(
+/// Decodes a runtime fixup. When deserialize is set, the serialized offsets are turned into positions in the file. The position is where the fixup ends and is only used for errors
+pub(crate) fn unpack_compressed_ints(
     ctx: &mut IgzLoaderContext,
+    position: u64,
     bytes: &[u8],
     count: u32,
     deserialize: bool,
-) -> Vec<u64> {
+) -> Result<Vec<u64>, IgzLoaderError> {
+    let output = unpack_serialized_ints(ctx.version, bytes, count).map_err(|kind| ctx.error(position, kind))?;
+    if deserialize {
+        output
+            .into_iter()
+            .map(|x| ctx.deserialize_offset(x).map_err(|kind| ctx.error(position, kind)))
+            .collect()
+    } else {
+        Ok(output)
+    }
+}
+
+/// The namespace's string, or its hash when the string isn't known
+fn namespace_label(namespace: &igName) -> String {
+    namespace.string.clone().unwrap_or_else(|| format!("{:#x}", namespace.hash))
+}
+
+/// The size of the data of a fixup, which starts after its header
+pub(crate) fn fixup_data_size(length: u32, start: u32) -> Result<usize, IgzLoaderErrorKind> {
+    match length.checked_sub(start) {
+        Some(size) => Ok(size as usize),
+        None => Err(IgzLoaderErrorKind::Io(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!("fixup data starts at {:#x}, past the end of the fixup at {:#x}", start, length),
+        ))),
+    }
+}
+
+/// Decodes the packed nibbles of a runtime fixup into serialized offsets. The inverse of [pack_compressed_ints](crate::core::save::ig_igz_saver::pack_compressed_ints)
+pub(crate) fn unpack_serialized_ints(version: u32, bytes: &[u8], count: u32) -> Result<Vec<u64>, IgzLoaderErrorKind> {
+    // Every value takes at least one nibble, so a count that doesn't fit is caught before allocating for it
+    if count as usize > bytes.len() * 2 {
+        return Err(IgzLoaderErrorKind::InvalidIndex { list: "packed runtime fixup", index: count as u64 });
+    }
+
     let mut output = Vec::with_capacity(count as usize);
     let mut prev_int: u32 = 0;
-    let mut shift_move_or_mask = false;
-    let mut idx: usize = 0;
+    let mut nibble_index: usize = 0;
+    let mut next_nibble = || {
+        let byte = bytes.get(nibble_index / 2).ok_or(IgzLoaderErrorKind::Io(ErrorKind::UnexpectedEof.into()))?;
+        let nibble = if nibble_index.is_multiple_of(2) { byte & 0xF } else { byte >> 4 };
+        nibble_index += 1;
+        Ok::<u32, IgzLoaderErrorKind>(nibble as u32)
+    };
 
     for _ in 0..count {
-        let mut current = if !shift_move_or_mask {
-            let b = bytes[idx];
-            shift_move_or_mask = true;
-            (b & 0xF) as u32
-        } else {
-            let b = bytes[idx];
-            shift_move_or_mask = false;
-            idx += 1;
-            (b >> 4) as u32
-        };
-
+        let mut current = next_nibble()?;
         let mut unpacked = current & 0x7;
         let mut shift_amount = 3;
 
         while (current & 0x8) != 0 {
-            current = if !shift_move_or_mask {
-                let b = bytes[idx];
-                shift_move_or_mask = true;
-                (b & 0xF) as u32
-            } else {
-                let b = bytes[idx];
-                shift_move_or_mask = false;
-                idx += 1;
-                (b >> 4) as u32
-            };
+            current = next_nibble()?;
             unpacked |= (current & 0x7) << (shift_amount & 0x1F);
             shift_amount += 3;
         }
 
         // delta‑and‑scale, plus version‑dependent bias
         prev_int = prev_int
-            .wrapping_add(unpacked * 4)
-            .wrapping_add(if ctx.version < 9 { 4 } else { 0 });
-
-        let final_val = if deserialize {
-            ctx.deserialize_offset(prev_int as u64)
-        } else {
-            prev_int as u64
-        };
+            .wrapping_add(unpacked.wrapping_mul(4))
+            .wrapping_add(if version < 9 { 4 } else { 0 });
 
-        output.push(final_val);
+        output.push(prev_int as u64);
     }
 
-    output
+    Ok(output)
 }
 
 /// TryFrom<u32>'s implementation here has a conversion table for names of fixups from any igz versioned 7 or above.
@@ -507,6 +626,13 @@ impl igObjectLoader for igIGZObjectLoader {
         file_name.ends_with(".igz") || file_name.ends_with(".bld") || file_name.ends_with(".lng")
     }
 
+    fn can_read_magic(&self, header: &[u8]) -> bool {
+        match header.first_chunk::<4>() {
+            Some(magic) => matches!(u32::from_le_bytes(*magic), IGZ_LITTLE_ENDIAN_MAGIC | IGZ_BIG_ENDIAN_MAGIC),
+            None => false,
+        }
+    }
+
     fn get_name(&self) -> &'static str {
         "Alchemy Platform"
     }
@@ -524,7 +650,8 @@ impl igObjectLoader for igIGZObjectLoader {
         ig_metadata_manager: &mut igMetadataManager,
         dir: &mut igObjectDirectory,
         file_path: &str,
-    ) {
+    ) -> Result<(), LoaderError> {
+        let lenient = ig_object_stream_manager.lenient;
         igIGZLoader::read(
             ig_file_context,
             ig_registry,
@@ -534,13 +661,16 @@ impl igObjectLoader for igIGZObjectLoader {
             dir,
             file_path,
             true,
-        );
+            lenient,
+        )?;
+        Ok(())
     }
 }
 
 pub struct igIGZLoader {}
 
 /// See comment in [IgzLoaderContext]
+#[derive(Debug, Clone, Serialize)]
 pub struct RuntimeFields {
     pub vtables: Vec<u64>,
     pub object_lists: Vec<u64>,
@@ -555,7 +685,7 @@ pub struct RuntimeFields {
 }
 
 impl RuntimeFields {
-    fn new() -> RuntimeFields {
+    pub(crate) fn new() -> RuntimeFields {
         RuntimeFields {
             vtables: vec![],
             object_lists: vec![],
@@ -579,6 +709,8 @@ pub struct IgzLoaderContext {
     pub meta_object_version: u32,
     /// platform the igz targets
     pub platform: IG_CORE_PLATFORM,
+    /// endianness of the igz, worked out from the magic value
+    pub endian: Endian,
     /// The amount of sections present in an igz
     pub section_count: u32,
     /// amount of fixups present
@@ -589,8 +721,8 @@ pub struct IgzLoaderContext {
     pub loaded_pointers: [u32; 0x20],
     /// Offset where fixup's are present
     pub fixup_offset: u32,
-    /// A list of all igObject instances present inside the igz
-    pub vtbl_list: Vec<Arc<RwLock<igMetaObject>>>,
+    /// A list of all igObject instances present inside the igz. Types without metadata are [None] when loading leniently
+    pub vtbl_list: Vec<Option<Arc<RwLock<igMetaObject>>>>,
     /// A list of all strings present inside the igz
     pub string_list: Vec<String>,
     /// A list of all external ig object dependencies needed that don't get names
@@ -607,24 +739,76 @@ pub struct IgzLoaderContext {
     pub runtime_fields: RuntimeFields,
     /// TODO: comment
     pub offset_object_list: HashMap<u64, igObject>,
+    /// Path of the igz being loaded
+    pub path: String,
+    /// The part of the igz currently being read. Used to say where an [IgzLoaderError] came from
+    pub location: IgzLoaderLocation,
+    /// When set, objects and fields that fail to load are replaced by igNull and put in [IgzLoaderContext::warnings] instead of failing the igz
+    pub lenient: bool,
+    /// Problems skipped over so far. Becomes the [LoadReport](crate::core::load::ig_loader::LoadReport) of the directory
+    pub warnings: Vec<LoaderError>,
 }
 
 impl IgzLoaderContext {
-    pub fn deserialize_offset(&self, offset: u64) -> u64 {
+    pub fn deserialize_offset(&self, offset: u64) -> Result<u64, IgzLoaderErrorKind> {
+        let section = self.get_section_from_serialized_offset(offset)?;
         if self.version <= 6 {
-            self.loaded_pointers[(offset >> 0x18) as usize] as u64 + (offset & 0x00FFFFFF)
+            Ok(self.loaded_pointers[section] as u64 + (offset & 0x00FFFFFF))
         } else {
-            self.loaded_pointers[(offset >> 0x1B) as usize] as u64 + (offset & 0x07FFFFFF)
+            Ok(self.loaded_pointers[section] as u64 + (offset & 0x07FFFFFF))
         }
     }
 
-    pub fn get_pool_from_serialized_offset(&self, offset: u64) -> igMemoryPool {
-        if self.version <= 6 {
-            self.loaded_pools[(offset >> 0x18) as usize]
+    pub fn get_pool_from_serialized_offset(&self, offset: u64) -> Result<igMemoryPool, IgzLoaderErrorKind> {
+        Ok(self.loaded_pools[self.get_section_from_serialized_offset(offset)?])
+    }
+
+    /// Returns the index into [IgzLoaderContext::loaded_pools] of the section a serialized offset points into
+    fn get_section_from_serialized_offset(&self, offset: u64) -> Result<usize, IgzLoaderErrorKind> {
+        let section = if self.version <= 6 { offset >> 0x18 } else { offset >> 0x1B } as usize;
+        // section_count includes the fixup section, which never gets a pool
+        if section + 1 < self.section_count as usize {
+            Ok(section)
         } else {
-            self.loaded_pools[(offset >> 0x1B) as usize]
+            Err(IgzLoaderErrorKind::MissingSection(offset))
+        }
+    }
+
+    /// Returns the object created from the vtable at the serialized offset given
+    pub fn get_object(&self, position: u64, offset: u64) -> Result<igObject, IgzLoaderError> {
+        self.offset_object_list
+            .get(&offset)
+            .cloned()
+            .ok_or_else(|| self.error(position, IgzLoaderErrorKind::MissingObject(offset)))
+    }
+
+    /// Returns the string at the index given in TSTR
+    pub fn get_string(&self, position: u64, index: u64) -> Result<String, IgzLoaderError> {
+        self.string_list
+            .get(index as usize)
+            .cloned()
+            .ok_or_else(|| self.error(position, IgzLoaderErrorKind::InvalidIndex { list: "string list", index }))
+    }
+
+    /// Builds an [IgzLoaderError] for the part of the igz currently being read
+    pub fn error(&self, offset: u64, kind: IgzLoaderErrorKind) -> IgzLoaderError {
+        IgzLoaderError {
+            path: self.path.clone(),
+            location: self.location.clone(),
+            offset,
+            kind,
         }
     }
+
+    fn io_error(&self, handle: &Cursor<Vec<u8>>, error: std::io::Error) -> IgzLoaderError {
+        self.error(handle.position(), IgzLoaderErrorKind::Io(error))
+    }
+
+    /// Records a problem that was skipped over while loading leniently
+    pub fn warn(&mut self, error: IgzLoaderError) {
+        warn!("Skipping over a broken part of an igz: {}", error);
+        self.warnings.push(LoaderError::Igz(error));
+    }
 }
 
 impl igIGZLoader {
@@ -637,113 +821,154 @@ impl igIGZLoader {
         dir: &mut igObjectDirectory,
         file_path: &str,
         read_dependencies: bool,
-    ) {
+        lenient: bool,
+    ) -> Result<(), IgzLoaderError> {
         let mut fd = ig_file_context.open(ig_registry, file_path, 0);
-        if let Some(mut handle) = fd._handle {
-            // if file_path == "packages/generated/packagexmls/permanent_pkg.igz" {
-            //     use std::io::Read;
-            //     use byteorder::WriteBytesExt;
-            //     let old_pos = handle.position();
-            //     let mut file = std::fs::File::create("file.igz").unwrap();
-            //     for byte in handle.bytes() {
-            //         file.write_u8(byte.unwrap()).unwrap();
-            //     }
-            //     todo!("file.igz saved");
-            // }
-
-            let magic = read_u32(&mut handle, Little).unwrap();
-            match magic {
-                IGZ_BIG_ENDIAN_MAGIC => fd.endianness = Big,
-                IGZ_LITTLE_ENDIAN_MAGIC => fd.endianness = Little,
-                _ => {
-                    error!(
-                        "Failed to load igz {}. Magic value was wrong. Got: {}",
-                        file_path, magic
-                    );
-                    panic!("Alchemy Error! Check the logs.")
-                }
-            }
-
-            let version = read_u32(&mut handle, fd.endianness.clone()).unwrap();
-            let meta_object_version = read_u32(&mut handle, fd.endianness.clone()).unwrap();
-            let platform = imm.get_enum::<IG_CORE_PLATFORM>(
-                read_u32(&mut handle, fd.endianness.clone()).unwrap() as usize,
-            );
+        let Some(mut handle) = fd._handle else {
+            error!("Failed to load igz {}. File could not be read.", file_path);
+            return Err(IgzLoaderError {
+                path: file_path.to_string(),
+                location: IgzLoaderLocation::Header,
+                offset: 0,
+                kind: IgzLoaderErrorKind::FileUnreadable,
+            });
+        };
+        // if file_path == "packages/generated/packagexmls/permanent_pkg.igz" {
+        //     use std::io::Read;
+        //     use byteorder::WriteBytesExt;
+        //     let old_pos = handle.position();
+        //     let mut file = std::fs::File::create("file.igz").unwrap();
+        //     for byte in handle.bytes() {
+        //         file.write_u8(byte.unwrap()).unwrap();
+        //     }
+        //     todo!("file.igz saved");
+        // }
+
+        let mut shared_state = igIGZLoader::read_header(&mut handle, imm, file_path)?;
+        fd.endianness = shared_state.endian.clone();
+        shared_state.read_dependencies = read_dependencies;
+        shared_state.lenient = lenient;
+
+        igIGZLoader::parse_sections(&mut handle, fd.endianness.clone(), &mut shared_state)?;
+        if shared_state.version > 0x06 {
+            igIGZLoader::process_modern_fixup_sections(
+                &mut handle,
+                fd.endianness.clone(),
+                &mut shared_state,
+                ig_file_context,
+                ig_registry,
+                ig_object_stream_manager,
+                ig_ext_ref_system,
+                imm,
+                dir,
+            )?;
+        } else {
+            igIGZLoader::process_legacy_fixup_sections(
+                &mut handle,
+                fd.endianness.clone(),
+                &mut shared_state,
+                ig_file_context,
+                ig_registry,
+                ig_object_stream_manager,
+                ig_ext_ref_system,
+                imm,
+                dir,
+            )?;
+        }
 
-            let mut fixup_count = 0; // Older IGZ versions rely on you grabbing this information later on at the first section's offset (usually 2048 from what I've seen) + 0x10
+        igIGZLoader::read_objects(imm, ig_object_stream_manager, &mut handle, fd.endianness.clone(), &mut shared_state)?;
+        dir.load_report.warnings.append(&mut shared_state.warnings);
+        Ok(())
+    }
 
-            if version >= 0x07 {
-                // TODO: verify 0x07 acts like this as well. I know 0x08 does
-                fixup_count = read_u32(&mut handle, fd.endianness.clone()).unwrap();
-            }
+    /// Reads the igz header and sets up the context used by the rest of the loading process. Dependencies are read and loading is strict until told otherwise
+    pub(crate) fn read_header(
+        handle: &mut Cursor<Vec<u8>>,
+        imm: &igMetadataManager,
+        file_path: &str,
+    ) -> Result<IgzLoaderContext, IgzLoaderError> {
+        let header_error = |offset: u64, kind: IgzLoaderErrorKind| IgzLoaderError {
+            path: file_path.to_string(),
+            location: IgzLoaderLocation::Header,
+            offset,
+            kind,
+        };
 
-            let mut shared_state = IgzLoaderContext {
-                version,
-                meta_object_version,
-                platform,
-                section_count: 0,
-                fixup_count,
-                loaded_pools: Default::default(),
-                loaded_pointers: Default::default(),
-                fixup_offset: 0,
-                vtbl_list: vec![],
-                string_list: vec![],
-                external_list: vec![],
-                named_external_list: vec![],
-                named_handle_list: vec![],
-                read_dependencies,
-                thumbnails: vec![],
-                runtime_fields: RuntimeFields::new(),
-                offset_object_list: HashMap::new(),
-            };
-
-            igIGZLoader::parse_sections(&mut handle, fd.endianness.clone(), &mut shared_state);
-            if shared_state.version > 0x06 {
-                igIGZLoader::process_modern_fixup_sections(
-                    &mut handle,
-                    fd.endianness.clone(),
-                    &mut shared_state,
-                    ig_file_context,
-                    ig_registry,
-                    ig_object_stream_manager,
-                    ig_ext_ref_system,
-                    imm,
-                    dir,
-                );
-            } else {
-                igIGZLoader::process_legacy_fixup_sections(
-                    &mut handle,
-                    fd.endianness.clone(),
-                    &mut shared_state,
-                    ig_file_context,
-                    ig_registry,
-                    ig_object_stream_manager,
-                    ig_ext_ref_system,
-                    imm,
-                    dir,
+        let magic = read_u32(handle, Little).map_err(|e| header_error(0, e.into()))?;
+        let endian = match magic {
+            IGZ_BIG_ENDIAN_MAGIC => Big,
+            IGZ_LITTLE_ENDIAN_MAGIC => Little,
+            _ => {
+                error!(
+                    "Failed to load igz {}. Magic value was wrong. Got: {}",
+                    file_path, magic
                 );
+                return Err(header_error(0, IgzLoaderErrorKind::InvalidMagic(magic)));
             }
+        };
 
-            igIGZLoader::read_objects(imm, ig_object_stream_manager, &mut handle, fd.endianness.clone(), &mut shared_state);
-        } else {
-            error!("Failed to load igz {}. File could not be read.", file_path);
-            panic!("Alchemy Error! Check the logs.")
+        let version = read_u32(handle, endian.clone()).map_err(|e| header_error(handle.position(), e.into()))?;
+        if !matches!(version, 0x05 | 0x06 | 0x08 | 0x09) {
+            return Err(header_error(0x4, IgzLoaderErrorKind::UnsupportedVersion(version)));
+        }
+        let meta_object_version = read_u32(handle, endian.clone()).map_err(|e| header_error(handle.position(), e.into()))?;
+        let platform_index = read_u32(handle, endian.clone()).map_err(|e| header_error(handle.position(), e.into()))?;
+        let platform = imm
+            .try_get_enum::<IG_CORE_PLATFORM>(platform_index as usize)
+            .ok_or_else(|| header_error(0xC, IgzLoaderErrorKind::UnknownPlatform(platform_index)))?;
+
+        let mut fixup_count = 0; // Older IGZ versions rely on you grabbing this information later on at the first section's offset (usually 2048 from what I've seen) + 0x10
+
+        if version >= 0x07 {
+            // TODO: verify 0x07 acts like this as well. I know 0x08 does
+            fixup_count = read_u32(handle, endian.clone()).map_err(|e| header_error(handle.position(), e.into()))?;
         }
+
+        Ok(IgzLoaderContext {
+            version,
+            meta_object_version,
+            platform,
+            endian,
+            section_count: 0,
+            fixup_count,
+            loaded_pools: Default::default(),
+            loaded_pointers: Default::default(),
+            fixup_offset: 0,
+            vtbl_list: vec![],
+            string_list: vec![],
+            external_list: vec![],
+            named_external_list: vec![],
+            named_handle_list: vec![],
+            read_dependencies: true,
+            thumbnails: vec![],
+            runtime_fields: RuntimeFields::new(),
+            offset_object_list: HashMap::new(),
+            path: file_path.to_string(),
+            location: IgzLoaderLocation::Header,
+            lenient: false,
+            warnings: vec![],
+        })
     }
 
-    fn parse_sections(
+    pub(crate) fn parse_sections(
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         shared_state: &mut IgzLoaderContext,
-    ) {
+    ) -> Result<(), IgzLoaderError> {
+        // When every descriptor is used there is no empty one to stop at
+        shared_state.section_count = 0x20;
+        let descriptor_start = get_chunk_descriptor_start(shared_state.version).map_err(|kind| shared_state.error(0x4, kind))?;
+        let attribute_location = get_attribute_location(shared_state.version).map_err(|kind| shared_state.error(0x4, kind))?;
+
         for i in 0..0x20 {
-            handle.seek(SeekFrom::Start(get_chunk_descriptor_start(shared_state.version) + 0x10 * i)).unwrap();
-            let mem_pool_name_ptr = read_u32(handle, endian.clone()).unwrap();
+            shared_state.location = IgzLoaderLocation::Section(i as u32);
+            handle.set_position(descriptor_start + 0x10 * i);
+            let mem_pool_name_ptr = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
             let offset;
 
-            offset = read_u32(handle, endian.clone()).unwrap();
-            let _length = read_u32(handle, endian.clone()).unwrap();
-            let _alignment = read_u32(handle, endian.clone()).unwrap();
+            offset = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let _length = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let _alignment = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
 
             if offset == 0 {
                 shared_state.section_count = i as u32;
@@ -751,25 +976,28 @@ impl igIGZLoader {
             }
 
             if i == 0 && shared_state.version <= 0x06 {
-                // Giants and under don't store the fixup count in the header but in this weird second IGZ header area. TODO: find out if this applies to version 0x07(SSF)
+                // Giants and under don't store the fixup count in the header but in this weird second IGZ header area.
                 handle.set_position((offset + 0x10) as u64); // We don't care about storing the old position because the next code will just seek again anyway
-                shared_state.fixup_count = read_u32(handle, endian.clone()).unwrap()
+                shared_state.fixup_count = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?
             }
 
-            handle
-                .seek(SeekFrom::Start((get_attribute_location(shared_state.version) + mem_pool_name_ptr) as u64))
-                .unwrap();
-            let memory_pool_name = read_string(handle).unwrap();
+            handle.set_position((attribute_location + mem_pool_name_ptr) as u64);
+            let memory_pool_name = read_string(handle).map_err(|e| shared_state.io_error(handle, e))?;
             if i > 0 {
                 shared_state.loaded_pools[(i - 1) as usize] =
-                    igMemoryPool::from_str(&memory_pool_name).unwrap_or_else(|_| {
-                        panic!("Invalid memory pool name '{}'", memory_pool_name)
-                    });
+                    igMemoryPool::from_str(&memory_pool_name).map_err(|_| {
+                        shared_state.error(
+                            (attribute_location + mem_pool_name_ptr) as u64,
+                            IgzLoaderErrorKind::InvalidMemoryPool(memory_pool_name.clone()),
+                        )
+                    })?;
                 shared_state.loaded_pointers[(i - 1) as usize] = offset;
             } else {
                 shared_state.fixup_offset = offset;
             }
         }
+
+        Ok(())
     }
 
     /// This function handles the older style of fixup used in IGZ versions 0x06 (Giants/SSA Wii) and below. It is handled quite differently so in the end its just better do keep it separate.
@@ -783,23 +1011,25 @@ impl igIGZLoader {
         ig_ext_ref_system: &mut igExternalReferenceSystem,
         imm: &mut igMetadataManager,
         dir: &mut igObjectDirectory,
-    ) {
+    ) -> Result<(), IgzLoaderError> {
         // if you really care you might(not confirmed to be correct but seems to be) be able to find this value at fixup[0]'s offset + 0xC (u32)
         let mut bytes_processed = 0x1C;
 
         for _i in 0..shared_state.fixup_count {
             handle.set_position((shared_state.fixup_offset + bytes_processed) as u64);
-            let magic = read_u32(handle, endian.clone()).unwrap() as u8;
-            let _padding = read_u32(handle, endian.clone()).unwrap();
-            let _padding = read_u32(handle, endian.clone()).unwrap();
-            let count = read_u32(handle, endian.clone()).unwrap();
-            let length = read_u32(handle, endian.clone()).unwrap();
-            let start = read_u32(handle, endian.clone()).unwrap();
+            shared_state.location = IgzLoaderLocation::Section(0);
+            let magic = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))? as u8;
+            let _padding = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let _padding = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let count = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let length = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let start = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
             let fixup = Fixup::try_from(magic);
             handle.set_position((shared_state.fixup_offset + bytes_processed + start) as u64);
 
             if let Ok(fixup) = fixup {
                 debug!("Processing {:?}",fixup);
+                shared_state.location = IgzLoaderLocation::Fixup(format!("{:#04x}", magic));
                 fixup.fix(
                     handle,
                     endian.clone(),
@@ -813,7 +1043,7 @@ impl igIGZLoader {
                     ig_object_stream_manager,
                     ig_ext_ref_system,
                     shared_state,
-                );
+                )?;
             } else {
                 debug!(
                     "No fixup exists for the magic value {}",
@@ -823,6 +1053,8 @@ impl igIGZLoader {
 
             bytes_processed += length;
         }
+
+        Ok(())
     }
 
     fn process_modern_fixup_sections(
@@ -835,20 +1067,17 @@ impl igIGZLoader {
         ig_ext_ref_system: &mut igExternalReferenceSystem,
         imm: &mut igMetadataManager,
         dir: &mut igObjectDirectory,
-    ) {
+    ) -> Result<(), IgzLoaderError> {
         let mut bytes_processed = 0;
 
         for _i in 0..shared_state.fixup_count {
             handle.set_position((shared_state.fixup_offset + bytes_processed) as u64);
-            let magic = read_u32(handle, endian.clone()).unwrap();
-            let count = read_u32(handle, endian.clone()).unwrap();
-            let length = read_u32(handle, endian.clone()).unwrap();
-            let start = read_u32(handle, endian.clone()).unwrap();
-            handle
-                .seek(SeekFrom::Start(
-                    (shared_state.fixup_offset + bytes_processed + start) as u64,
-                ))
-                .unwrap();
+            shared_state.location = IgzLoaderLocation::Section(0);
+            let magic = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let count = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let length = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            let start = read_u32(handle, endian.clone()).map_err(|e| shared_state.io_error(handle, e))?;
+            handle.set_position((shared_state.fixup_offset + bytes_processed + start) as u64);
 
             let fixup = Fixup::try_from(magic);
             if let Ok(fixup) = fixup {
@@ -857,6 +1086,7 @@ impl igIGZLoader {
                     "Processing {}",
                     String::from_utf8_lossy(&magic.to_le_bytes())
                 );
+                shared_state.location = IgzLoaderLocation::Fixup(String::from_utf8_lossy(&magic.to_le_bytes()).to_string());
                 fixup.fix(
                     handle,
                     endian.clone(),
@@ -870,7 +1100,7 @@ impl igIGZLoader {
                     ig_object_stream_manager,
                     ig_ext_ref_system,
                     shared_state,
-                );
+                )?;
             } else {
                 debug!(
                     "No fixup exists for the magic value {}",
@@ -880,6 +1110,8 @@ impl igIGZLoader {
 
             bytes_processed += length;
         }
+
+        Ok(())
     }
 
     fn read_objects(
@@ -888,28 +1120,40 @@ impl igIGZLoader {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgzLoaderContext,
-    ) {
+    ) -> Result<(), IgzLoaderError> {
         let offset_object_list = ctx.offset_object_list.clone();
         
         for (offset, object) in offset_object_list {
-            handle.set_position(ctx.deserialize_offset(offset));
-            imm.read_igz_fields(object_stream_manager, handle, endian.clone(), ctx, object.clone())
+            if object.read().unwrap().as_any().is::<igNull>() {
+                // Stand-in for an object that failed to instantiate, there is nothing to read into
+                continue;
+            }
+
+            ctx.location = IgzLoaderLocation::Object(offset);
+            let position = ctx.deserialize_offset(offset).map_err(|kind| ctx.error(handle.position(), kind))?;
+            handle.set_position(position);
+            imm.read_igz_fields(object_stream_manager, handle, endian.clone(), ctx, object.clone())?
         }
+
+        Ok(())
     }
 }
 
-fn get_chunk_descriptor_start(version: u32) -> u64 {
+/// Version 0x08 (Trap Team) shares the header layout of 0x09. What sets it apart is in the fixups: every packed offset is biased by 4 before 0x09.
+/// 0x07 (Swap Force) is refused until its layout has been checked against real files
+pub(crate) fn get_chunk_descriptor_start(version: u32) -> Result<u64, IgzLoaderErrorKind> {
     match version {
-        0x05 | 0x06 => 0xC,
-        0x09 => 0x14,
-        _ => todo!("Unsupported igz version {}", version)
+        0x05 | 0x06 => Ok(0xC),
+        0x08 | 0x09 => Ok(0x14),
+        _ => Err(IgzLoaderErrorKind::UnsupportedVersion(version)),
     }
 }
 
-fn get_attribute_location(version: u32) -> u32 {
+/// Where the names of the memory pools the sections are in start, each chunk descriptor points to its name relative to this
+pub(crate) fn get_attribute_location(version: u32) -> Result<u32, IgzLoaderErrorKind> {
     match version {
-        0x05 | 0x06 => 0x56C,
-        0x09 => 0x224,
-        _ => todo!("Unsupported igz version {}", version)
+        0x05 | 0x06 => Ok(0x56C),
+        0x08 | 0x09 => Ok(0x224),
+        _ => Err(IgzLoaderErrorKind::UnsupportedVersion(version)),
     }
 }
@@ -1,26 +1,35 @@
-use crate::core::ig_file_context::igFileContext;
+use crate::core::ig_file_context::{igFileContext, OPEN_FLAG_HEADER_ONLY, OPEN_FLAG_MEMORY_MAP};
 use crate::core::ig_objects::{igObjectDirectory, igObjectStreamManager};
 use crate::core::ig_registry::igRegistry;
-use crate::core::load::ig_igz_loader::igIGZObjectLoader;
+use crate::core::load::ig_igb_loader::{igIGBObjectLoader, IgbLoaderError};
+use crate::core::load::ig_igx_loader::{igIGXObjectLoader, IgxLoaderError};
+use crate::core::load::ig_igz_loader::{igIGZObjectLoader, IgzLoaderError};
 use crate::core::meta::ig_metadata_manager::igMetadataManager;
-use once_cell::sync::Lazy;
+use std::fmt::{Display, Formatter};
 use std::sync::{Arc, RwLock};
 use crate::core::ig_external_ref::igExternalReferenceSystem;
 
-static LOADERS: Lazy<[Arc<RwLock<dyn igObjectLoader>>; 1]> =
-    Lazy::new(|| [Arc::new(RwLock::new(igIGZObjectLoader))]);
+/// How many bytes from the start of a file are handed to [igObjectLoader::can_read_magic]
+pub const MAGIC_PEEK_SIZE: usize = 0x80;
 
 /// The shared base between anything that can load an alchemy binary (igz, igx, igb)
 pub trait igObjectLoader: Send + Sync {
-    /// Returns true if the loader can load the specified file
+    /// Returns true if the loader can load the specified file, going by its extension
     fn can_read(&self, file_name: &str) -> bool;
 
+    /// Returns true if the start of a file is in the format of this loader. Only the first [MAGIC_PEEK_SIZE] bytes are given, fewer if the file is smaller.
+    /// Loaders that can't tell their files apart by content can leave this as is and be picked by [igObjectLoader::can_read] alone
+    fn can_read_magic(&self, _header: &[u8]) -> bool {
+        false
+    }
+
     /// Internal name of the loader.
     fn get_name(&self) -> &'static str;
 
     /// The provider of the loader. For the built-in loaders of alchemy, this will usually be "Alchemy"
     fn get_type(&self) -> &'static str;
 
+    /// Reads the file into the directory. When [igObjectStreamManager::lenient] is set, problems with single objects are collected into [igObjectDirectory::load_report] instead of failing the whole file
     fn read_file(
         &self,
         ig_file_context: &igFileContext,
@@ -30,17 +39,139 @@ pub trait igObjectLoader: Send + Sync {
         ig_metadata_manager: &mut igMetadataManager,
         dir: &mut igObjectDirectory,
         file_path: &str,
-    );
+    ) -> Result<(), LoaderError>;
+}
+
+/// Describes why a loader could not read a file
+#[derive(Debug)]
+pub enum LoaderError {
+    /// Returned when an igz could not be read
+    Igz(IgzLoaderError),
+    /// Returned when an igx could not be read
+    Igx(IgxLoaderError),
+    /// Returned when an igb could not be read
+    Igb(IgbLoaderError),
+    /// Returned by loaders registered outside of ig-library, see [igObjectLoaderRegistry::register]
+    Custom(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Display for LoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::Igz(e) => write!(f, "{}", e),
+            LoaderError::Igx(e) => write!(f, "{}", e),
+            LoaderError::Igb(e) => write!(f, "{}", e),
+            LoaderError::Custom(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+impl From<IgzLoaderError> for LoaderError {
+    fn from(value: IgzLoaderError) -> Self {
+        LoaderError::Igz(value)
+    }
+}
+
+impl From<IgxLoaderError> for LoaderError {
+    fn from(value: IgxLoaderError) -> Self {
+        LoaderError::Igx(value)
+    }
+}
+
+impl From<IgbLoaderError> for LoaderError {
+    fn from(value: IgbLoaderError) -> Self {
+        LoaderError::Igb(value)
+    }
+}
+
+/// Everything that went wrong while loading a directory without stopping it from loading
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// Problems that were skipped over, in the order they were found
+    pub warnings: Vec<LoaderError>,
+}
+
+impl LoadReport {
+    /// Returns true if the directory loaded without any problems
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// What made the [igObjectLoaderRegistry] pick a loader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoaderMatch {
+    /// The loader recognised the start of the file
+    Magic,
+    /// No loader recognised the contents, but the loader claims the file extension
+    Extension,
+}
+
+/// The loader picked for a file and why it was picked
+#[derive(Clone)]
+pub struct LoaderChoice {
+    pub loader: Arc<RwLock<dyn igObjectLoader>>,
+    pub matched_by: LoaderMatch,
 }
 
-pub fn get_loader(file_path: &str) -> Option<Arc<RwLock<dyn igObjectLoader>>> {
-    for loader in LOADERS.iter() {
-        let loader_guard = loader.read().unwrap();
-        if loader_guard.can_read(file_path) {
-            drop(loader_guard);
-            return Some(loader.clone());
+/// Every loader the [igObjectStreamManager] can pick from when loading a file. Owned by [igAlchemy](crate::util::ig_common::igAlchemy) so custom formats can be added at runtime without changes to ig-library
+pub struct igObjectLoaderRegistry {
+    loaders: Vec<Arc<RwLock<dyn igObjectLoader>>>,
+}
+
+impl igObjectLoaderRegistry {
+    /// Creates a registry with the built-in igz, igx and igb loaders
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Arc::new(RwLock::new(igIGBObjectLoader)));
+        registry.register(Arc::new(RwLock::new(igIGXObjectLoader)));
+        registry.register(Arc::new(RwLock::new(igIGZObjectLoader)));
+        registry
+    }
+
+    /// Creates a registry without any loaders
+    pub fn empty() -> Self {
+        igObjectLoaderRegistry { loaders: vec![] }
+    }
+
+    /// Adds a loader. Loaders registered last are asked first, which lets a custom loader take over files a built-in loader would also claim
+    pub fn register(&mut self, loader: Arc<RwLock<dyn igObjectLoader>>) {
+        self.loaders.insert(0, loader);
+    }
+
+    /// Every registered loader, in the order they are asked
+    pub fn loaders(&self) -> &[Arc<RwLock<dyn igObjectLoader>>] {
+        &self.loaders
+    }
+
+    /// Picks a loader for a file. Loaders that recognise the header are preferred over ones that only claim the extension, as extensions are often reused between formats
+    pub fn find(&self, file_path: &str, header: &[u8]) -> Option<LoaderChoice> {
+        let by_magic = self.loaders.iter().find(|loader| loader.read().unwrap().can_read_magic(header));
+        if let Some(loader) = by_magic {
+            return Some(LoaderChoice { loader: loader.clone(), matched_by: LoaderMatch::Magic });
         }
+
+        let by_extension = self.loaders.iter().find(|loader| loader.read().unwrap().can_read(file_path));
+        by_extension.map(|loader| LoaderChoice { loader: loader.clone(), matched_by: LoaderMatch::Extension })
     }
 
-    None
+    /// Reads the start of a file and picks a loader for it. See [igObjectLoaderRegistry::find]
+    pub fn detect(&self, ig_file_context: &igFileContext, ig_registry: &igRegistry, file_path: &str) -> Option<LoaderChoice> {
+        // Avoids reading or decompressing the whole file just to look at the start of it, on devices that support it
+        let fd = ig_file_context.open(ig_registry, file_path, OPEN_FLAG_MEMORY_MAP | OPEN_FLAG_HEADER_ONLY);
+        let header = match (&fd._mapped, &fd._handle) {
+            (Some(mapped), _) => &mapped[..mapped.len().min(MAGIC_PEEK_SIZE)],
+            (None, Some(handle)) => &handle.get_ref()[..handle.get_ref().len().min(MAGIC_PEEK_SIZE)],
+            (None, None) => &[][..],
+        };
+        self.find(file_path, header)
+    }
+}
+
+impl Default for igObjectLoaderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -1,4 +1,5 @@
 pub mod ig_loader;
 pub mod ig_igz_loader;
+pub mod ig_igz_dump;
 pub mod ig_igx_loader;
 pub mod ig_igb_loader;
\ No newline at end of file
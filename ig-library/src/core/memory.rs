@@ -1,6 +1,10 @@
 use crate::core::ig_core_platform::IG_CORE_PLATFORM;
 use crate::core::ig_memory::igMemoryPool;
 
+/// Stored in place of an element of an [igMemory] that is null, such as an empty slot of an igHashTable keyed by strings. Elements are shared as [igAny](crate::core::ig_objects::igAny) with whatever owns them, so there is no [None] to store instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct igNullElement;
+
 #[allow(dead_code)] // TODO: i need to look into this more. it seems cauldron's implementation (what this is based on) isn't completely finished and leaves a few things out... Will check in ghidra later hopefully
 pub struct igMemory<T> where T: 'static + Send + Sync {
     pub data: Vec<T>,
@@ -33,6 +37,17 @@ where
         self.alignment_multiple = alignment / metafield_alignment as u32;
         self.data = Vec::with_capacity(size as usize / metafield_size)
     }
+
+    /// The inverse of [igMemory::set_flags]. Packs the size in bytes, the alignment and the cpu flag into the flags stored in front of the memory
+    pub fn get_flags(&self, size: u64, alignment: u32, platform: IG_CORE_PLATFORM) -> u64 {
+        let alignment_bits = (alignment.max(4).trailing_zeros() - 2) as u64;
+
+        if platform.is_64bit() {
+            size | (alignment_bits << 0x3B) | ((self.optimal_cpuread_write as u64) << 0x3F)
+        } else {
+            size | (alignment_bits << 0x1B) | ((self.optimal_cpuread_write as u64) << 0x1F)
+        }
+    }
 }
 
 impl<T> igMemory<T>
@@ -1,8 +1,8 @@
 use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::{igAny, igObjectStreamManager};
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::ig_metadata_manager::igMetadataManager;
 use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
@@ -14,7 +14,7 @@ use std::io::Cursor;
 pub trait igMetaField: Send + Sync {
     fn type_id(&self) -> std::any::TypeId;
 
-    /// Takes a value in an igz and will convert it into <T>. Will return [None] when the read value is "null"
+    /// Takes a value in an igz and will convert it into <T>. Will return [None] when the read value is "null". If the value couldn't be read, the type [IgzLoaderErrorKind] will be returned and the caller adds where in the igz it happened
     fn value_from_igz(
         &self,
         registry: &igMetafieldRegistry,
@@ -23,8 +23,9 @@ pub trait igMetaField: Send + Sync {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgzLoaderContext,
-    ) -> Option<igAny>;
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind>;
     /// Accepts a value of type <T> and will return [Ok] if successful. If an error occurred, the type [IgzSaverError] will be returned hopefully containing useful information for debugging
+    #[allow(clippy::too_many_arguments)]
     fn value_into_igz(
         &self,
         registry: &igMetafieldRegistry,
@@ -33,30 +34,30 @@ pub trait igMetaField: Send + Sync {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgzSaverError>;
 
-    /// Takes a value in an igx and will convert it into <T>. Will return [None] when the read value is "null"
+    /// Takes the element of a value in an igx and will convert it into <T>. Will return [None] when the read value is "null". Elements marked null are handled by the caller and never reach the metafield
     fn value_from_igx(
         &self,
         registry: &igMetafieldRegistry,
         metadata_manager: &igMetadataManager,
         object_stream_manager: &igObjectStreamManager,
-        handle: &mut Cursor<Vec<u8>>,
-        endian: Endian,
+        node: &IgxNode,
         ctx: &mut IgxLoaderContext,
-    ) -> Option<igAny>;
-    /// Accepts a value of type <T> and will return [Ok] if successful. If an error occurred, the type [IgxSaverError] will be returned hopefully containing useful information for debugging
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind>;
+    /// Accepts a value of type <T> and writes it into the element given, returning [Ok] if successful. "null" values are marked by the caller instead. If an error occurred, the type [IgxSaverError] will be returned hopefully containing useful information for debugging
     fn value_into_igx(
         &self,
         registry: &igMetafieldRegistry,
         metadata_manager: &igMetadataManager,
         object_stream_manager: &igObjectStreamManager,
-        handle: &mut Cursor<Vec<u8>>,
-        endian: Endian,
+        node: &mut IgxNode,
         ctx: &mut IgxSaverContext,
+        value: igAny,
     ) -> Result<(), IgxSaverError>;
 
-    /// Takes a value in an igb and will convert it into <T>. Will return [None] when the read value is "null"
+    /// Takes a value in an igb and will convert it into <T>. Will return [None] when the read value is "null". Values in an igb are stored one after the other, so the handle has to be left at the end of what was read. If the value couldn't be read, the type [IgbLoaderErrorKind] will be returned and the caller adds where in the igb it happened
     fn value_from_igb(
         &self,
         registry: &igMetafieldRegistry,
@@ -65,8 +66,9 @@ pub trait igMetaField: Send + Sync {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgbLoaderContext,
-    ) -> Option<igAny>;
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind>;
     /// Accepts a value of type <T> and will return [Ok] if successful. If an error occurred, the type [IgbSaverError] will be returned hopefully containing useful information for debugging
+    #[allow(clippy::too_many_arguments)]
     fn value_into_igb(
         &self,
         registry: &igMetafieldRegistry,
@@ -75,5 +77,6 @@ pub trait igMetaField: Send + Sync {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgbSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgbSaverError>;
 }
@@ -0,0 +1,185 @@
+use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_objects::{igAny, igObjectStreamManager};
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
+use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
+use crate::core::meta::field::ig_metafields::igMetaField;
+use crate::core::meta::ig_metadata_manager::{igMetaFieldInfo, igMetadataManager};
+use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
+use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
+use std::any::TypeId;
+use std::io::Cursor;
+use std::sync::{Arc, RwLock};
+
+/// A fixed amount of elements stored inline in an object, one after the other. Every "*ArrayMetaField" is one of these, with the element read by the metafield of the same name without "Array" (igIntArrayMetaField holds igIntMetaField elements).
+/// The value is a [Vec] of the elements, where [None] is a null element
+pub(crate) struct igArrayMetaField {
+    element: Arc<dyn igMetaField>,
+    count: usize,
+    /// Distance between the start of two elements. The element size padded to its alignment
+    stride: u64,
+}
+
+impl igArrayMetaField {
+    /// Used as the factory when registering every "*ArrayMetaField" type. The size of the field covers every element, so the size of one is worked out from the element count
+    pub(crate) fn from_field(
+        field: Arc<igMetaFieldInfo>,
+        imm: &igMetadataManager,
+        registry: &igMetafieldRegistry,
+        platform: IG_CORE_PLATFORM,
+    ) -> Arc<dyn igMetaField> {
+        let mut element_info = field.ark_info.read().unwrap().clone();
+        let count = element_info.num.unwrap_or(1).max(1) as usize;
+        element_info._type = Arc::from(field._type.replace("ArrayMetaField", "MetaField"));
+        element_info.num = None;
+
+        let stride = field.size / count as u32;
+        let element = igMetaFieldInfo {
+            _type: element_info._type.clone(),
+            name: field.name.clone(),
+            size: stride,
+            alignment: field.alignment,
+            offset: 0,
+            ark_info: Arc::new(RwLock::new(element_info)),
+        };
+
+        Arc::new(igArrayMetaField {
+            element: registry.get(Arc::new(element), imm, platform),
+            count,
+            stride: stride as u64,
+        })
+    }
+}
+
+impl igMetaField for igArrayMetaField {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<Vec<Option<igAny>>>()
+    }
+
+    fn value_from_igz(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzLoaderContext,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        let start = handle.position();
+        let mut values: Vec<Option<igAny>> = Vec::with_capacity(self.count);
+        for i in 0..self.count {
+            handle.set_position(start + self.stride * i as u64);
+            values.push(self.element.value_from_igz(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx)?);
+        }
+        Ok(Some(Arc::new(RwLock::new(values))))
+    }
+
+    fn value_into_igz(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
+    ) -> Result<(), IgzSaverError> {
+        // The object is zeroed before its fields are written, so a null array is left that way
+        let Some(value) = value else {
+            return Ok(());
+        };
+        let guard = value.read().unwrap();
+        let values = guard.downcast_ref::<Vec<Option<igAny>>>().ok_or(IgzSaverError::InvalidValueType("Vec<Option<igAny>>"))?;
+        if values.len() != self.count {
+            return Err(IgzSaverError::InvalidArrayLength { expected: self.count, found: values.len() });
+        }
+
+        let start = handle.position();
+        for (i, x) in values.iter().enumerate() {
+            handle.set_position(start + self.stride * i as u64);
+            self.element.value_into_igz(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx, x.clone())?;
+        }
+        Ok(())
+    }
+
+    fn value_from_igx(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        if node.children.len() != self.count {
+            return Err(IgxLoaderErrorKind::InvalidArrayLength { expected: self.count, found: node.children.len() });
+        }
+
+        let mut values: Vec<Option<igAny>> = Vec::with_capacity(self.count);
+        for item in &node.children {
+            if item.is_null() {
+                values.push(None);
+                continue;
+            }
+            values.push(self.element.value_from_igx(registry, metadata_manager, object_stream_manager, item, ctx)?);
+        }
+        Ok(Some(Arc::new(RwLock::new(values))))
+    }
+
+    fn value_into_igx(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        node: &mut IgxNode,
+        ctx: &mut IgxSaverContext,
+        value: igAny,
+    ) -> Result<(), IgxSaverError> {
+        let guard = value.read().unwrap();
+        let values = guard.downcast_ref::<Vec<Option<igAny>>>().ok_or(IgxSaverError::InvalidValueType("Vec<Option<igAny>>"))?;
+        if values.len() != self.count {
+            return Err(IgxSaverError::InvalidArrayLength { expected: self.count, found: values.len() });
+        }
+
+        for x in values {
+            let mut item = IgxNode::new("item");
+            match x {
+                Some(x) => self.element.value_into_igx(registry, metadata_manager, object_stream_manager, &mut item, ctx, x.clone())?,
+                None => item.set_null(),
+            }
+            node.children.push(item);
+        }
+        Ok(())
+    }
+
+    fn value_from_igb(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        let mut values: Vec<Option<igAny>> = Vec::with_capacity(self.count);
+        for _ in 0..self.count {
+            values.push(self.element.value_from_igb(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx)?);
+        }
+        Ok(Some(Arc::new(RwLock::new(values))))
+    }
+
+    fn value_into_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
+    ) -> Result<(), IgbSaverError> {
+        Err(IgbSaverError::Unsupported("igArrayMetaField"))
+    }
+}
@@ -0,0 +1,265 @@
+use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_objects::{igAny, igObjectStreamManager};
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
+use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
+use crate::core::meta::field::ig_metafields::igMetaField;
+use crate::core::meta::field::r#impl::ig_enum_meta_field::igEnumMetaField;
+use crate::core::meta::field::r#impl::ig_placeholder_meta_field::igPlaceholderMetafield;
+use crate::core::meta::ig_metadata_manager::{igEnumValue, igMetaFieldInfo, igMetadataManager};
+use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
+use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
+use log::debug;
+use std::any::{Any, TypeId};
+use std::io::Cursor;
+use std::sync::{Arc, RwLock};
+
+/// What the bits of a bitfield are turned into
+enum BitFieldValue {
+    Bool,
+    Enum(igEnumMetaField),
+    /// One of the integer types, sign extended from its bits when signed
+    Int { type_id: TypeId, signed: bool },
+}
+
+/// Represents the different reasons a bitfield can't be packed into its storage field
+#[derive(Debug)]
+pub(crate) enum BitFieldError {
+    /// Returned when the value of the bitfield or its storage field isn't the type the metadata lists
+    InvalidValueType(&'static str),
+    /// Returned when an enum value is packed by a name its meta enum doesn't have
+    UnknownEnumValue { meta_enum: Arc<str>, name: Arc<str> },
+    /// Returned when the value needs more bits than the bitfield has
+    ValueOutOfRange { field: Arc<str>, bits: u8 },
+}
+
+/// A value packed into some of the bits of another field of the object, the storage field. Bitfields have no data of their own, so the metafield reads and writes nothing.
+/// Instead, the metadata manager unpacks every bitfield from its storage field once an object's fields have been read, and packs them back into the storage field when the object is saved
+pub(crate) struct igBitFieldMetaField {
+    name: Arc<str>,
+    shift: u8,
+    bits: u8,
+    /// The name of the field the bits are stored in
+    pub(crate) storage_field: Arc<str>,
+    value: BitFieldValue,
+}
+
+impl igBitFieldMetaField {
+    /// Used as the factory when registering igBitFieldMetaField. Falls back to a placeholder holding nothing when the bitfield can't be used
+    pub(crate) fn from_field(
+        field: Arc<igMetaFieldInfo>,
+        imm: &igMetadataManager,
+        _registry: &igMetafieldRegistry,
+        _platform: IG_CORE_PLATFORM,
+    ) -> Arc<dyn igMetaField> {
+        match igBitFieldMetaField::new(&field, imm) {
+            Some(bitfield) => Arc::new(bitfield),
+            None => Arc::new(igPlaceholderMetafield {
+                size: 0,
+                missing_impl_name: field.name.clone().unwrap_or(Arc::from("igBitFieldMetaField")),
+            }),
+        }
+    }
+
+    /// [None] when the field has no bit shift info or the bits are turned into a type other than a bool, enum or integer
+    pub(crate) fn new(field: &igMetaFieldInfo, imm: &igMetadataManager) -> Option<Self> {
+        let ark_info = field.ark_info.read().unwrap();
+        let bit_shift_info = ark_info.ig_bit_shift_info.as_ref()?.read().unwrap();
+        let inner = bit_shift_info._type.as_ref()?.read().unwrap();
+        let value = match inner._type.as_ref() {
+            "igEnumMetaField" => BitFieldValue::Enum(igEnumMetaField::new(inner.ig_meta_enum.clone(), imm)),
+            _ => {
+                let type_id = igMetaField::type_id(&*imm.meta_field_registry.get_simple(&inner));
+                if type_id == TypeId::of::<bool>() {
+                    BitFieldValue::Bool
+                } else if let Some(signed) = is_signed(type_id) {
+                    BitFieldValue::Int { type_id, signed }
+                } else {
+                    debug!("bitfield {:?} holds a {} which can't be unpacked", field.name, inner._type);
+                    return None;
+                }
+            }
+        };
+
+        Some(igBitFieldMetaField {
+            name: field.name.clone().unwrap_or_default(),
+            shift: bit_shift_info.shift,
+            bits: bit_shift_info.bits,
+            storage_field: Arc::from(bit_shift_info.storage_field.as_str()),
+            value,
+        })
+    }
+
+    fn mask(&self) -> u64 {
+        match self.bits {
+            64.. => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// The bits of the field as the integer they hold
+    fn extract(&self, raw: u64, signed: bool) -> i128 {
+        let raw = raw & self.mask();
+        match signed && self.bits > 0 && self.bits < 64 {
+            true => {
+                let unused = 64 - self.bits as u32;
+                (((raw << unused) as i64) >> unused) as i128
+            }
+            false => raw as i128,
+        }
+    }
+
+    /// Takes the value of the bitfield out of the value of its storage field
+    pub(crate) fn unpack(&self, storage: u64) -> igAny {
+        let raw = storage >> self.shift;
+        match &self.value {
+            BitFieldValue::Bool => Arc::new(RwLock::new(self.extract(raw, false) != 0)),
+            BitFieldValue::Enum(meta_enum) => Arc::new(RwLock::new(meta_enum.to_value(self.extract(raw, false) as i32))),
+            BitFieldValue::Int { type_id, signed } => int_from(*type_id, self.extract(raw, *signed)).unwrap(),
+        }
+    }
+
+    /// Replaces the bits of the field in the value of its storage field
+    pub(crate) fn pack(&self, storage: u64, value: &igAny) -> Result<u64, BitFieldError> {
+        let guard = value.read().unwrap();
+        let (value, signed) = match &self.value {
+            BitFieldValue::Bool => (*guard.downcast_ref::<bool>().ok_or(BitFieldError::InvalidValueType("bool"))? as i128, false),
+            BitFieldValue::Enum(meta_enum) => {
+                let value = guard.downcast_ref::<igEnumValue>().ok_or(BitFieldError::InvalidValueType("igEnumValue"))?;
+                let raw = meta_enum.to_raw(value).ok_or_else(|| {
+                    let (meta_enum, name) = meta_enum.unknown_name(value);
+                    BitFieldError::UnknownEnumValue { meta_enum, name }
+                })?;
+                (raw as i128, false)
+            }
+            BitFieldValue::Int { signed, .. } => (int_value(&*guard).ok_or(BitFieldError::InvalidValueType("integer"))?, *signed),
+        };
+
+        let raw = value as u64 & self.mask();
+        if self.extract(raw, signed) != value {
+            return Err(BitFieldError::ValueOutOfRange { field: self.name.clone(), bits: self.bits });
+        }
+        Ok((storage & !(self.mask() << self.shift)) | (raw << self.shift))
+    }
+}
+
+/// [None] when the type isn't one of the integers a bitfield or storage field can be
+fn is_signed(type_id: TypeId) -> Option<bool> {
+    match type_id {
+        x if x == TypeId::of::<i8>() || x == TypeId::of::<i16>() || x == TypeId::of::<i32>() || x == TypeId::of::<i64>() => Some(true),
+        x if x == TypeId::of::<u8>() || x == TypeId::of::<u16>() || x == TypeId::of::<u32>() || x == TypeId::of::<u64>() => Some(false),
+        _ => None,
+    }
+}
+
+/// Reads any of the integer types. [None] when the value isn't one
+pub(crate) fn int_value(value: &(dyn Any + Send + Sync)) -> Option<i128> {
+    macro_rules! read {
+        ($($type:ty),*) => {
+            $(if let Some(value) = value.downcast_ref::<$type>() {
+                return Some(*value as i128);
+            })*
+        };
+    }
+    read!(i8, u8, i16, u16, i32, u32, i64, u64);
+    None
+}
+
+/// Creates a value of the integer type given, wrapping around when it doesn't fit. [None] when the type isn't an integer
+pub(crate) fn int_from(type_id: TypeId, value: i128) -> Option<igAny> {
+    macro_rules! create {
+        ($($type:ty),*) => {
+            $(if type_id == TypeId::of::<$type>() {
+                return Some(Arc::new(RwLock::new(value as $type)));
+            })*
+        };
+    }
+    create!(i8, u8, i16, u16, i32, u32, i64, u64);
+    None
+}
+
+impl igMetaField for igBitFieldMetaField {
+    fn type_id(&self) -> TypeId {
+        match &self.value {
+            BitFieldValue::Bool => TypeId::of::<bool>(),
+            BitFieldValue::Enum(_) => TypeId::of::<igEnumValue>(),
+            BitFieldValue::Int { type_id, .. } => *type_id,
+        }
+    }
+
+    fn value_from_igz(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgzLoaderContext,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        Ok(None)
+    }
+
+    fn value_into_igz(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgzSaverContext,
+        _value: Option<igAny>,
+    ) -> Result<(), IgzSaverError> {
+        Ok(())
+    }
+
+    fn value_from_igx(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _node: &IgxNode,
+        _ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        Ok(None)
+    }
+
+    fn value_into_igx(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _node: &mut IgxNode,
+        _ctx: &mut IgxSaverContext,
+        _value: igAny,
+    ) -> Result<(), IgxSaverError> {
+        Ok(())
+    }
+
+    fn value_from_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        Ok(None)
+    }
+
+    fn value_into_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
+    ) -> Result<(), IgbSaverError> {
+        Err(IgbSaverError::Unsupported("igBitFieldMetaField"))
+    }
+}
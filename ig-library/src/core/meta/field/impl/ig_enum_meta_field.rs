@@ -0,0 +1,178 @@
+use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_objects::{igAny, igObjectStreamManager};
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
+use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
+use crate::core::meta::field::ig_metafields::igMetaField;
+use crate::core::meta::ig_metadata_manager::{igEnumValue, igMetaFieldInfo, igMetadataManager};
+use crate::core::meta::ig_xml_metadata::ArcMetaEnum;
+use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
+use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
+use crate::util::byteorder_fixes::{read_i32, write_i32};
+use log::debug;
+use std::any::TypeId;
+use std::io::Cursor;
+use std::sync::{Arc, RwLock};
+
+/// An enum stored as a 32-bit integer. Values are read as an [igEnumValue] named from the meta enum the field lists, with numbers the meta enum doesn't know keeping no name
+pub(crate) struct igEnumMetaField {
+    meta_enum_name: Arc<str>,
+    /// [None] when the meta enum isn't in the metadata, in which case every value is only a number
+    meta_enum: Option<Arc<ArcMetaEnum>>,
+}
+
+impl igEnumMetaField {
+    /// Used as the factory when registering igEnumMetaField. Looks up the meta enum named by the field
+    pub(crate) fn from_field(
+        field: Arc<igMetaFieldInfo>,
+        imm: &igMetadataManager,
+        _registry: &igMetafieldRegistry,
+        _platform: IG_CORE_PLATFORM,
+    ) -> Arc<dyn igMetaField> {
+        let meta_enum_name = field.ark_info.read().unwrap().ig_meta_enum.clone();
+        Arc::new(igEnumMetaField::new(meta_enum_name, imm))
+    }
+
+    /// Also used by fields holding an enum inside of them, such as bitfields
+    pub(crate) fn new(meta_enum_name: Option<Arc<str>>, imm: &igMetadataManager) -> Self {
+        let meta_enum_name = meta_enum_name.unwrap_or(Arc::from("igEnumMetaField"));
+        let meta_enum = imm.get_meta_enum(&meta_enum_name);
+        if meta_enum.is_none() {
+            debug!("the meta enum {} doesn't exist. Its values will only be numbers", meta_enum_name);
+        }
+        igEnumMetaField { meta_enum_name, meta_enum }
+    }
+
+    /// Names the number read from a file
+    pub(crate) fn to_value(&self, raw: i32) -> igEnumValue {
+        let name = self.meta_enum.as_ref().and_then(|meta_enum| meta_enum.values.iter().find(|x| x.value == raw)).map(|x| x.name.clone());
+        igEnumValue { name, value: raw }
+    }
+
+    /// The number to write for a value. [None] when the value is saved by a name the meta enum doesn't have
+    pub(crate) fn to_raw(&self, value: &igEnumValue) -> Option<i32> {
+        match &value.name {
+            Some(name) => self.meta_enum.as_ref()?.values.iter().find(|x| x.name == *name).map(|x| x.value),
+            None => Some(value.value),
+        }
+    }
+
+    /// The meta enum and name to report when [igEnumMetaField::to_raw] fails
+    pub(crate) fn unknown_name(&self, value: &igEnumValue) -> (Arc<str>, Arc<str>) {
+        (self.meta_enum_name.clone(), value.name.clone().unwrap_or_default())
+    }
+}
+
+impl igMetaField for igEnumMetaField {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<igEnumValue>()
+    }
+
+    fn value_from_igz(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        _ctx: &mut IgzLoaderContext,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        Ok(Some(Arc::new(RwLock::new(self.to_value(read_i32(handle, endian)?)))))
+    }
+
+    fn value_into_igz(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        _ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
+    ) -> Result<(), IgzSaverError> {
+        let raw = match value {
+            Some(value) => {
+                let guard = value.read().unwrap();
+                let value = guard.downcast_ref::<igEnumValue>().ok_or(IgzSaverError::InvalidValueType("igEnumValue"))?;
+                self.to_raw(value).ok_or_else(|| {
+                    let (meta_enum, name) = self.unknown_name(value);
+                    IgzSaverError::UnknownEnumValue { meta_enum, name }
+                })?
+            }
+            None => 0,
+        };
+        write_i32(handle, raw, endian)?;
+        Ok(())
+    }
+
+    fn value_from_igx(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        _ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        // Written by name, unless the number has none
+        let text = node.text.trim();
+        let value = match text.parse::<i32>() {
+            Ok(raw) => self.to_value(raw),
+            Err(_) => {
+                let value = igEnumValue::from_name(text);
+                let raw = self.to_raw(&value).ok_or_else(|| IgxLoaderErrorKind::InvalidValue {
+                    expected: "enum value",
+                    value: node.text.clone(),
+                })?;
+                self.to_value(raw)
+            }
+        };
+        Ok(Some(Arc::new(RwLock::new(value))))
+    }
+
+    fn value_into_igx(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        node: &mut IgxNode,
+        _ctx: &mut IgxSaverContext,
+        value: igAny,
+    ) -> Result<(), IgxSaverError> {
+        let guard = value.read().unwrap();
+        let value = guard.downcast_ref::<igEnumValue>().ok_or(IgxSaverError::InvalidValueType("igEnumValue"))?;
+        let raw = self.to_raw(value).ok_or_else(|| {
+            let (meta_enum, name) = self.unknown_name(value);
+            IgxSaverError::UnknownEnumValue { meta_enum, name }
+        })?;
+        node.text = self.to_value(raw).to_string();
+        Ok(())
+    }
+
+    fn value_from_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        _ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        Ok(Some(Arc::new(RwLock::new(self.to_value(read_i32(handle, endian)?)))))
+    }
+
+    fn value_into_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
+    ) -> Result<(), IgbSaverError> {
+        Err(IgbSaverError::Unsupported("igEnumMetaField"))
+    }
+}
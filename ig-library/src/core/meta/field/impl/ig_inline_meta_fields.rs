@@ -0,0 +1,291 @@
+use crate::core::ig_fs::Endian;
+use crate::core::ig_math::{igMatrix44f, igQuaternionf, igRandom, igVec2f, igVec3f, igVec4f};
+use crate::core::ig_objects::{igAny, igObjectStreamManager};
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
+use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
+use crate::core::meta::field::ig_metafields::igMetaField;
+use crate::core::meta::ig_metadata_manager::igMetadataManager;
+use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
+use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
+use crate::util::byteorder_fixes::{
+    read_f32, read_i16, read_i64, read_i8, read_u16, read_u32, read_u64, read_u8, write_f32, write_i16, write_i64, write_i8, write_u16,
+    write_u32, write_u64, write_u8,
+};
+use std::any::TypeId;
+use std::io::{Cursor, Write};
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+/// A value stored directly in an object as a fixed amount of bytes with no pointers in it. Every primitive and math metafield is one of these
+pub(crate) trait InlineValue: Default + Clone + Send + Sync + 'static {
+    /// Name of the rust type, used in errors
+    const TYPE_NAME: &'static str;
+
+    fn read(handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Self>;
+
+    fn write(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<()>;
+
+    /// How the value is written in an igx. Values with more than one component separate them with spaces
+    fn to_text(&self) -> String;
+
+    fn from_text(text: &str) -> Option<Self>;
+}
+
+/// Metafield for any [InlineValue]. `PADDING` is the amount of unused bytes stored after the value, such as the fourth float of an igVec3fAligned
+pub(crate) struct igInlineMetaField<T: InlineValue, const PADDING: usize = 0>(PhantomData<T>);
+
+impl<T: InlineValue, const PADDING: usize> igInlineMetaField<T, PADDING> {
+    pub(crate) fn new() -> Self {
+        igInlineMetaField(PhantomData)
+    }
+}
+
+pub(crate) type igBoolMetaField = igInlineMetaField<bool>;
+pub(crate) type igCharMetaField = igInlineMetaField<i8>;
+pub(crate) type igUnsignedCharMetaField = igInlineMetaField<u8>;
+pub(crate) type igShortMetaField = igInlineMetaField<i16>;
+pub(crate) type igUnsignedShortMetaField = igInlineMetaField<u16>;
+pub(crate) type igUnsignedIntMetaField = igInlineMetaField<u32>;
+pub(crate) type igLongMetaField = igInlineMetaField<i64>;
+pub(crate) type igUnsignedLongMetaField = igInlineMetaField<u64>;
+pub(crate) type igFloatMetaField = igInlineMetaField<f32>;
+/// Time in seconds
+pub(crate) type igTimeMetaField = igInlineMetaField<f32>;
+pub(crate) type igVec2fMetaField = igInlineMetaField<igVec2f>;
+pub(crate) type igVec3fMetaField = igInlineMetaField<igVec3f>;
+pub(crate) type igVec3fAlignedMetaField = igInlineMetaField<igVec3f, 4>;
+pub(crate) type igVec4fMetaField = igInlineMetaField<igVec4f>;
+pub(crate) type igQuaternionfMetaField = igInlineMetaField<igQuaternionf>;
+pub(crate) type igMatrix44fMetaField = igInlineMetaField<igMatrix44f>;
+pub(crate) type igRandomMetaField = igInlineMetaField<igRandom>;
+
+macro_rules! inline_primitive {
+    ($type:ty, $read:ident, $write:ident) => {
+        impl InlineValue for $type {
+            const TYPE_NAME: &'static str = stringify!($type);
+
+            fn read(handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Self> {
+                $read(handle, endian)
+            }
+
+            fn write(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<()> {
+                $write(handle, *self, endian)
+            }
+
+            fn to_text(&self) -> String {
+                self.to_string()
+            }
+
+            fn from_text(text: &str) -> Option<Self> {
+                text.parse().ok()
+            }
+        }
+    };
+}
+
+inline_primitive!(i8, read_i8, write_i8);
+inline_primitive!(u8, read_u8, write_u8);
+inline_primitive!(i16, read_i16, write_i16);
+inline_primitive!(u16, read_u16, write_u16);
+inline_primitive!(u32, read_u32, write_u32);
+inline_primitive!(i64, read_i64, write_i64);
+inline_primitive!(u64, read_u64, write_u64);
+inline_primitive!(f32, read_f32, write_f32);
+
+impl InlineValue for bool {
+    const TYPE_NAME: &'static str = "bool";
+
+    fn read(handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Self> {
+        Ok(read_u8(handle, endian)? != 0)
+    }
+
+    fn write(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<()> {
+        write_u8(handle, *self as u8, endian)
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        text.parse().ok()
+    }
+}
+
+/// Math types are their components one after the other, all of the same primitive type
+macro_rules! inline_components {
+    ($type:ty, $component:ty, $($field:ident),+) => {
+        impl InlineValue for $type {
+            const TYPE_NAME: &'static str = stringify!($type);
+
+            fn read(handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Self> {
+                Ok(Self {
+                    $($field: <$component>::read(handle, endian.clone())?,)+
+                })
+            }
+
+            fn write(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<()> {
+                $(self.$field.write(handle, endian.clone())?;)+
+                Ok(())
+            }
+
+            fn to_text(&self) -> String {
+                [$(self.$field.to_text()),+].join(" ")
+            }
+
+            fn from_text(text: &str) -> Option<Self> {
+                let mut components = text.split_whitespace();
+                let value = Self {
+                    $($field: <$component>::from_text(components.next()?)?,)+
+                };
+                components.next().is_none().then_some(value)
+            }
+        }
+    };
+}
+
+inline_components!(igVec2f, f32, x, y);
+inline_components!(igVec3f, f32, x, y, z);
+inline_components!(igVec4f, f32, x, y, z, w);
+inline_components!(igQuaternionf, f32, x, y, z, w);
+inline_components!(igRandom, u32, seed, state);
+
+impl InlineValue for igMatrix44f {
+    const TYPE_NAME: &'static str = "igMatrix44f";
+
+    fn read(handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Self> {
+        let mut matrix = igMatrix44f::default();
+        for value in matrix.m.iter_mut().flatten() {
+            *value = read_f32(handle, endian.clone())?;
+        }
+        Ok(matrix)
+    }
+
+    fn write(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<()> {
+        for value in self.m.iter().flatten() {
+            write_f32(handle, *value, endian.clone())?;
+        }
+        Ok(())
+    }
+
+    fn to_text(&self) -> String {
+        self.m.iter().flatten().map(|x| x.to_string()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let values: Vec<f32> = text.split_whitespace().map(|x| x.parse().ok()).collect::<Option<_>>()?;
+        if values.len() != 16 {
+            return None;
+        }
+        let mut matrix = igMatrix44f::default();
+        for (value, parsed) in matrix.m.iter_mut().flatten().zip(values) {
+            *value = parsed;
+        }
+        Some(matrix)
+    }
+}
+
+impl<T: InlineValue, const PADDING: usize> igInlineMetaField<T, PADDING> {
+    fn read_value(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian) -> std::io::Result<Option<igAny>> {
+        let value = T::read(handle, endian)?;
+        handle.set_position(handle.position() + PADDING as u64);
+        Ok(Some(Arc::new(RwLock::new(value))))
+    }
+
+    fn write_value(&self, handle: &mut Cursor<Vec<u8>>, endian: Endian, value: Option<igAny>) -> Result<(), IgzSaverError> {
+        let value = match value {
+            Some(value) => value.read().unwrap().downcast_ref::<T>().ok_or(IgzSaverError::InvalidValueType(T::TYPE_NAME))?.clone(),
+            None => T::default(),
+        };
+        value.write(handle, endian)?;
+        handle.write_all(&[0; PADDING])?;
+        Ok(())
+    }
+}
+
+impl<T: InlineValue, const PADDING: usize> igMetaField for igInlineMetaField<T, PADDING> {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn value_from_igz(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        _ctx: &mut IgzLoaderContext,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        Ok(self.read_value(handle, endian)?)
+    }
+
+    fn value_into_igz(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        _ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
+    ) -> Result<(), IgzSaverError> {
+        self.write_value(handle, endian, value)
+    }
+
+    fn value_from_igx(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        _ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        let value = T::from_text(node.text.trim()).ok_or_else(|| IgxLoaderErrorKind::InvalidValue {
+            expected: T::TYPE_NAME,
+            value: node.text.clone(),
+        })?;
+        Ok(Some(Arc::new(RwLock::new(value))))
+    }
+
+    fn value_into_igx(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        node: &mut IgxNode,
+        _ctx: &mut IgxSaverContext,
+        value: igAny,
+    ) -> Result<(), IgxSaverError> {
+        node.text = value.read().unwrap().downcast_ref::<T>().ok_or(IgxSaverError::InvalidValueType(T::TYPE_NAME))?.to_text();
+        Ok(())
+    }
+
+    fn value_from_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        _ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        Ok(self.read_value(handle, endian)?)
+    }
+
+    fn value_into_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
+    ) -> Result<(), IgbSaverError> {
+        Err(IgbSaverError::Unsupported("igInlineMetaField"))
+    }
+}
@@ -1,15 +1,15 @@
 ﻿use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::{igAny, igObjectStreamManager};
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{parse_text, IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::field::ig_metafields::igMetaField;
 use crate::core::meta::ig_metadata_manager::igMetadataManager;
 use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
 use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
 use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
-use crate::util::byteorder_fixes::read_i32;
+use crate::util::byteorder_fixes::{read_i32, write_i32};
 use std::any::TypeId;
 use std::io::Cursor;
 use std::sync::{Arc, RwLock};
@@ -29,8 +29,8 @@ impl igMetaField for igIntMetaField {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         _ctx: &mut IgzLoaderContext,
-    ) -> Option<igAny> {
-        Some(Arc::new(RwLock::new(read_i32(handle, endian).unwrap())))
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        Ok(Some(Arc::new(RwLock::new(read_i32(handle, endian)?))))
     }
 
     fn value_into_igz(
@@ -38,11 +38,17 @@ impl igMetaField for igIntMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
         _ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgzSaverError> {
-        todo!()
+        let value = match value {
+            Some(value) => *value.read().unwrap().downcast_ref::<i32>().ok_or(IgzSaverError::InvalidValueType("i32"))?,
+            None => 0,
+        };
+        write_i32(handle, value, endian)?;
+        Ok(())
     }
 
     fn value_from_igx(
@@ -50,11 +56,10 @@ impl igMetaField for igIntMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        node: &IgxNode,
         _ctx: &mut IgxLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        Ok(Some(Arc::new(RwLock::new(parse_text::<i32>(node, "i32")?))))
     }
 
     fn value_into_igx(
@@ -62,11 +67,12 @@ impl igMetaField for igIntMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        node: &mut IgxNode,
         _ctx: &mut IgxSaverContext,
+        value: igAny,
     ) -> Result<(), IgxSaverError> {
-        todo!()
+        node.text = value.read().unwrap().downcast_ref::<i32>().ok_or(IgxSaverError::InvalidValueType("i32"))?.to_string();
+        Ok(())
     }
 
     fn value_from_igb(
@@ -74,11 +80,11 @@ impl igMetaField for igIntMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
         _ctx: &mut IgbLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        Ok(Some(Arc::new(RwLock::new(read_i32(handle, endian)?))))
     }
 
     fn value_into_igb(
@@ -89,7 +95,8 @@ impl igMetaField for igIntMetaField {
         _handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
     ) -> Result<(), IgbSaverError> {
-        todo!()
+        Err(IgbSaverError::Unsupported("igIntMetaField"))
     }
 }
\ No newline at end of file
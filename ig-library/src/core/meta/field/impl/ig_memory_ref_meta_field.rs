@@ -1,29 +1,38 @@
 use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::{igAny, igObjectStreamManager};
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
-use crate::core::memory::igMemory;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind, IGB_NULL_INDEX};
+use crate::core::load::ig_igx_loader::{parse_hex, IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
+use crate::core::ig_memory::igMemoryPool;
+use crate::core::memory::{igMemory, igNullElement};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::field::ig_metafields::igMetaField;
 use crate::core::meta::ig_metadata_manager::{igMetaFieldInfo, igMetadataManager};
 use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
-use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igx_saver::{format_hex, IgxSaverContext, IgxSaverError};
 use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
-use crate::util::byteorder_fixes::{read_ptr, read_struct_array_u8_ref};
+use crate::util::byteorder_fixes::{read_ptr, read_struct_array_u8_ref, read_u32, write_ptr};
 use std::any::TypeId;
 use std::io::Cursor;
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 use log::debug;
 
 pub(crate) struct igMemoryRefMetaField(pub Arc<igMetaFieldInfo>);
 
-impl igMetaField for igMemoryRefMetaField {
-    fn type_id(&self) -> TypeId {
-        TypeId::of::<igMemory<igAny>>()
-    }
+/// Null elements are stored as [igNullElement] so the memory keeps its length
+fn null_element() -> igAny {
+    Arc::new(RwLock::new(igNullElement))
+}
 
-    fn value_from_igz(
+fn is_null_element(value: &igAny) -> bool {
+    value.read().unwrap().is::<igNullElement>()
+}
+
+impl igMemoryRefMetaField {
+    /// Reads the memory the same way as [igMetaField::value_from_igz], stopping after `limit` elements. Used by types that only use the start of their memory, such as an igVector
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn read_igz(
         &self,
         registry: &igMetafieldRegistry,
         metadata_manager: &igMetadataManager,
@@ -31,97 +40,254 @@ impl igMetaField for igMemoryRefMetaField {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgzLoaderContext,
-    ) -> Option<igAny> {
+        limit: usize,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
         #[cfg(debug_assertions)]
         debug!("Internal meta object type={}", self.0._type);
         let start = handle.position();
-        let flags = read_ptr(handle, ctx.platform.clone(), endian.clone()).unwrap();
-        let raw = read_ptr(handle, ctx.platform.clone(), endian.clone()).unwrap();
+        let flags = read_ptr(handle, ctx.platform.clone(), endian.clone())?;
+        let raw = read_ptr(handle, ctx.platform.clone(), endian.clone())?;
 
-        let offset = ctx.deserialize_offset(raw);
+        let offset = ctx.deserialize_offset(raw)?;
         let mut memory: igMemory<igAny> = igMemory::new(); // We don't know the type inside the memory, we didn't create it. However, we know the metafield so we know what is supposed to be here, making it safe in the end.
 
         // TODO: make 2 constructors for igMemory: one takes a pool and the other a set of flags. This fits in with rust's structuring where nothing should be used until initialized and guarantees better safety
         if ctx.runtime_fields.pool_ids.binary_search(&start).is_ok() {
-            memory.pool = ctx.loaded_pools[(flags & 0xFFFFFF) as usize];
+            let index = flags & 0xFFFFFF;
+            memory.pool = *ctx.loaded_pools.get(index as usize).ok_or(IgzLoaderErrorKind::InvalidIndex { list: "loaded pools", index })?;
         } else {
             memory.set_flags(flags, self.0.alignment as usize, self.0.size as usize, ctx.platform.clone());
-            memory.pool = ctx.get_pool_from_serialized_offset(raw);
+            memory.pool = ctx.get_pool_from_serialized_offset(raw)?;
 
             let guard = self.0.ark_info.read().unwrap();
             // Optimized u8 slice copy
             if guard._type.as_ref() == "igUnsignedCharMetaField" {
                 handle.set_position(offset);
-                let slice = read_struct_array_u8_ref(handle, endian.clone(), memory.data.len()).unwrap();
+                let slice = read_struct_array_u8_ref(handle, endian.clone(), memory.data.capacity().min(limit))?;
                 for x in slice {
-                    handle.set_position(offset);
-                    memory.data.push(Arc::new(RwLock::new(x)));
+                    memory.data.push(Arc::new(RwLock::new(*x)));
                 }
             } else {
                 let inner_meta_field = registry.get_simple(&self.0.ark_info.read().unwrap());
-                for i in 0..memory.data.capacity() {
+                for i in 0..memory.data.capacity().min(limit) {
                     handle.set_position(offset + (self.0.size as u64) * (i as u64));
-                    memory.data.push(inner_meta_field.value_from_igz(
+                    let value = inner_meta_field.value_from_igz(
                         registry,
                         metadata_manager,
                         object_stream_manager,
                         handle,
                         endian.clone(),
                         ctx,
-                    )?)
+                    )?;
+                    memory.data.push(value.unwrap_or_else(null_element))
                 }
             }
         }
 
-        Some(Arc::new(RwLock::new(memory)))
+        Ok(Some(Arc::new(RwLock::new(memory))))
+    }
+}
+
+impl igMetaField for igMemoryRefMetaField {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<igMemory<igAny>>()
+    }
+
+    fn value_from_igz(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzLoaderContext,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        self.read_igz(registry, metadata_manager, object_stream_manager, handle, endian, ctx, usize::MAX)
     }
 
     fn value_into_igz(
         &self,
-        _registry: &igMetafieldRegistry,
-        _metadata_manager: &igMetadataManager,
-        _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgzSaverContext,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgzSaverError> {
-        todo!()
+        let Some(value) = value else {
+            write_ptr(handle, 0, ctx.platform.clone(), endian.clone())?;
+            write_ptr(handle, 0, ctx.platform.clone(), endian)?;
+            return Ok(());
+        };
+        let guard = value.read().unwrap();
+        let memory = guard.downcast_ref::<igMemory<igAny>>().ok_or(IgzSaverError::InvalidValueType("igMemory<igAny>"))?;
+
+        let size = self.0.size as usize * memory.data.len();
+        let alignment = (self.0.alignment * memory.alignment_multiple.max(1)).next_power_of_two().max(4);
+        write_ptr(handle, memory.get_flags(size as u64, alignment, ctx.platform.clone()), ctx.platform.clone(), endian.clone())?;
+        if memory.data.is_empty() {
+            write_ptr(handle, 0, ctx.platform.clone(), endian)?;
+            return Ok(());
+        }
+
+        let block = ctx.add_block(memory.pool, alignment);
+        ctx.write_block_ref(handle, endian.clone(), block)?;
+
+        let mut data = Cursor::new(vec![0u8; size]);
+        let previous_block = ctx.set_current_block(block);
+        let guard = self.0.ark_info.read().unwrap();
+        // Optimized u8 slice copy
+        if guard._type.as_ref() == "igUnsignedCharMetaField" {
+            for (i, x) in memory.data.iter().enumerate() {
+                data.get_mut()[i] = *x.read().unwrap().downcast_ref::<u8>().ok_or(IgzSaverError::InvalidValueType("u8"))?;
+            }
+        } else {
+            let inner_meta_field = registry.get_simple(&guard);
+            for (i, x) in memory.data.iter().enumerate() {
+                data.set_position((self.0.size as u64) * (i as u64));
+                let value = (!is_null_element(x)).then(|| x.clone());
+                inner_meta_field.value_into_igz(
+                    registry,
+                    metadata_manager,
+                    object_stream_manager,
+                    &mut data,
+                    endian.clone(),
+                    ctx,
+                    value,
+                )?;
+            }
+        }
+        ctx.set_current_block(previous_block);
+        ctx.set_block_data(block, data.into_inner());
+
+        Ok(())
     }
 
     fn value_from_igx(
         &self,
-        _registry: &igMetafieldRegistry,
-        _metadata_manager: &igMetadataManager,
-        _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgxLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        let mut memory: igMemory<igAny> = igMemory::new();
+        let pool = node.required_attribute("pool")?;
+        memory.pool = igMemoryPool::from_str(pool).map_err(|_| IgxLoaderErrorKind::InvalidMemoryPool(pool.to_string()))?;
+        if let Some(alignment_multiple) = node.attribute("alignmentMultiple") {
+            memory.alignment_multiple = alignment_multiple.parse().map_err(|_| IgxLoaderErrorKind::InvalidValue {
+                expected: "u32",
+                value: alignment_multiple.to_string(),
+            })?;
+        }
+
+        let guard = self.0.ark_info.read().unwrap();
+        // u8 memory is stored as a single hex string instead of an element per byte
+        if guard._type.as_ref() == "igUnsignedCharMetaField" {
+            for x in parse_hex(&node.text)? {
+                memory.data.push(Arc::new(RwLock::new(x)));
+            }
+        } else {
+            let inner_meta_field = registry.get_simple(&guard);
+            for item in &node.children {
+                if item.is_null() {
+                    memory.data.push(null_element());
+                    continue;
+                }
+                let value = inner_meta_field.value_from_igx(registry, metadata_manager, object_stream_manager, item, ctx)?;
+                memory.data.push(value.unwrap_or_else(null_element));
+            }
+        }
+
+        Ok(Some(Arc::new(RwLock::new(memory))))
     }
 
     fn value_into_igx(
         &self,
-        _registry: &igMetafieldRegistry,
-        _metadata_manager: &igMetadataManager,
-        _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgxSaverContext,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        node: &mut IgxNode,
+        ctx: &mut IgxSaverContext,
+        value: igAny,
     ) -> Result<(), IgxSaverError> {
-        todo!()
+        let guard = value.read().unwrap();
+        let memory = guard.downcast_ref::<igMemory<igAny>>().ok_or(IgxSaverError::InvalidValueType("igMemory<igAny>"))?;
+        node.set_attribute("pool", format!("{:?}", memory.pool));
+        if memory.alignment_multiple != 0 {
+            node.set_attribute("alignmentMultiple", memory.alignment_multiple);
+        }
+
+        let ark_info = self.0.ark_info.read().unwrap();
+        // u8 memory is stored as a single hex string instead of an element per byte
+        if ark_info._type.as_ref() == "igUnsignedCharMetaField" {
+            let mut bytes = Vec::with_capacity(memory.data.len());
+            for x in &memory.data {
+                bytes.push(*x.read().unwrap().downcast_ref::<u8>().ok_or(IgxSaverError::InvalidValueType("u8"))?);
+            }
+            node.text = format_hex(&bytes);
+        } else {
+            let inner_meta_field = registry.get_simple(&ark_info);
+            for x in &memory.data {
+                let mut item = IgxNode::new("item");
+                if is_null_element(x) {
+                    item.set_null();
+                } else {
+                    inner_meta_field.value_into_igx(registry, metadata_manager, object_stream_manager, &mut item, ctx, x.clone())?;
+                }
+                node.children.push(item);
+            }
+        }
+
+        Ok(())
     }
 
     fn value_from_igb(
         &self,
-        _registry: &igMetafieldRegistry,
-        _metadata_manager: &igMetadataManager,
-        _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgbLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        let index = read_u32(handle, endian.clone())?;
+        if index == IGB_NULL_INDEX {
+            return Ok(None);
+        }
+        let block = ctx.get_memory(index)?;
+        let mut memory: igMemory<igAny> = igMemory::new();
+        memory.pool = block.pool;
+
+        let end = block.offset + block.size as u64;
+        let after_index = handle.position();
+        handle.set_position(block.offset);
+        let guard = self.0.ark_info.read().unwrap();
+        // Optimized u8 slice copy
+        if guard._type.as_ref() == "igUnsignedCharMetaField" {
+            let slice = read_struct_array_u8_ref(handle, endian.clone(), block.size as usize)?;
+            for x in slice {
+                memory.data.push(Arc::new(RwLock::new(*x)));
+            }
+        } else {
+            let inner_meta_field = registry.get_simple(&guard);
+            while handle.position() < end {
+                let value = inner_meta_field.value_from_igb(
+                    registry,
+                    metadata_manager,
+                    object_stream_manager,
+                    handle,
+                    endian.clone(),
+                    ctx,
+                )?;
+                memory.data.push(value.unwrap_or_else(null_element))
+            }
+        }
+
+        // The memory lives elsewhere in the igb, the next field comes right after the ref index
+        handle.set_position(after_index);
+        Ok(Some(Arc::new(RwLock::new(memory))))
     }
 
     fn value_into_igb(
@@ -132,7 +298,8 @@ impl igMetaField for igMemoryRefMetaField {
         _handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
     ) -> Result<(), IgbSaverError> {
-        todo!()
+        Err(IgbSaverError::Unsupported("igMemoryRefMetaField"))
     }
 }
@@ -1,14 +1,15 @@
 use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::{igAny, igObject, igObjectStreamManager};
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind, IGB_NULL_INDEX};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::field::ig_metafields::igMetaField;
 use crate::core::meta::ig_metadata_manager::igMetadataManager;
 use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
 use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
 use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
+use crate::util::byteorder_fixes::{read_u32, write_ptr};
 use std::any::TypeId;
 use std::io::Cursor;
 use std::sync::{Arc, RwLock};
@@ -30,7 +31,7 @@ impl igMetaField for igObjectRefMetaField {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgzLoaderContext,
-    ) -> Option<igAny> {
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
         let base_offset = handle.position();
         let size_type_meta_field = igSizeTypeMetaField;
         let raw = *size_type_meta_field.value_from_igz(
@@ -40,31 +41,34 @@ impl igMetaField for igObjectRefMetaField {
             handle,
             endian,
             ctx,
-        ).unwrap().read().unwrap().downcast_ref::<u64>().unwrap();
+        )?.unwrap().read().unwrap().downcast_ref::<u64>().unwrap();
 
         let is_offset = ctx.runtime_fields.offsets.binary_search(&base_offset).is_ok();
         if is_offset {
-            return Some(Arc::new(RwLock::new(ctx.offset_object_list[&raw].clone())));
+            let object = ctx.offset_object_list.get(&raw).ok_or(IgzLoaderErrorKind::MissingObject(raw))?;
+            return Ok(Some(Arc::new(RwLock::new(object.clone()))));
         }
+        let index = raw & 0x7FFFFFFF;
         let is_named_external = ctx.runtime_fields.named_externals.binary_search(&base_offset).is_ok();
         if is_named_external {
-            return Some(Arc::new(RwLock::new(ctx.named_external_list[(raw & 0x7FFFFFFF) as usize].clone())));
+            let object = ctx.named_external_list.get(index as usize).ok_or(IgzLoaderErrorKind::InvalidIndex { list: "named external list", index })?;
+            return Ok(Some(Arc::new(RwLock::new(object.clone()))));
         }
         let is_exid = ctx.runtime_fields.externals.binary_search(&base_offset).is_ok();
         if is_exid {
-            return if let Some(obj) = ctx.external_list[(raw & 0x7FFFFFFF) as usize].get_object_alias(object_stream_manager) {
-                Some(Arc::new(RwLock::new(obj)))
-            } else {
-                None
-            };
+            let external = ctx.external_list.get_mut(index as usize).ok_or(IgzLoaderErrorKind::InvalidIndex { list: "external list", index })?;
+            return Ok(external.get_object_alias(object_stream_manager).map(|obj| {
+                let object: igAny = Arc::new(RwLock::new(obj));
+                object
+            }));
         }
         if raw != 0 {
             // value should not be null, but we couldn't determine what it actually was.
             error!("Failed to read igObjectRefMetaField properly");
-            panic!("Alchemy Error! Check the logs.");
+            return Err(IgzLoaderErrorKind::UnresolvedObjectRef(raw));
         }
 
-        None
+        Ok(None)
     }
 
     fn value_into_igz(
@@ -72,23 +76,30 @@ impl igMetaField for igObjectRefMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgzSaverContext,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgzSaverError> {
-        todo!()
+        if let Some(value) = value {
+            let object = value.read().unwrap().downcast_ref::<igObject>().ok_or(IgzSaverError::InvalidValueType("igObject"))?.clone();
+            ctx.write_object_ref(handle, endian, &object)
+        } else {
+            write_ptr(handle, 0, ctx.platform.clone(), endian)?;
+            Ok(())
+        }
     }
 
     fn value_from_igx(
         &self,
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
-        _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgxLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+        object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        let object = ctx.get_object(node, object_stream_manager)?;
+        Ok(Some(Arc::new(RwLock::new(object))))
     }
 
     fn value_into_igx(
@@ -96,11 +107,13 @@ impl igMetaField for igObjectRefMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgxSaverContext,
+        node: &mut IgxNode,
+        ctx: &mut IgxSaverContext,
+        value: igAny,
     ) -> Result<(), IgxSaverError> {
-        todo!()
+        let object = value.read().unwrap().downcast_ref::<igObject>().ok_or(IgxSaverError::InvalidValueType("igObject"))?.clone();
+        ctx.write_object_ref(node, &object);
+        Ok(())
     }
 
     fn value_from_igb(
@@ -108,11 +121,15 @@ impl igMetaField for igObjectRefMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgbLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        let index = read_u32(handle, endian)?;
+        if index == IGB_NULL_INDEX {
+            return Ok(None);
+        }
+        Ok(Some(Arc::new(RwLock::new(ctx.get_object(index)?))))
     }
 
     fn value_into_igb(
@@ -123,7 +140,8 @@ impl igMetaField for igObjectRefMetaField {
         _handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
     ) -> Result<(), IgbSaverError> {
-        todo!()
+        Err(IgbSaverError::Unsupported("igObjectRefMetaField"))
     }
 }
@@ -1,15 +1,15 @@
 use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::{igAny, igObjectStreamManager};
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{parse_hex, IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
 use crate::core::meta::field::ig_metafields::igMetaField;
 use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
-use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igx_saver::{format_hex, IgxSaverContext, IgxSaverError};
 use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
 use log::{error, warn};
 use std::any::TypeId;
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Write};
 use std::sync::{Arc, RwLock};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::ig_metadata_manager::igMetadataManager;
@@ -33,11 +33,11 @@ impl igMetaField for igPlaceholderMetafield {
         handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgzLoaderContext,
-    ) -> Option<igAny> {
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
         warn!("{} has no implementation. Using igPlaceholderMetafield. Harass hydos to implement this or make a PR!", self.missing_impl_name);
-        let mut fake_buffer = Vec::with_capacity(self.size as usize);
-        handle.read_exact(&mut fake_buffer).unwrap();
-        Some(Arc::new(RwLock::new(fake_buffer)))
+        let mut buffer = vec![0u8; self.size as usize];
+        handle.read_exact(&mut buffer)?;
+        Ok(Some(Arc::new(RwLock::new(buffer))))
     }
 
     fn value_into_igz(
@@ -45,16 +45,19 @@ impl igMetaField for igPlaceholderMetafield {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
+        handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgzSaverError> {
-        todo!()
-        // let fake_buffer = Vec::with_capacity(self.platform_size(ctx.platform.clone()) as usize);
-        // handle
-        //     .write(fake_buffer.as_slice())
-        //     .map_err(|_e| IgzSaverError::Unknown)
-        //     .map(|_t| {})
+        // Whatever bytes were read are written back untouched. Without an implementation there is no way of knowing if they contain pointers
+        warn!("{} has no implementation. Using igPlaceholderMetafield. Harass hydos to implement this or make a PR!", self.missing_impl_name);
+        if let Some(value) = value {
+            let guard = value.read().unwrap();
+            let bytes = guard.downcast_ref::<Vec<u8>>().ok_or(IgzSaverError::InvalidValueType("Vec<u8>"))?;
+            handle.write_all(bytes)?;
+        }
+        Ok(())
     }
 
     fn value_from_igx(
@@ -62,11 +65,11 @@ impl igMetaField for igPlaceholderMetafield {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        node: &IgxNode,
         _ctx: &mut IgxLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        warn!("{} has no implementation. Using igPlaceholderMetafield. Harass hydos to implement this or make a PR!", self.missing_impl_name);
+        Ok(Some(Arc::new(RwLock::new(parse_hex(&node.text)?))))
     }
 
     fn value_into_igx(
@@ -74,14 +77,16 @@ impl igMetaField for igPlaceholderMetafield {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        node: &mut IgxNode,
         _ctx: &mut IgxSaverContext,
+        value: igAny,
     ) -> Result<(), IgxSaverError> {
-        error!(
-            "Using igPlaceholderMetafield for saving is not supported. Implement the metafield!"
-        );
-        panic!("Alchemy Error! Check the logs.")
+        // Whatever bytes were read are written back untouched, same as with igz
+        warn!("{} has no implementation. Using igPlaceholderMetafield. Harass hydos to implement this or make a PR!", self.missing_impl_name);
+        let guard = value.read().unwrap();
+        let bytes = guard.downcast_ref::<Vec<u8>>().ok_or(IgxSaverError::InvalidValueType("Vec<u8>"))?;
+        node.text = format_hex(bytes);
+        Ok(())
     }
 
     fn value_from_igb(
@@ -89,12 +94,14 @@ impl igMetaField for igPlaceholderMetafield {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
+        handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgbLoaderContext,
-    ) -> Option<igAny> {
-        error!("Using igPlaceholderMetafield not supported. Implement the metafield!");
-        panic!("Alchemy Error! Check the logs.")
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        warn!("{} has no implementation. Using igPlaceholderMetafield. Harass hydos to implement this or make a PR!", self.missing_impl_name);
+        let mut buffer = vec![0u8; self.size as usize];
+        handle.read_exact(&mut buffer)?;
+        Ok(Some(Arc::new(RwLock::new(buffer))))
     }
 
     fn value_into_igb(
@@ -105,6 +112,7 @@ impl igMetaField for igPlaceholderMetafield {
         _handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
     ) -> Result<(), IgbSaverError> {
         error!(
             "Using igPlaceholderMetafield for saving is not supported. Implement the metafield!"
@@ -1,15 +1,15 @@
 use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::{igAny, igObjectStreamManager};
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::field::ig_metafields::igMetaField;
 use crate::core::meta::ig_metadata_manager::igMetadataManager;
 use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
 use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
 use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
-use crate::util::byteorder_fixes::{read_ptr, read_string};
+use crate::util::byteorder_fixes::{read_ptr, read_string, read_struct_array_u8, read_u32, write_ptr};
 use std::any::TypeId;
 use std::io::Cursor;
 use std::sync::{Arc, RwLock};
@@ -29,29 +29,29 @@ impl igMetaField for igStringMetaField {
         handle: &mut Cursor<Vec<u8>>,
         endian: Endian,
         ctx: &mut IgzLoaderContext,
-    ) -> Option<igAny> {
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
         let base_pos = handle.position();
         let is_ref = ctx
             .runtime_fields
             .string_references
             .binary_search(&base_pos).is_ok();
 
-        let is_table = ctx.runtime_fields.string_tables.binary_search(&base_pos) >= Ok(0);
+        let is_table = ctx.runtime_fields.string_tables.binary_search(&base_pos).is_ok();
 
-        let raw = read_ptr(handle, ctx.platform.clone(), endian).unwrap();
+        let raw = read_ptr(handle, ctx.platform.clone(), endian)?;
         let mut result: Option<String> = None;
 
         if is_ref {
-            let offset = ctx.deserialize_offset(raw);
+            let offset = ctx.deserialize_offset(raw)?;
             handle.set_position(offset);
-            result = Some(read_string(handle).unwrap());
+            result = Some(read_string(handle)?);
         } else if is_table {
-            result = Some(ctx.string_list[raw as usize].clone());
+            result = Some(ctx.string_list.get(raw as usize).cloned().ok_or(IgzLoaderErrorKind::InvalidIndex { list: "string list", index: raw })?);
         }
 
         handle.set_position(base_pos + ctx.platform.get_pointer_size() as u64);
         // Based rust casting
-        result.map(|s| {
+        Ok(result.map(|s| {
             // 1) make an Arc<str>
             let arc_str: Arc<str> = Arc::from(s.into_boxed_str());
             // 2) lock the Arc<str>, producing Arc<RwLock<Arc<str>>>
@@ -59,7 +59,7 @@ impl igMetaField for igStringMetaField {
             // 3) coerce to igAny
             let object: igAny = concrete;
             object
-        })
+        }))
     }
 
     fn value_into_igz(
@@ -67,11 +67,19 @@ impl igMetaField for igStringMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
-        _ctx: &mut IgzSaverContext,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
     ) -> Result<(), IgzSaverError> {
-        todo!()
+        if let Some(value) = value {
+            let guard = value.read().unwrap();
+            let string = guard.downcast_ref::<Arc<str>>().ok_or(IgzSaverError::InvalidValueType("Arc<str>"))?;
+            ctx.write_string_ref(handle, endian, string)
+        } else {
+            write_ptr(handle, 0, ctx.platform.clone(), endian)?;
+            Ok(())
+        }
     }
 
     fn value_from_igx(
@@ -79,11 +87,11 @@ impl igMetaField for igStringMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        node: &IgxNode,
         _ctx: &mut IgxLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        let string: Arc<str> = Arc::from(node.text.as_str());
+        Ok(Some(Arc::new(RwLock::new(string))))
     }
 
     fn value_into_igx(
@@ -91,11 +99,14 @@ impl igMetaField for igStringMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        node: &mut IgxNode,
         _ctx: &mut IgxSaverContext,
+        value: igAny,
     ) -> Result<(), IgxSaverError> {
-        todo!()
+        let guard = value.read().unwrap();
+        let string = guard.downcast_ref::<Arc<str>>().ok_or(IgxSaverError::InvalidValueType("Arc<str>"))?;
+        node.text = string.to_string();
+        Ok(())
     }
 
     fn value_from_igb(
@@ -103,11 +114,19 @@ impl igMetaField for igStringMetaField {
         _registry: &igMetafieldRegistry,
         _metadata_manager: &igMetadataManager,
         _object_stream_manager: &igObjectStreamManager,
-        _handle: &mut Cursor<Vec<u8>>,
-        _endian: Endian,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
         _ctx: &mut IgbLoaderContext,
-    ) -> Option<igAny> {
-        todo!()
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        // Strings are stored in place as their length, including the null terminator, followed by the characters. A length of 0 is "null"
+        let length = read_u32(handle, endian.clone())?;
+        if length == 0 {
+            return Ok(None);
+        }
+        let bytes = read_struct_array_u8(handle, endian, length as usize)?;
+        let end = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+        let string: Arc<str> = Arc::from(String::from_utf8_lossy(&bytes[..end]).as_ref());
+        Ok(Some(Arc::new(RwLock::new(string))))
     }
 
     fn value_into_igb(
@@ -118,7 +137,8 @@ impl igMetaField for igStringMetaField {
         _handle: &mut Cursor<Vec<u8>>,
         _endian: Endian,
         _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
     ) -> Result<(), IgbSaverError> {
-        todo!()
+        Err(IgbSaverError::Unsupported("igStringMetaField"))
     }
 }
@@ -0,0 +1,150 @@
+use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_objects::{igAny, igObjectStreamManager};
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
+use crate::core::memory::igMemory;
+use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
+use crate::core::meta::field::ig_metafields::igMetaField;
+use crate::core::meta::field::r#impl::ig_memory_ref_meta_field::igMemoryRefMetaField;
+use crate::core::meta::field::r#impl::ig_placeholder_meta_field::igPlaceholderMetafield;
+use crate::core::meta::ig_metadata_manager::{igMetaFieldInfo, igMetadataManager};
+use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
+use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
+use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
+use crate::util::byteorder_fixes::{read_ptr, read_u32, write_ptr};
+use std::any::TypeId;
+use std::io::Cursor;
+use std::sync::Arc;
+use log::debug;
+
+/// An igVector is the amount of elements in use followed by a memory ref holding them. Only the elements in use are read, so the value is the same [igMemory] a memory ref gives with the count being its length
+pub(crate) struct igVectorMetaField(pub igMemoryRefMetaField);
+
+impl igVectorMetaField {
+    /// Used as the factory when registering igVectorMetaField. The element type comes from the vector info, with its alignment raised by the vector's alignment multiple
+    pub(crate) fn from_field(
+        field: Arc<igMetaFieldInfo>,
+        imm: &igMetadataManager,
+        _registry: &igMetafieldRegistry,
+        platform: IG_CORE_PLATFORM,
+    ) -> Arc<dyn igMetaField> {
+        let vector_info = field.ark_info.read().unwrap().ig_vector_info.clone();
+        // Vectors inside of other types (such as the elements of an igVectorArrayMetaField) don't come with their element type
+        let Some((raw_element, vector_info)) = vector_info.and_then(|x| Some((x.field.clone()?, x))) else {
+            debug!("igVectorMetaField {:?} has no element type. Using igPlaceholderMetafield", field.name);
+            return Arc::new(igPlaceholderMetafield {
+                size: field.size,
+                missing_impl_name: field.name.clone().unwrap_or(Arc::from("igVectorMetaField")),
+            });
+        };
+        let element = raw_element.read().unwrap();
+        let alignment_multiple = match vector_info.mem_type_alignment_multiple {
+            u8::MAX | 0 => 1,
+            multiple => multiple as u32,
+        };
+        let element_info = igMetaFieldInfo {
+            ark_info: raw_element.clone(),
+            _type: element._type.clone(),
+            name: element.name.clone(),
+            size: imm.calculate_size(&element, platform),
+            alignment: element.required_alignment.unwrap_or(4) * alignment_multiple,
+            offset: element.offset,
+        };
+        Arc::new(igVectorMetaField(igMemoryRefMetaField(Arc::new(element_info))))
+    }
+}
+
+impl igMetaField for igVectorMetaField {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<igMemory<igAny>>()
+    }
+
+    fn value_from_igz(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzLoaderContext,
+    ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
+        let count = read_ptr(handle, ctx.platform.clone(), endian.clone())?;
+        self.0.read_igz(registry, metadata_manager, object_stream_manager, handle, endian, ctx, count as usize)
+    }
+
+    fn value_into_igz(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgzSaverContext,
+        value: Option<igAny>,
+    ) -> Result<(), IgzSaverError> {
+        let count = match &value {
+            Some(value) => value.read().unwrap().downcast_ref::<igMemory<igAny>>().ok_or(IgzSaverError::InvalidValueType("igMemory<igAny>"))?.data.len(),
+            None => 0,
+        };
+        write_ptr(handle, count as u64, ctx.platform.clone(), endian.clone())?;
+        self.0.value_into_igz(registry, metadata_manager, object_stream_manager, handle, endian, ctx, value)
+    }
+
+    fn value_from_igx(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        ctx: &mut IgxLoaderContext,
+    ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+        // Written the same way as a memory ref, the count is the amount of items
+        self.0.value_from_igx(registry, metadata_manager, object_stream_manager, node, ctx)
+    }
+
+    fn value_into_igx(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        node: &mut IgxNode,
+        ctx: &mut IgxSaverContext,
+        value: igAny,
+    ) -> Result<(), IgxSaverError> {
+        self.0.value_into_igx(registry, metadata_manager, object_stream_manager, node, ctx, value)
+    }
+
+    fn value_from_igb(
+        &self,
+        registry: &igMetafieldRegistry,
+        metadata_manager: &igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        endian: Endian,
+        ctx: &mut IgbLoaderContext,
+    ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+        let count = read_u32(handle, endian.clone())? as usize;
+        let value = self.0.value_from_igb(registry, metadata_manager, object_stream_manager, handle, endian, ctx)?;
+        if let Some(value) = &value {
+            if let Some(memory) = value.write().unwrap().downcast_mut::<igMemory<igAny>>() {
+                memory.data.truncate(count);
+            }
+        }
+        Ok(value)
+    }
+
+    fn value_into_igb(
+        &self,
+        _registry: &igMetafieldRegistry,
+        _metadata_manager: &igMetadataManager,
+        _object_stream_manager: &igObjectStreamManager,
+        _handle: &mut Cursor<Vec<u8>>,
+        _endian: Endian,
+        _ctx: &mut IgbSaverContext,
+        _value: Option<igAny>,
+    ) -> Result<(), IgbSaverError> {
+        Err(IgbSaverError::Unsupported("igVectorMetaField"))
+    }
+}
@@ -3,4 +3,9 @@ pub(crate) mod ig_string_meta_field;
 pub(crate) mod ig_memory_ref_meta_field;
 pub(crate) mod ig_object_ref_meta_field;
 pub(crate) mod ig_size_type_meta_field;
-pub(crate) mod ig_int_meta_field;
\ No newline at end of file
+pub(crate) mod ig_int_meta_field;
+pub(crate) mod ig_inline_meta_fields;
+pub(crate) mod ig_array_meta_field;
+pub(crate) mod ig_vector_meta_field;
+pub(crate) mod ig_enum_meta_field;
+pub(crate) mod ig_bit_field_meta_field;
\ No newline at end of file
@@ -1,14 +1,18 @@
 use crate::core::ig_core_platform::IG_CORE_PLATFORM;
-use crate::core::ig_custom::{igNameList, igObjectList, igStringRefList};
+use crate::core::ig_custom::{igHashTable, igNameList, igNull, igObjectList, igStringRefList};
 use crate::core::ig_fs::Endian;
 use crate::core::ig_memory::igMemoryPool;
-use crate::core::ig_objects::{igAny, igObjectStreamManager, ObjectExt};
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::ig_objects::{igAny, igObject, igObjectStreamManager, ObjectExt};
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderError, IgbLoaderErrorKind, IgbLoaderLocation, IgbMetaObjectField};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderError, IgxLoaderErrorKind, IgxLoaderLocation, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderError, IgzLoaderErrorKind, IgzLoaderLocation};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
-use crate::core::meta::ig_xml_metadata::{ArcMetaEnum, ArcMetaField, ArkMetaObjectField, MetaObject, RawArkMetaObjectField};
-use log::{debug, error, info};
+use crate::core::meta::field::ig_metafields::igMetaField;
+use crate::core::meta::field::r#impl::ig_bit_field_meta_field::{int_from, int_value, igBitFieldMetaField, BitFieldError};
+use crate::core::meta::ig_xml_metadata::{ArcMetaEnum, ArcMetaField, ArkMetaObjectField, HashTableInfo, MetaObject, RawArkMetaObjectField};
+use log::{debug, error, info, warn};
 use phf::phf_map;
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::io::Cursor;
 use std::ops::Sub;
@@ -31,21 +35,22 @@ pub static TYPE_TO_METAOBJECT_LOOKUP: phf::Map<
     "igObjectList"            => igObjectList::construct,
     "igStringRefList"            => igStringRefList::construct,
     "igNameList"            => igNameList::construct,
+    "igHashTable"            => igHashTable::construct,
 };
 
 /// Fast structure used to manage and create new instances of metaobjects, metafields, and metaenums
 pub struct igMetadataManager {
     meta_fields: HashMap<Arc<str>, ArcMetaField>,
-    meta_enums: HashMap<Arc<str>, ArcMetaEnum>,
+    meta_enums: HashMap<Arc<str>, Arc<ArcMetaEnum>>,
     meta_objects: HashMap<Arc<str>, MetaObject>,
     object_meta_lookup: HashMap<Arc<str>, Arc<RwLock<igMetaObject>>>,
     /// The platform the metadata system is targeting. Can be stored here because we know this is not used between different loaded games.
-    platform: IG_CORE_PLATFORM,
+    pub(crate) platform: IG_CORE_PLATFORM,
     pub meta_field_registry: igMetafieldRegistry,
 }
 
 impl igMetadataManager {
-    /// Takes in igz context and sets the fields of the passed in ig_object. called from ig_igz_loader.
+    /// Takes in igz context and sets the fields of the passed in ig_object. called from ig_igz_loader. When loading leniently, fields that fail are reported to the context and object references that fail become igNull
     pub(crate) fn read_igz_fields(
         &mut self,
         object_stream_manager: &igObjectStreamManager,
@@ -53,7 +58,7 @@ impl igMetadataManager {
         endian: Endian,
         ctx: &mut IgzLoaderContext,
         ig_object: Arc<RwLock<dyn __internalObjectBase>>,
-    ) {
+    ) -> Result<(), IgzLoaderError> {
         let object_offset = handle.position();
         let meta = ig_object.read().unwrap().meta_type(self);
         let meta = meta.read().unwrap();
@@ -61,40 +66,249 @@ impl igMetadataManager {
         let fields = &meta.field_storage.name_lookup;
 
         for (name, field) in fields {
-            match name.as_ref() {
+            match field._type.as_ref() {
                 "igStaticMetaField" | "igPropertyFieldMetaField" => {
                     // ignored, not important on a per-object basis.
                 }
+                "igBitFieldMetaField" => {
+                    // unpacked from its storage field once every field is read
+                }
                 &_ => {
                     #[cfg(debug_assertions)]
                     debug!("Setting up igz field(name={}, type={})", name, field._type);
                     handle.set_position(object_offset + field.offset as u64);
+                    ctx.location = IgzLoaderLocation::Field { object: object_offset, field: name.clone() };
                     let metafield = self.meta_field_registry.get(field.clone(), self, self.platform.clone());
-                    let value = metafield.value_from_igz(&self.meta_field_registry, &self, object_stream_manager, handle, endian.clone(), ctx);
+                    let value = match metafield.value_from_igz(&self.meta_field_registry, &self, object_stream_manager, handle, endian.clone(), ctx) {
+                        Ok(value) => value,
+                        Err(kind) => {
+                            let error = ctx.error(handle.position(), kind);
+                            if !ctx.lenient {
+                                return Err(error);
+                            }
+                            ctx.warn(error);
+                            if metafield.type_id() != TypeId::of::<igObject>() {
+                                // Leave the field with its default value
+                                continue;
+                            }
+                            let null: igObject = Arc::new(RwLock::new(igNull));
+                            let value: igAny = Arc::new(RwLock::new(null));
+                            Some(value)
+                        }
+                    };
                     if let Ok(mut guard) = ig_object.write() {
-                        match guard.set_field(name.as_ref(), value) {
-                            Ok(_) => {}
-                            Err(SetObjectFieldError::FieldDoesntExist) => {
-                                error!("When reading the igz value for the field {}, got SetObjectFieldError::FieldDoesntExist", name);
-                                panic!("Alchemy Error! Check the logs.")
+                        if let Err(e) = guard.set_field(name.as_ref(), value) {
+                            let error = ctx.error(object_offset + field.offset as u64, IgzLoaderErrorKind::SetField(e));
+                            if !ctx.lenient {
+                                return Err(error);
                             }
-                            Err(SetObjectFieldError::InvalidValueType) => {
-                                error!("When reading the igz value for the field {}, got SetObjectFieldError::InvalidValueType", name);
-                                panic!("Alchemy Error! Check the logs.")
+                            ctx.warn(error);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.read_bitfields(&meta, &ig_object);
+        Ok(())
+    }
+
+    /// Takes in igx context and sets the fields of the passed in ig_object from the children of its element. called from ig_igx_loader. Fields without an element keep their default value. When loading leniently, fields that fail are reported to the context and object references that fail become igNull
+    pub(crate) fn read_igx_fields(
+        &mut self,
+        object_stream_manager: &igObjectStreamManager,
+        node: &IgxNode,
+        ctx: &mut IgxLoaderContext,
+        ig_object: Arc<RwLock<dyn __internalObjectBase>>,
+    ) -> Result<(), IgxLoaderError> {
+        let object_id = node.attribute("id").unwrap_or_default().to_string();
+        let meta = ig_object.read().unwrap().meta_type(self);
+        let meta = meta.read().unwrap();
+        debug!("igObject(name={}) fields are being set", meta.name);
+        let fields = &meta.field_storage.name_lookup;
+
+        for (name, field) in fields {
+            match field._type.as_ref() {
+                "igStaticMetaField" | "igPropertyFieldMetaField" => {
+                    // ignored, not important on a per-object basis.
+                }
+                "igBitFieldMetaField" => {
+                    // unpacked from its storage field once every field is read
+                }
+                &_ => {
+                    let Some(child) = node.child(name) else {
+                        continue;
+                    };
+                    #[cfg(debug_assertions)]
+                    debug!("Setting up igx field(name={}, type={})", name, field._type);
+                    ctx.location = IgxLoaderLocation::Field { object: object_id.clone(), field: name.clone() };
+                    let metafield = self.meta_field_registry.get(field.clone(), self, self.platform.clone());
+                    let value = if child.is_null() {
+                        Ok(None)
+                    } else {
+                        metafield.value_from_igx(&self.meta_field_registry, self, object_stream_manager, child, ctx)
+                    };
+                    let value = match value {
+                        Ok(value) => value,
+                        Err(kind) => {
+                            let error = ctx.error(kind);
+                            if !ctx.lenient {
+                                return Err(error);
                             }
-                            Err(SetObjectFieldError::InvalidValue) => {
-                                error!("When reading the igz value for the field {}, got SetObjectFieldError::InvalidValue", name);
-                                panic!("Alchemy Error! Check the logs.")
+                            ctx.warn(error);
+                            if metafield.type_id() != TypeId::of::<igObject>() {
+                                // Leave the field with its default value
+                                continue;
                             }
-                            Err(SetObjectFieldError::Unknown) => {
-                                error!("When reading the igz value for the field {}, got SetObjectFieldError::Unknown", name);
-                                panic!("Alchemy Error! Check the logs.")
+                            let null: igObject = Arc::new(RwLock::new(igNull));
+                            let value: igAny = Arc::new(RwLock::new(null));
+                            Some(value)
+                        }
+                    };
+                    if let Ok(mut guard) = ig_object.write() {
+                        if let Err(e) = guard.set_field(name.as_ref(), value) {
+                            let error = ctx.error(IgxLoaderErrorKind::SetField(e));
+                            if !ctx.lenient {
+                                return Err(error);
                             }
+                            ctx.warn(error);
                         }
                     }
                 }
             }
         }
+
+        self.read_bitfields(&meta, &ig_object);
+        ctx.location = IgxLoaderLocation::Object(object_id);
+        Ok(())
+    }
+
+    /// Takes in igb context and sets the fields of the passed in ig_object. called from ig_igb_loader. The fields listed in the igb are matched up with the fields of the metadata in order, and reading stops at the first field the two disagree on. When loading leniently, fields that fail are reported to the context and object references that fail become igNull
+    pub(crate) fn read_igb_fields(
+        &mut self,
+        object_stream_manager: &igObjectStreamManager,
+        handle: &mut Cursor<Vec<u8>>,
+        ctx: &mut IgbLoaderContext,
+        igb_fields: &[IgbMetaObjectField],
+        ig_object: Arc<RwLock<dyn __internalObjectBase>>,
+    ) -> Result<(), IgbLoaderError> {
+        let object_location = ctx.location.clone();
+        let IgbLoaderLocation::Object(object_index) = object_location else {
+            unreachable!("igb fields are only read while reading an object");
+        };
+        let meta = ig_object.read().unwrap().meta_type(self);
+        let meta = meta.read().unwrap();
+        debug!("igObject(name={}) fields are being set", meta.name);
+        let mut fields: Vec<_> = meta.field_storage.name_lookup
+            .iter()
+            .filter(|(_, field)| !matches!(field._type.as_ref(), "igStaticMetaField" | "igPropertyFieldMetaField"))
+            .collect();
+        fields.sort_by_key(|(_, field)| field.offset);
+
+        for ((name, field), igb_field) in fields.into_iter().zip(igb_fields) {
+            ctx.location = IgbLoaderLocation::Field { object: object_index, field: name.clone() };
+            let igb_type = &ctx.meta_fields[igb_field.meta_field as usize].name;
+            if igb_type.as_str() != field._type.as_ref() {
+                let error = ctx.error(handle.position(), IgbLoaderErrorKind::FieldMismatch { expected: field._type.clone(), found: igb_type.clone() });
+                if !ctx.lenient {
+                    return Err(error);
+                }
+                ctx.warn(error);
+                break;
+            }
+
+            #[cfg(debug_assertions)]
+            debug!("Setting up igb field(name={}, type={})", name, field._type);
+            let metafield = self.meta_field_registry.get(field.clone(), self, self.platform.clone());
+            let value = match metafield.value_from_igb(&self.meta_field_registry, self, object_stream_manager, handle, ctx.endian.clone(), ctx) {
+                Ok(value) => value,
+                Err(kind) => {
+                    let error = ctx.error(handle.position(), kind);
+                    if !ctx.lenient {
+                        return Err(error);
+                    }
+                    ctx.warn(error);
+                    if metafield.type_id() != TypeId::of::<igObject>() {
+                        // The size of what failed to read isn't known, so the fields after it can't be found
+                        break;
+                    }
+                    let null: igObject = Arc::new(RwLock::new(igNull));
+                    let value: igAny = Arc::new(RwLock::new(null));
+                    Some(value)
+                }
+            };
+            if let Ok(mut guard) = ig_object.write() {
+                if let Err(e) = guard.set_field(name.as_ref(), value) {
+                    let error = ctx.error(handle.position(), IgbLoaderErrorKind::SetField(e));
+                    if !ctx.lenient {
+                        return Err(error);
+                    }
+                    ctx.warn(error);
+                }
+            }
+        }
+
+        self.read_bitfields(&meta, &ig_object);
+        ctx.location = object_location;
+        Ok(())
+    }
+
+    /// Sets every bitfield of the object from the storage field it is packed into. Called once the rest of the fields have been read, as a storage field may come after its bitfields
+    fn read_bitfields(&self, meta: &igMetaObject, ig_object: &Arc<RwLock<dyn __internalObjectBase>>) {
+        for (name, field) in &meta.field_storage.name_lookup {
+            if field._type.as_ref() != "igBitFieldMetaField" {
+                continue;
+            }
+            let Some(bitfield) = igBitFieldMetaField::new(field, self) else {
+                continue;
+            };
+            let mut guard = ig_object.write().unwrap();
+            let storage = guard.get_field(&bitfield.storage_field).ok().flatten();
+            let Some(storage) = storage.and_then(|x| int_value(&*x.read().unwrap())) else {
+                warn!("bitfield {} of {} has no integer in its storage field {}", name, meta.name, bitfield.storage_field);
+                continue;
+            };
+            if let Err(e) = guard.set_field(name, Some(bitfield.unpack(storage as u64))) {
+                warn!("bitfield {} of {} couldn't be set: {:?}", name, meta.name, e);
+            }
+        }
+    }
+
+    /// The value of every storage field of the object with its bitfields packed in, by the name of the storage field. Bitfields that are null keep the bits already in their storage field
+    pub(crate) fn pack_bitfields(&self, meta: &igMetaObject, object: &igObject) -> Result<HashMap<Arc<str>, igAny>, BitFieldError> {
+        let mut packed: HashMap<Arc<str>, (TypeId, u64)> = HashMap::new();
+        for (name, field) in &meta.field_storage.name_lookup {
+            if field._type.as_ref() != "igBitFieldMetaField" {
+                continue;
+            }
+            let Some(bitfield) = igBitFieldMetaField::new(field, self) else {
+                continue;
+            };
+            let Ok(Some(value)) = object.read().unwrap().get_field(name) else {
+                continue;
+            };
+            let (type_id, storage) = match packed.get(&bitfield.storage_field) {
+                Some(storage) => *storage,
+                None => {
+                    let Some(storage_field) = meta.field_storage.name_lookup.get(&bitfield.storage_field) else {
+                        warn!("bitfield {} of {} is stored in {} which doesn't exist", name, meta.name, bitfield.storage_field);
+                        continue;
+                    };
+                    let type_id = igMetaField::type_id(&*self.meta_field_registry.get(storage_field.clone(), self, self.platform.clone()));
+                    let storage = match object.read().unwrap().get_field(&bitfield.storage_field) {
+                        Ok(Some(storage)) => int_value(&*storage.read().unwrap()).ok_or(BitFieldError::InvalidValueType("integer"))? as u64,
+                        _ => 0,
+                    };
+                    (type_id, storage)
+                }
+            };
+            packed.insert(bitfield.storage_field.clone(), (type_id, bitfield.pack(storage, &value)?));
+        }
+
+        packed
+            .into_iter()
+            .map(|(name, (type_id, storage))| Ok((name, int_from(type_id, storage as i128).ok_or(BitFieldError::InvalidValueType("integer"))?)))
+            .collect()
     }
 }
 
@@ -300,6 +514,11 @@ impl FieldStorage {
             name_lookup,
         }
     }
+
+    /// Every field, including those sharing an offset with another field such as bitfields and their storage field
+    pub fn fields(&self) -> impl Iterator<Item = &Arc<igMetaFieldInfo>> {
+        self.name_lookup.values().chain(self.offset_lookup.values().filter(|x| x.name.is_none()))
+    }
 }
 
 type InternalMetaObjectConstructor = fn(
@@ -318,6 +537,8 @@ pub struct igMetaObject {
     /// The (optional if we are the root metaobject __internalObjectBase) name of the parent igMetaObject we inherit from
     pub parent: Option<Arc<str>>,
     pub field_storage: FieldStorage,
+    /// Present when the type extends igHashTable. Describes what an empty slot of the table holds
+    pub hash_table_info: Option<HashTableInfo>,
 }
 
 /// Describes all possible errors returned from the function [igMetaObject::instantiate]
@@ -368,6 +589,24 @@ impl igMetaObject {
         let arc = fun(self, _source_pool)?;
         Ok(arc)
     }
+
+    /// The alignment an instance of this type needs. Never less than the alignment of the vtable pointer at the start of every object
+    pub fn alignment(&self, platform: IG_CORE_PLATFORM) -> u32 {
+        self.field_storage
+            .fields()
+            .map(|field| field.alignment)
+            .fold(platform.get_pointer_size() as u32, u32::max)
+    }
+
+    /// The size of an instance of this type, worked out from where the last field ends and padded to [igMetaObject::alignment]
+    pub fn size(&self, platform: IG_CORE_PLATFORM) -> u32 {
+        let end = self
+            .field_storage
+            .fields()
+            .map(|field| field.offset as u32 + field.size)
+            .fold(platform.get_pointer_size() as u32, u32::max);
+        end.next_multiple_of(self.alignment(platform))
+    }
 }
 
 static IG_META_OBJECT_NAME: &str = "igMetaObject";
@@ -401,11 +640,11 @@ impl __internalObjectBase for igMetaObject {
     }
 
     fn as_any(&self) -> &(dyn Any + Send + Sync) {
-        todo!()
+        self
     }
 
     fn as_mut_any(&mut self) -> &mut (dyn Any + Send + Sync) {
-        todo!()
+        self
     }
 }
 
@@ -441,6 +680,16 @@ impl igMetadataManager {
                 constructor: *constructor,
                 parent: parent_meta,
                 field_storage,
+                hash_table_info: current_meta.hash_table_info,
+            }
+        } else if current_meta.hash_table_info.is_some() {
+            // Every hash table is laid out the same way, only the types of the keys and values differ
+            igMetaObject {
+                name: Arc::from(type_name),
+                constructor: igHashTable::construct,
+                parent: parent_meta,
+                field_storage,
+                hash_table_info: current_meta.hash_table_info,
             }
         } else {
             igMetaObject {
@@ -448,12 +697,23 @@ impl igMetadataManager {
                 constructor: igGenericObject::new,
                 parent: parent_meta,
                 field_storage,
+                hash_table_info: None,
             }
         }
     }
 
+    /// The size of a field on the platform given. Arrays are their element size padded to the element alignment, once for every element
     pub(crate) fn calculate_size(&self, object: &RawArkMetaObjectField, platform: IG_CORE_PLATFORM) -> u32 {
-        self.meta_fields[&object._type].platform_info[&platform].size as u32
+        let info = &self.meta_fields[&object._type].platform_info[&platform];
+        match object.num {
+            Some(num) => (info.size as u32).next_multiple_of((info.align as u32).max(1)) * num as u32,
+            None => info.size as u32,
+        }
+    }
+
+    /// Names of every metafield type the metadata knows the sizing of
+    pub(crate) fn meta_field_names(&self) -> impl Iterator<Item = &Arc<str>> {
+        self.meta_fields.keys()
     }
 
     /// Loops through all available field and builds up a list of field for the current meta object taking into account overridden field.
@@ -466,15 +726,15 @@ impl igMetadataManager {
         // TODO: handle however compound fields work.
         if let Some(parent) = &parent_ref {
             let parent = self.get_or_create_meta(parent.as_ref()).unwrap();
-            let parent_fields = &parent.read().unwrap().field_storage.offset_lookup;
+            let parent = parent.read().unwrap();
             let mut new_fields: Vec<Arc<igMetaFieldInfo>> = Vec::new();
 
-            for parent_field in parent_fields {
+            for parent_field in parent.field_storage.fields() {
                 let mut overriden = false;
 
                 for override_field in &current_object.overriden_fields {
                     let override_field = override_field.read().unwrap();
-                    if *parent_field.0 == override_field.offset {
+                    if parent_field.offset == override_field.offset && (parent_field.name.is_none() || parent_field.name == override_field.name) {
                         new_fields.push(Arc::new(igMetaFieldInfo {
                             ark_info: Arc::new(RwLock::new(override_field.clone())),
                             _type: override_field.clone()._type,
@@ -493,7 +753,7 @@ impl igMetadataManager {
                 }
 
                 if !overriden {
-                    new_fields.push(parent_field.1.clone())
+                    new_fields.push(parent_field.clone())
                 }
             }
 
@@ -555,7 +815,7 @@ impl igMetadataManager {
 
         let mut meta_enums = HashMap::with_capacity(enum_list.len());
         for enm in enum_list {
-            meta_enums.insert(enm.ref_name.clone(), enm);
+            meta_enums.insert(enm.ref_name.clone(), Arc::new(enm));
         }
 
         let mut meta_objects = HashMap::with_capacity(object_list.len());
@@ -573,6 +833,31 @@ impl igMetadataManager {
         }
     }
 
+    /// Returns true when the metadata contains a meta object with this name
+    pub fn has_meta(&self, type_name: &str) -> bool {
+        self.meta_objects.contains_key(type_name)
+    }
+
+    /// Finds the meta enum with the name given, such as the one an igEnumMetaField lists
+    pub(crate) fn get_meta_enum(&self, name: &str) -> Option<Arc<ArcMetaEnum>> {
+        self.meta_enums.get(name).cloned()
+    }
+
+    /// The inverse of [igMetadataManager::get_enum]. Finds the index of the enum value with the name given
+    pub fn get_enum_index<T: MetaEnumImpl>(&self, value_name: &str) -> Option<usize> {
+        self.meta_enums
+            .get(T::META_KEY)?
+            .values
+            .iter()
+            .position(|value| value.name.as_ref() == value_name)
+    }
+
+    /// Same as [igMetadataManager::get_enum] but returns [None] instead of panicking when the index or the enum is unknown
+    pub fn try_get_enum<T: MetaEnumImpl>(&self, value_index: usize) -> Option<T> {
+        let value = self.meta_enums.get(T::META_KEY)?.values.get(value_index)?;
+        T::from_str(&value.name).ok()
+    }
+
     pub fn get_enum<T: MetaEnumImpl>(&self, value_index: usize) -> T {
         let value = &self.meta_enums[T::META_KEY].values[value_index];
         if let Ok(return_value) = T::from_str(&value.name) {
@@ -593,3 +878,32 @@ pub trait MetaEnumImpl: FromStr {
     /// The name of the enum you are from the metadata. For example: "IG_CORE_PLATFORM". See metaenums.xml for more enum's to choose from.
     const META_KEY: &'static str;
 }
+
+/// Value of an igEnumMetaField. The number stored in the file along with the name metaenums.xml gives it, which is [None] when the number isn't one of the enum's values.
+/// When saved the name is used over the number, so either can be changed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct igEnumValue {
+    pub name: Option<Arc<str>>,
+    pub value: i32,
+}
+
+impl igEnumValue {
+    /// A value saved by name. The number is looked up in the meta enum when saving
+    pub fn from_name(name: &str) -> Self {
+        igEnumValue { name: Some(Arc::from(name)), value: 0 }
+    }
+
+    /// A value saved as the number given, whether the meta enum has a name for it or not
+    pub fn from_value(value: i32) -> Self {
+        igEnumValue { name: None, value }
+    }
+}
+
+impl std::fmt::Display for igEnumValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
@@ -42,7 +42,9 @@ type igMetaObjectXml = Vec<MetaObject>;
 /// Stores extra information about what the hash table expects
 #[derive(Debug, Clone)]
 pub struct HashTableInfo {
+    /// What the value of an empty slot holds, written the way Alchemy prints it. Null references are "(null)"
     pub invalid_value: String,
+    /// What the key of an empty slot holds, written the way Alchemy prints it. Null references are "(null)"
     pub invalid_key: String,
 }
 
@@ -89,9 +91,9 @@ pub struct RawArkMetaObjectField {
     pub meta_object: Option<Arc<str>>,
     /// Some field will require a specific alignment otherwise they won't work. These types will specify it. I am unsure specifically which ones do this.
     pub required_alignment: Option<u32>,
-    /// Present when _type is equal to "igVectorMetaField"
+    /// Present when _type is equal to "igVectorMetaField" or "igVectorArrayMetaField"
     pub ig_vector_info: Option<VectorInfo>,
-    /// Present when _type is equal to "igMemoryRefMetaField"
+    /// Present when _type is equal to "igMemoryRefMetaField" or "igMemoryRefArrayMetaField"
     pub ig_memory_ref_info: Option<ArkMetaObjectField>,
     /// Present when _type is equal to "igBitFieldMetaField"
     pub ig_bit_shift_info: Option<Arc<RwLock<BitShiftInfo>>>,
@@ -101,6 +103,8 @@ pub struct RawArkMetaObjectField {
     pub ig_meta_enum: Option<Arc<str>>,
     /// Present when _type is equal to "igStaticMetaField"
     pub ig_static_info: Option<ArkMetaObjectField>,
+    /// Present when _type is one of the "*ArrayMetaField" types. The amount of elements stored inline in the object
+    pub num: Option<u16>,
 }
 
 #[derive(Debug, Clone)]
@@ -338,7 +342,7 @@ fn load_meta_objects(path: &PathBuf) -> Result<Vec<MetaObject>, String> {
                 b"overriddenmetafields" => field_type = FieldType::OverridenField,
                 b"metafields" => field_type = FieldType::NewField,
                 b"compoundfields" => field_type = FieldType::CompoundField,
-                b"metafield" => on_metafield_tag(
+                b"metafield" | b"hashtable" => on_metafield_tag(
                     &mut current_meta_object,
                     &mut current_meta_field,
                     &mut field_type,
@@ -454,10 +458,10 @@ fn on_metafield_tag(
             for result in e.attributes() {
                 let attrib = result.unwrap();
                 match attrib.key.local_name().as_ref() {
-                    b"invalid_value" => {
+                    b"invalidvalue" => {
                         invalid_value = Some(String::from(attrib.unescape_value().unwrap()));
                     }
-                    b"invalid_key" => {
+                    b"invalidkey" => {
                         invalid_key = Some(String::from(attrib.unescape_value().unwrap()));
                     }
                     _ => {
@@ -538,7 +542,7 @@ fn on_metafield_tag(
                         *current_meta_field = child_metafield;
                     }
 
-                    "igVectorMetaField" => {
+                    "igVectorMetaField" | "igVectorArrayMetaField" => {
                         let child_metafield = process_new_metafield(&e);
                         current_meta_field_ref
                             .write()
@@ -602,7 +606,7 @@ fn on_metafield_tag(
 
                         *current_meta_field = child_metafield;
                     }
-                    "igMemoryRefMetaField" => {
+                    "igMemoryRefMetaField" | "igMemoryRefArrayMetaField" => {
                         // This ONE type is driving me insane.
                         // We ARE a child, but we are still burdened with the responsibility
                         // to process an entire new metafield which itself has a chance of having its own metafields...
@@ -685,6 +689,7 @@ fn process_new_metafield(e: &BytesStart) -> Option<ArkMetaObjectField> {
         _type: None,
     };
     let mut ig_meta_enum: Option<Arc<str>> = None;
+    let mut num: Option<u16> = None;
     let mut ig_vector_info = VectorInfo {
         field: None,
         mem_type_alignment_multiple: u8::MAX,
@@ -730,6 +735,12 @@ fn process_new_metafield(e: &BytesStart) -> Option<ArkMetaObjectField> {
                 ig_vector_info.mem_type_alignment_multiple =
                     u8::from_str_radix(&without_prefix, 16).unwrap();
             }
+            // *ArrayMetaField
+            b"num" => {
+                // Written in decimal like requiredAlignment
+                let raw = String::from(attrib.unescape_value().unwrap());
+                num = Some(u16::from_str(&raw).unwrap());
+            }
             // igMemoryRefMetaField
             // ...
             // igPropertyFieldMetaField
@@ -748,7 +759,7 @@ fn process_new_metafield(e: &BytesStart) -> Option<ArkMetaObjectField> {
 
     // Don't store vector info when it's not a igVectorMetaField to not confuse users of metadata
     let mut optional_ig_vector = None;
-    if _type.clone().unwrap().as_ref() == "igVectorMetaField" {
+    if matches!(_type.clone().unwrap().as_ref(), "igVectorMetaField" | "igVectorArrayMetaField") {
         optional_ig_vector = Some(ig_vector_info)
     }
 
@@ -764,5 +775,6 @@ fn process_new_metafield(e: &BytesStart) -> Option<ArkMetaObjectField> {
         ig_property_info: None, // Requires child metafield to get more information
         ig_meta_enum,
         ig_static_info: None, // Requires child metafield to get more information
+        num,
     })))
 }
@@ -1,2 +1,20 @@
+use std::fmt::{Display, Formatter};
+
 pub struct IgbSaverContext;
-pub enum IgbSaverError {}
\ No newline at end of file
+
+/// Describes everything that can go wrong while writing an igb
+#[derive(Debug)]
+pub enum IgbSaverError {
+    /// Returned when a metafield, named by its type, can't be written to an igb yet
+    Unsupported(&'static str),
+}
+
+impl Display for IgbSaverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgbSaverError::Unsupported(metafield) => write!(f, "{} can't be saved to an igb", metafield),
+        }
+    }
+}
+
+impl std::error::Error for IgbSaverError {}
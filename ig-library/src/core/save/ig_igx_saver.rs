@@ -1,2 +1,253 @@
-pub struct IgxSaverContext;
-pub enum IgxSaverError {}
\ No newline at end of file
+use crate::core::ig_objects::{igObject, igObjectDirectory, igObjectStreamManager};
+use crate::core::load::ig_igx_loader::{IgxNode, IGX_ROOT};
+use crate::core::meta::field::r#impl::ig_bit_field_meta_field::BitFieldError;
+use crate::core::meta::ig_metadata_manager::{igMetaObject, igMetadataManager};
+use crate::core::save::ig_igz_saver::{collect_external_names, object_key};
+use crate::util::ig_name::igName;
+use log::warn;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// Describes everything that can go wrong while writing an igx
+#[derive(Debug)]
+pub enum IgxSaverError {
+    /// Returned when an object's type has no metadata
+    UnknownType(Arc<str>),
+    /// Returned when an object couldn't provide the value of a field its metadata lists
+    MissingField { object: Arc<str>, field: Arc<str> },
+    /// Returned when a metafield is handed a value that isn't the type it writes
+    InvalidValueType(&'static str),
+    /// Returned when a fixed size array is handed a different amount of elements than its metadata lists
+    InvalidArrayLength { expected: usize, found: usize },
+    /// Returned when an enum value is saved by a name its meta enum doesn't have
+    UnknownEnumValue { meta_enum: Arc<str>, name: Arc<str> },
+    /// Returned when a bitfield is given a value that needs more bits than it has
+    BitFieldOutOfRange { field: Arc<str>, bits: u8 },
+    /// Returned when writing to the output failed
+    Io(std::io::Error),
+}
+
+impl Display for IgxSaverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgxSaverError::UnknownType(name) => write!(f, "no metadata exists for the type {}", name),
+            IgxSaverError::MissingField { object, field } => write!(f, "{} did not provide a value for the field {}", object, field),
+            IgxSaverError::InvalidValueType(expected) => write!(f, "metafield expected a value of type {}", expected),
+            IgxSaverError::InvalidArrayLength { expected, found } => write!(f, "array expected {} elements but was given {}", expected, found),
+            IgxSaverError::UnknownEnumValue { meta_enum, name } => write!(f, "{} has no value named {}", meta_enum, name),
+            IgxSaverError::BitFieldOutOfRange { field, bits } => write!(f, "the value of bitfield {} doesn't fit in {} bits", field, bits),
+            IgxSaverError::Io(e) => write!(f, "failed to write igx: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IgxSaverError {}
+
+impl From<BitFieldError> for IgxSaverError {
+    fn from(value: BitFieldError) -> Self {
+        match value {
+            BitFieldError::InvalidValueType(expected) => IgxSaverError::InvalidValueType(expected),
+            BitFieldError::UnknownEnumValue { meta_enum, name } => IgxSaverError::UnknownEnumValue { meta_enum, name },
+            BitFieldError::ValueOutOfRange { field, bits } => IgxSaverError::BitFieldOutOfRange { field, bits },
+        }
+    }
+}
+
+impl From<std::io::Error> for IgxSaverError {
+    fn from(value: std::io::Error) -> Self {
+        IgxSaverError::Io(value)
+    }
+}
+
+/// Writes bytes as text that [parse_hex](crate::core::load::ig_igx_loader::parse_hex) can read back
+pub(crate) fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+pub struct IgxSaverContext {
+    pending_objects: VecDeque<(igObject, u32)>,
+    object_ids: HashMap<usize, u32>,
+    /// Objects that live in another loaded directory, along with the (namespace, name) they can be found with
+    external_names: HashMap<usize, (igName, igName)>,
+}
+
+impl IgxSaverContext {
+    pub(crate) fn new() -> Self {
+        IgxSaverContext {
+            pending_objects: VecDeque::new(),
+            object_ids: HashMap::new(),
+            external_names: HashMap::new(),
+        }
+    }
+
+    /// Points an element at an object. Objects belonging to another directory become external references, anything else is queued to be written into this igx
+    pub fn write_object_ref(&mut self, node: &mut IgxNode, object: &igObject) {
+        let key = object_key(object);
+        if let Some(id) = self.object_ids.get(&key) {
+            node.set_attribute("ref", id);
+            return;
+        }
+        if let Some((namespace, name)) = self.external_names.get(&key) {
+            write_name(node, namespace, "namespace", "namespaceHash");
+            write_name(node, name, "name", "nameHash");
+            return;
+        }
+
+        let guard = object.read().unwrap();
+        if let Some(meta) = guard.as_any().downcast_ref::<igMetaObject>() {
+            node.set_attribute("metaobject", &meta.name);
+            return;
+        }
+        if guard.object_name().as_ref() == "igNull" {
+            warn!("Writing an igNull as a null reference. The object it replaced failed to load");
+            node.set_null();
+            return;
+        }
+        drop(guard);
+
+        let id = self.add_object(object);
+        node.set_attribute("ref", id);
+    }
+
+    /// Gives an object an id and queues it to be written
+    fn add_object(&mut self, object: &igObject) -> u32 {
+        let id = self.object_ids.len() as u32;
+        self.object_ids.insert(object_key(object), id);
+        self.pending_objects.push_back((object.clone(), id));
+        id
+    }
+}
+
+/// Writes a name as a string when its hash can be made from the string again, otherwise by its hash. Strings that don't give back the hash are kept next to it,
+/// unless they were only looked up from the hash
+fn write_name(node: &mut IgxNode, name: &igName, string: &str, hash: &str) {
+    if let Some(value) = name.source_string() {
+        node.set_attribute(string, value);
+        return;
+    }
+    if let (Some(value), false) = (&name.string, name.recovered) {
+        node.set_attribute(string, value);
+    }
+    node.set_attribute(hash, format!("{:#x}", name.hash));
+}
+
+pub struct igIGXSaver;
+
+impl igIGXSaver {
+    /// Writes the directory's objects, names and dependencies as an igx. Objects found in the name lists of other directories loaded by the [igObjectStreamManager] are written as external references
+    pub fn save(
+        imm: &mut igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        dir: &igObjectDirectory,
+    ) -> Result<Vec<u8>, IgxSaverError> {
+        let mut ctx = IgxSaverContext::new();
+        ctx.external_names = collect_external_names(object_stream_manager, dir);
+
+        let mut root = IgxNode::new(IGX_ROOT);
+        if let Some(name) = &dir.name.string {
+            root.set_attribute("name", name);
+        }
+
+        for dependency in dir.dependencies.iter() {
+            let Ok(dependency) = dependency.try_read() else {
+                continue;
+            };
+            let mut node = IgxNode::new("dependency");
+            node.set_attribute("name", dependency.name.string.clone().unwrap_or_else(|| dependency.path.clone()));
+            node.set_attribute("path", &dependency.path);
+            root.children.push(node);
+        }
+
+        let object_list: igObject = dir.object_list.clone();
+        root.set_attribute("objectList", ctx.add_object(&object_list));
+        if dir.use_name_list {
+            let name_list: igObject = dir.name_list.clone();
+            root.set_attribute("nameList", ctx.add_object(&name_list));
+        }
+
+        while let Some((object, id)) = ctx.pending_objects.pop_front() {
+            let node = igIGXSaver::write_object(imm, object_stream_manager, &mut ctx, &object, id)?;
+            root.children.push(node);
+        }
+
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+        write_node(&mut writer, &root)?;
+        Ok(writer.into_inner())
+    }
+
+    /// Writes every field of an object into an object element. Objects it references are queued on the context
+    fn write_object(
+        imm: &mut igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        ctx: &mut IgxSaverContext,
+        object: &igObject,
+        id: u32,
+    ) -> Result<IgxNode, IgxSaverError> {
+        let object_name = object.read().unwrap().object_name();
+        if !imm.has_meta(&object_name) {
+            return Err(IgxSaverError::UnknownType(object_name));
+        }
+        let meta = object.read().unwrap().meta_type(imm);
+        let meta = meta.read().unwrap();
+
+        let mut node = IgxNode::new("object");
+        node.set_attribute("type", &meta.name);
+        node.set_attribute("id", id);
+        node.set_attribute("pool", format!("{:?}", object.read().unwrap().internal_pool()));
+
+        let bitfields = imm.pack_bitfields(&meta, object)?;
+        let mut fields: Vec<_> = meta.field_storage.name_lookup.iter().collect();
+        fields.sort_by_key(|(_, field)| field.offset);
+        for (name, field) in fields {
+            match field._type.as_ref() {
+                "igStaticMetaField" | "igPropertyFieldMetaField" => {
+                    // ignored, not stored on a per-object basis.
+                }
+                "igBitFieldMetaField" => {
+                    // packed into its storage field instead
+                }
+                &_ => {
+                    let value = match bitfields.get(name) {
+                        Some(value) => Ok(Some(value.clone())),
+                        None => object.read().unwrap().get_field(name),
+                    };
+                    let value = value.map_err(|_| IgxSaverError::MissingField {
+                        object: meta.name.clone(),
+                        field: name.clone(),
+                    })?;
+                    let metafield = imm.meta_field_registry.get(field.clone(), imm, imm.platform.clone());
+                    let mut child = IgxNode::new(name);
+                    match value {
+                        Some(value) => metafield.value_into_igx(&imm.meta_field_registry, imm, object_stream_manager, &mut child, ctx, value)?,
+                        None => child.set_null(),
+                    }
+                    node.children.push(child);
+                }
+            }
+        }
+
+        Ok(node)
+    }
+}
+
+/// Elements with children are written across multiple lines, value elements on a single one
+fn write_node(writer: &mut Writer<Vec<u8>>, node: &IgxNode) -> std::io::Result<()> {
+    let start = BytesStart::new(node.name.as_str())
+        .with_attributes(node.attributes.iter().map(|(key, value)| (key.as_str(), value.as_str())));
+    if node.children.is_empty() && node.text.is_empty() {
+        return writer.write_event(Event::Empty(start));
+    }
+
+    writer.write_event(Event::Start(start))?;
+    if node.children.is_empty() {
+        writer.write_event(Event::Text(BytesText::new(&node.text)))?;
+    }
+    for child in &node.children {
+        write_node(writer, child)?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(node.name.as_str())))
+}
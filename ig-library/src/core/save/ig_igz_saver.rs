@@ -1,8 +1,711 @@
 use crate::core::ig_core_platform::IG_CORE_PLATFORM;
+use crate::core::ig_fs::Endian;
+use crate::core::ig_memory::igMemoryPool;
+use crate::core::ig_objects::{igObject, igObjectDirectory, igObjectStreamManager};
+use crate::core::load::ig_igz_loader::{get_attribute_location, get_chunk_descriptor_start, IGZ_LITTLE_ENDIAN_MAGIC};
+use crate::core::meta::field::r#impl::ig_bit_field_meta_field::BitFieldError;
+use crate::core::meta::ig_metadata_manager::{igMetaObject, igMetadataManager};
+use crate::util::byteorder_fixes::{write_ptr, write_string, write_u32, write_u64};
+use crate::util::ig_name::igName;
+use log::warn;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+
+/// Sections (and the fixups before them) start on this boundary
+const SECTION_ALIGNMENT: u64 = 0x800;
+/// The header has room for this many chunk descriptors. The first one always describes the fixups
+const MAX_CHUNKS: usize = 0x20;
+/// Every fixup starts with a magic, a count, a length and the start of its data
+const FIXUP_HEADER_SIZE: u32 = 0x10;
+
+/// Describes everything that can go wrong while writing an igz
+#[derive(Debug)]
+pub enum IgzSaverError {
+    /// Returned when the igz version requested can't be written
+    UnsupportedVersion(u32),
+    /// Returned when the platform is missing from the IG_CORE_PLATFORM meta enum
+    UnknownPlatform(IG_CORE_PLATFORM),
+    /// Returned when an object's type has no metadata
+    UnknownType(Arc<str>),
+    /// Returned when an object couldn't provide the value of a field its metadata lists
+    MissingField { object: Arc<str>, field: Arc<str> },
+    /// Returned when a metafield is handed a value that isn't the type it writes
+    InvalidValueType(&'static str),
+    /// Returned when a fixed size array is handed a different amount of elements than its metadata lists
+    InvalidArrayLength { expected: usize, found: usize },
+    /// Returned when an enum value is saved by a name its meta enum doesn't have
+    UnknownEnumValue { meta_enum: Arc<str>, name: Arc<str> },
+    /// Returned when a bitfield is given a value that needs more bits than it has
+    BitFieldOutOfRange { field: Arc<str>, bits: u8 },
+    /// Returned when the objects use more memory pools than the header can describe
+    TooManySections,
+    /// Returned when a section grows past what a serialized offset can address
+    SectionTooLarge(igMemoryPool),
+    /// Returned when the offsets of a runtime fixup aren't increasing or aligned to 4 bytes, so they can't be packed
+    UnpackableOffset { offset: u64, previous: u64 },
+    /// Returned when writing to the output failed
+    Io(std::io::Error),
+}
+
+impl Display for IgzSaverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IgzSaverError::UnsupportedVersion(version) => write!(f, "igz version {:#x} can't be saved", version),
+            IgzSaverError::UnknownPlatform(platform) => write!(f, "{:?} is not in the IG_CORE_PLATFORM meta enum", platform),
+            IgzSaverError::UnknownType(name) => write!(f, "no metadata exists for the type {}", name),
+            IgzSaverError::MissingField { object, field } => write!(f, "{} did not provide a value for the field {}", object, field),
+            IgzSaverError::InvalidValueType(expected) => write!(f, "metafield expected a value of type {}", expected),
+            IgzSaverError::InvalidArrayLength { expected, found } => write!(f, "array expected {} elements but was given {}", expected, found),
+            IgzSaverError::UnknownEnumValue { meta_enum, name } => write!(f, "{} has no value named {}", meta_enum, name),
+            IgzSaverError::BitFieldOutOfRange { field, bits } => write!(f, "the value of bitfield {} doesn't fit in {} bits", field, bits),
+            IgzSaverError::TooManySections => write!(f, "objects use more than {} memory pools", MAX_CHUNKS - 1),
+            IgzSaverError::SectionTooLarge(pool) => write!(f, "the {:?} section is too large to address", pool),
+            IgzSaverError::UnpackableOffset { offset, previous } => write!(f, "can't pack the offset {:#x} after {:#x} into a runtime fixup", offset, previous),
+            IgzSaverError::Io(e) => write!(f, "failed to write igz: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IgzSaverError {}
+
+impl From<BitFieldError> for IgzSaverError {
+    fn from(value: BitFieldError) -> Self {
+        match value {
+            BitFieldError::InvalidValueType(expected) => IgzSaverError::InvalidValueType(expected),
+            BitFieldError::UnknownEnumValue { meta_enum, name } => IgzSaverError::UnknownEnumValue { meta_enum, name },
+            BitFieldError::ValueOutOfRange { field, bits } => IgzSaverError::BitFieldOutOfRange { field, bits },
+        }
+    }
+}
+
+impl From<std::io::Error> for IgzSaverError {
+    fn from(value: std::io::Error) -> Self {
+        IgzSaverError::Io(value)
+    }
+}
+
+/// The runtime fixups listing where pointers needing special treatment live. These are what the loader stores in [RuntimeFields](crate::core::load::ig_igz_loader::RuntimeFields)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IgzRuntimeFixup {
+    /// ROFS. The pointer is a serialized offset to an object or memory in the igz
+    Offsets,
+    /// RSTR. The pointer is a serialized offset to a string in the igz
+    StringReferences,
+    /// REXT. The pointer is an index into the EXID list
+    Externals,
+    /// RNEX. The pointer is an index into the EXNM list
+    NamedExternals,
+    /// RHND. The pointer is an index into the handles of the EXNM list
+    Handles,
+    /// RMHN. The pointer is a memory handle
+    MemoryHandles,
+}
+
+impl IgzRuntimeFixup {
+    /// The order runtime fixups are written in
+    const ALL: [IgzRuntimeFixup; 6] = [
+        IgzRuntimeFixup::Offsets,
+        IgzRuntimeFixup::StringReferences,
+        IgzRuntimeFixup::Externals,
+        IgzRuntimeFixup::NamedExternals,
+        IgzRuntimeFixup::Handles,
+        IgzRuntimeFixup::MemoryHandles,
+    ];
+
+    fn magic(&self) -> &'static [u8; 4] {
+        match self {
+            IgzRuntimeFixup::Offsets => b"ROFS",
+            IgzRuntimeFixup::StringReferences => b"RSTR",
+            IgzRuntimeFixup::Externals => b"REXT",
+            IgzRuntimeFixup::NamedExternals => b"RNEX",
+            IgzRuntimeFixup::Handles => b"RHND",
+            IgzRuntimeFixup::MemoryHandles => b"RMHN",
+        }
+    }
+}
+
+/// A chunk of memory belonging to a single pool. Objects, strings and memory each get their own block, and blocks sharing a pool are packed into the same section when the file is laid out
+struct IgzBlock {
+    pool: igMemoryPool,
+    alignment: u32,
+    data: Vec<u8>,
+}
+
+/// A pointer written into a block that has to be listed in one of the runtime fixups
+struct Relocation {
+    block: usize,
+    position: u64,
+    kind: IgzRuntimeFixup,
+    /// The block being pointed to. Set on pointers that get patched with a serialized offset once the layout is known
+    target: Option<usize>,
+}
 
 pub struct IgzSaverContext {
-    pub platform: IG_CORE_PLATFORM
+    pub version: u32,
+    pub platform: IG_CORE_PLATFORM,
+    blocks: Vec<IgzBlock>,
+    /// The block metafields are currently writing into. Relocations are recorded against it
+    current_block: usize,
+    pending_objects: VecDeque<(igObject, usize)>,
+    object_blocks: HashMap<usize, usize>,
+    string_blocks: HashMap<String, usize>,
+    relocations: Vec<Relocation>,
+    vtbl_list: Vec<Arc<str>>,
+    string_list: Vec<String>,
+    string_lookup: HashMap<String, u32>,
+    /// (name hash, namespace hash) pairs written to EXID
+    external_list: Vec<(u32, u32)>,
+    /// Raw handles written to EXNM
+    named_external_list: Vec<u64>,
+    /// Objects that live in another loaded igz, along with the (namespace, name) they can be found with
+    external_names: HashMap<usize, (igName, igName)>,
+}
+
+/// The section a block was placed in and the offset of the block inside it
+type BlockLocation = (usize, u64);
+
+/// Identifies an object by its allocation so shared references are only written once
+pub(crate) fn object_key(object: &igObject) -> usize {
+    Arc::as_ptr(object) as *const () as usize
+}
+
+/// Records which objects can be found in the name lists of other loaded directories so they are written as references instead of copied in. Returns the (namespace, name) of each by [object_key]
+pub(crate) fn collect_external_names(object_stream_manager: &igObjectStreamManager, dir: &igObjectDirectory) -> HashMap<usize, (igName, igName)> {
+    let owned: HashSet<usize> = dir.object_list.read().unwrap().iter().map(|object| object_key(&object)).collect();
+    let mut external_names = HashMap::new();
+
+    for other in object_stream_manager.path_to_directory_lookup.values() {
+        let Ok(other) = other.try_read() else {
+            continue;
+        };
+        if other.path == dir.path || !other.use_name_list {
+            continue;
+        }
+
+        let objects = other.object_list.read().unwrap();
+        let names = other.name_list.read().unwrap();
+        for (object, name) in objects.iter().zip(names.iter()) {
+            let key = object_key(&object);
+            if !owned.contains(&key) {
+                external_names.entry(key).or_insert_with(|| (other.name.clone(), name));
+            }
+        }
+    }
+
+    external_names
+}
+
+impl IgzSaverContext {
+    pub(crate) fn new(version: u32, platform: IG_CORE_PLATFORM) -> Self {
+        IgzSaverContext {
+            version,
+            platform,
+            blocks: vec![],
+            current_block: 0,
+            pending_objects: VecDeque::new(),
+            object_blocks: HashMap::new(),
+            string_blocks: HashMap::new(),
+            relocations: vec![],
+            vtbl_list: vec![],
+            string_list: vec![],
+            string_lookup: HashMap::new(),
+            external_list: vec![],
+            named_external_list: vec![],
+            external_names: HashMap::new(),
+        }
+    }
+
+    /// Creates a new empty block of memory in the pool given. Returns the index of the block
+    pub fn add_block(&mut self, pool: igMemoryPool, alignment: u32) -> usize {
+        self.blocks.push(IgzBlock {
+            pool,
+            alignment: alignment.max(1),
+            data: vec![],
+        });
+        self.blocks.len() - 1
+    }
+
+    /// Replaces the contents of a block. Positions recorded while the block was current are relative to the start of this data
+    pub fn set_block_data(&mut self, block: usize, data: Vec<u8>) {
+        self.blocks[block].data = data;
+    }
+
+    /// Makes the block given the one being written into. Returns the previous block so it can be restored afterward
+    pub fn set_current_block(&mut self, block: usize) -> usize {
+        std::mem::replace(&mut self.current_block, block)
+    }
+
+    /// Lists the pointer at the handle's position in a runtime fixup. Call this before writing the pointer
+    pub fn add_runtime_fixup(&mut self, handle: &Cursor<Vec<u8>>, kind: IgzRuntimeFixup) {
+        self.relocations.push(Relocation {
+            block: self.current_block,
+            position: handle.position(),
+            kind,
+            target: None,
+        });
+    }
+
+    /// Writes a pointer to the start of a block. The value is filled in once the file is laid out
+    pub fn write_block_ref(&mut self, handle: &mut Cursor<Vec<u8>>, endian: Endian, block: usize) -> Result<(), IgzSaverError> {
+        self.relocations.push(Relocation {
+            block: self.current_block,
+            position: handle.position(),
+            kind: IgzRuntimeFixup::Offsets,
+            target: Some(block),
+        });
+        write_ptr(handle, 0, self.platform.clone(), endian)?;
+        Ok(())
+    }
+
+    /// Writes a pointer to a string. Strings with the same contents share the same memory
+    pub fn write_string_ref(&mut self, handle: &mut Cursor<Vec<u8>>, endian: Endian, string: &str) -> Result<(), IgzSaverError> {
+        let block = match self.string_blocks.get(string) {
+            Some(block) => *block,
+            None => {
+                let block = self.add_block(igMemoryPool::String, 1);
+                let mut data = Cursor::new(Vec::with_capacity(string.len() + 1));
+                write_string(&mut data, string)?;
+                self.set_block_data(block, data.into_inner());
+                self.string_blocks.insert(string.to_string(), block);
+                block
+            }
+        };
+
+        self.relocations.push(Relocation {
+            block: self.current_block,
+            position: handle.position(),
+            kind: IgzRuntimeFixup::StringReferences,
+            target: Some(block),
+        });
+        write_ptr(handle, 0, self.platform.clone(), endian)?;
+        Ok(())
+    }
+
+    /// Writes a pointer to an object. Objects belonging to another igz become external references, anything else is queued to be written into this igz
+    pub fn write_object_ref(&mut self, handle: &mut Cursor<Vec<u8>>, endian: Endian, object: &igObject) -> Result<(), IgzSaverError> {
+        let key = object_key(object);
+        if let Some(block) = self.object_blocks.get(&key) {
+            return self.write_block_ref(handle, endian, *block);
+        }
+        if let Some((namespace, name)) = self.external_names.get(&key).cloned() {
+            return self.write_external_ref(handle, endian, &namespace, &name);
+        }
+
+        let guard = object.read().unwrap();
+        if let Some(meta) = guard.as_any().downcast_ref::<igMetaObject>() {
+            let name = igName::new(meta.name.to_string());
+            drop(guard);
+            return self.write_external_ref(handle, endian, &igName::new("metaobject".to_string()), &name);
+        }
+        if guard.object_name().as_ref() == "igNull" {
+            warn!("Writing an igNull as a null reference. The object it replaced failed to load");
+            write_ptr(handle, 0, self.platform.clone(), endian)?;
+            return Ok(());
+        }
+        drop(guard);
+
+        let block = self.add_object(object);
+        self.write_block_ref(handle, endian, block)
+    }
+
+    /// Writes a reference to an object living in another igz. Names with a known string go in EXNM, names only known by hash go in EXID.
+    /// EXNM names are hashed again when loaded, so a string that wouldn't give back the same hash counts as unknown
+    fn write_external_ref(&mut self, handle: &mut Cursor<Vec<u8>>, endian: Endian, namespace: &igName, name: &igName) -> Result<(), IgzSaverError> {
+        let index = match (namespace.source_string(), name.source_string()) {
+            (Some(namespace), Some(name)) => {
+                let raw = ((self.add_string(namespace) as u64) << 32) | self.add_string(name) as u64;
+                self.add_runtime_fixup(handle, IgzRuntimeFixup::NamedExternals);
+                match self.named_external_list.iter().position(|x| *x == raw) {
+                    Some(index) => index,
+                    None => {
+                        self.named_external_list.push(raw);
+                        self.named_external_list.len() - 1
+                    }
+                }
+            }
+            _ => {
+                let pair = (name.hash, namespace.hash);
+                self.add_runtime_fixup(handle, IgzRuntimeFixup::Externals);
+                match self.external_list.iter().position(|x| *x == pair) {
+                    Some(index) => index,
+                    None => {
+                        self.external_list.push(pair);
+                        self.external_list.len() - 1
+                    }
+                }
+            }
+        };
+
+        write_ptr(handle, index as u64, self.platform.clone(), endian)?;
+        Ok(())
+    }
+
+    /// Adds a string to TSTR. Returns its index
+    fn add_string(&mut self, string: &str) -> u32 {
+        if let Some(index) = self.string_lookup.get(string) {
+            return *index;
+        }
+        let index = self.string_list.len() as u32;
+        self.string_list.push(string.to_string());
+        self.string_lookup.insert(string.to_string(), index);
+        index
+    }
+
+    /// Creates a block for an object and queues it to be written
+    fn add_object(&mut self, object: &igObject) -> usize {
+        let pool = *object.read().unwrap().internal_pool();
+        let block = self.add_block(pool, self.platform.get_pointer_size() as u32);
+        self.object_blocks.insert(object_key(object), block);
+        self.pending_objects.push_back((object.clone(), block));
+        block
+    }
+
+    /// Index of the type in TMET, adding it when it hasn't been seen yet
+    fn vtbl_index(&mut self, name: &Arc<str>) -> usize {
+        match self.vtbl_list.iter().position(|x| x == name) {
+            Some(index) => index,
+            None => {
+                self.vtbl_list.push(name.clone());
+                self.vtbl_list.len() - 1
+            }
+        }
+    }
+
+    /// The inverse of [IgzLoaderContext::deserialize_offset](crate::core::load::ig_igz_loader::IgzLoaderContext::deserialize_offset)
+    pub fn serialize_offset(&self, section: usize, offset: u64) -> u64 {
+        if self.version <= 6 {
+            ((section as u64) << 0x18) | offset
+        } else {
+            ((section as u64) << 0x1B) | offset
+        }
+    }
+
+    /// Packs every block into one section per memory pool, in the order the pools were first used. Returns the sections and where each block ended up
+    fn layout(&self) -> Result<(Vec<IgzBlock>, Vec<BlockLocation>), IgzSaverError> {
+        let mut sections: Vec<IgzBlock> = vec![];
+        let mut locations = Vec::with_capacity(self.blocks.len());
+
+        for block in &self.blocks {
+            let index = match sections.iter().position(|section| section.pool == block.pool) {
+                Some(index) => index,
+                None => {
+                    sections.push(IgzBlock {
+                        pool: block.pool,
+                        alignment: 1,
+                        data: vec![],
+                    });
+                    sections.len() - 1
+                }
+            };
+
+            let section = &mut sections[index];
+            let offset = section.data.len().next_multiple_of(block.alignment as usize);
+            section.data.resize(offset, 0);
+            section.data.extend_from_slice(&block.data);
+            section.alignment = section.alignment.max(block.alignment);
+            locations.push((index, offset as u64));
+        }
+
+        if sections.len() >= MAX_CHUNKS {
+            return Err(IgzSaverError::TooManySections);
+        }
+        let max_size = if self.version <= 6 { 0x00FF_FFFF } else { 0x07FF_FFFF };
+        if let Some(section) = sections.iter().find(|section| section.data.len() > max_size) {
+            return Err(IgzSaverError::SectionTooLarge(section.pool));
+        }
+
+        Ok((sections, locations))
+    }
+}
+
+pub struct igIGZSaver;
+
+impl igIGZSaver {
+    /// Writes the directory's objects, names and dependencies as an igz. Objects found in the name lists of other directories loaded by the [igObjectStreamManager] are written as external references
+    pub fn save(
+        imm: &mut igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        dir: &igObjectDirectory,
+        version: u32,
+        meta_object_version: u32,
+        platform: IG_CORE_PLATFORM,
+        endian: Endian,
+    ) -> Result<Vec<u8>, IgzSaverError> {
+        if version != 0x09 {
+            return Err(IgzSaverError::UnsupportedVersion(version));
+        }
+        let platform_index = imm
+            .get_enum_index::<IG_CORE_PLATFORM>(&format!("{:?}", platform))
+            .ok_or(IgzSaverError::UnknownPlatform(platform.clone()))?;
+
+        let mut ctx = IgzSaverContext::new(version, platform);
+        ctx.external_names = collect_external_names(object_stream_manager, dir);
+
+        let object_list: igObject = dir.object_list.clone();
+        let object_list_block = ctx.add_object(&object_list);
+        let name_list_block = if dir.use_name_list {
+            let name_list: igObject = dir.name_list.clone();
+            Some(ctx.add_object(&name_list))
+        } else {
+            None
+        };
+
+        while let Some((object, block)) = ctx.pending_objects.pop_front() {
+            igIGZSaver::write_object(imm, object_stream_manager, &mut ctx, endian.clone(), &object, block)?;
+        }
+
+        let (mut sections, locations) = ctx.layout()?;
+        let serialized = |block: usize| ctx.serialize_offset(locations[block].0, locations[block].1);
+
+        let mut runtime_fields: HashMap<IgzRuntimeFixup, Vec<u64>> = HashMap::new();
+        for relocation in &ctx.relocations {
+            let (section, offset) = locations[relocation.block];
+            let position = offset + relocation.position;
+            if let Some(target) = relocation.target {
+                let mut handle = Cursor::new(std::mem::take(&mut sections[section].data));
+                handle.set_position(position);
+                write_ptr(&mut handle, serialized(target), ctx.platform.clone(), endian.clone())?;
+                sections[section].data = handle.into_inner();
+            }
+            runtime_fields.entry(relocation.kind).or_default().push(ctx.serialize_offset(section, position));
+        }
+
+        let mut vtables: Vec<u64> = ctx.object_blocks.values().map(|block| serialized(*block)).collect();
+        vtables.sort();
+
+        let mut fixups = Cursor::new(Vec::new());
+        let mut fixup_count = 0;
+
+        let mut fixup_data = Cursor::new(Vec::new());
+        for name in &ctx.vtbl_list {
+            write_aligned_string(&mut fixup_data, version, name)?;
+        }
+        write_fixup(&mut fixups, endian.clone(), b"TMET", ctx.vtbl_list.len(), &fixup_data.into_inner())?;
+        fixup_count += 1;
+
+        if !ctx.string_list.is_empty() {
+            let mut fixup_data = Cursor::new(Vec::new());
+            for string in &ctx.string_list {
+                write_aligned_string(&mut fixup_data, version, string)?;
+            }
+            write_fixup(&mut fixups, endian.clone(), b"TSTR", ctx.string_list.len(), &fixup_data.into_inner())?;
+            fixup_count += 1;
+        }
+
+        let dependencies: Vec<(String, String)> = dir.dependencies.iter().filter_map(|dependency| {
+            let dependency = dependency.try_read().ok()?;
+            Some((dependency.name.string.clone().unwrap_or_else(|| dependency.path.clone()), dependency.path.clone()))
+        }).collect();
+        if !dependencies.is_empty() {
+            let mut fixup_data = Cursor::new(Vec::new());
+            for (name, path) in &dependencies {
+                write_string(&mut fixup_data, name)?;
+                write_string(&mut fixup_data, path)?;
+            }
+            write_fixup(&mut fixups, endian.clone(), b"TDEP", dependencies.len(), &fixup_data.into_inner())?;
+            fixup_count += 1;
+        }
+
+        if !ctx.external_list.is_empty() {
+            let mut fixup_data = Cursor::new(Vec::new());
+            for (name, namespace) in &ctx.external_list {
+                write_u32(&mut fixup_data, *name, endian.clone())?;
+                write_u32(&mut fixup_data, *namespace, endian.clone())?;
+            }
+            write_fixup(&mut fixups, endian.clone(), b"EXID", ctx.external_list.len(), &fixup_data.into_inner())?;
+            fixup_count += 1;
+        }
+
+        if !ctx.named_external_list.is_empty() {
+            let mut fixup_data = Cursor::new(Vec::new());
+            for raw in &ctx.named_external_list {
+                write_u64(&mut fixup_data, *raw, endian.clone())?;
+            }
+            write_fixup(&mut fixups, endian.clone(), b"EXNM", ctx.named_external_list.len(), &fixup_data.into_inner())?;
+            fixup_count += 1;
+        }
+
+        write_fixup(&mut fixups, endian.clone(), b"RVTB", vtables.len(), &pack_compressed_ints(version, &vtables)?)?;
+        write_fixup(&mut fixups, endian.clone(), b"ROOT", 1, &pack_compressed_ints(version, &[serialized(object_list_block)])?)?;
+        fixup_count += 2;
+
+        for kind in IgzRuntimeFixup::ALL {
+            if let Some(offsets) = runtime_fields.get_mut(&kind) {
+                offsets.sort();
+                write_fixup(&mut fixups, endian.clone(), kind.magic(), offsets.len(), &pack_compressed_ints(version, offsets)?)?;
+                fixup_count += 1;
+            }
+        }
+
+        if let Some(name_list_block) = name_list_block {
+            let mut fixup_data = Cursor::new(Vec::new());
+            write_u32(&mut fixup_data, serialized(name_list_block) as u32, endian.clone())?;
+            write_fixup(&mut fixups, endian.clone(), b"ONAM", 1, &fixup_data.into_inner())?;
+            fixup_count += 1;
+        }
+
+        // The fixups take the first chunk descriptor, every section after it is described by its memory pool's name
+        let mut chunks = vec![IgzBlock {
+            pool: igMemoryPool::Default,
+            alignment: FIXUP_HEADER_SIZE,
+            data: fixups.into_inner(),
+        }];
+        chunks.append(&mut sections);
+
+        let unsupported = |_| IgzSaverError::UnsupportedVersion(version);
+        let descriptor_start = get_chunk_descriptor_start(version).map_err(unsupported)?;
+        let attribute_location = get_attribute_location(version).map_err(unsupported)?;
+        let mut file = Cursor::new(Vec::new());
+        // Written in the file's endian, which is how the loader tells the endian of the file apart
+        write_u32(&mut file, IGZ_LITTLE_ENDIAN_MAGIC, endian.clone())?;
+        write_u32(&mut file, version, endian.clone())?;
+        write_u32(&mut file, meta_object_version, endian.clone())?;
+        write_u32(&mut file, platform_index as u32, endian.clone())?;
+        write_u32(&mut file, fixup_count, endian.clone())?;
+
+        let mut names = Cursor::new(Vec::new());
+        let mut name_lookup: HashMap<String, u32> = HashMap::new();
+        let mut chunk_offsets = Vec::with_capacity(chunks.len());
+        let mut offset = SECTION_ALIGNMENT;
+        for (i, chunk) in chunks.iter().enumerate() {
+            let name = format!("{:?}", chunk.pool);
+            let name_ptr = match name_lookup.get(&name) {
+                Some(name_ptr) => *name_ptr,
+                None => {
+                    let name_ptr = names.position() as u32;
+                    write_string(&mut names, &name)?;
+                    name_lookup.insert(name, name_ptr);
+                    name_ptr
+                }
+            };
+
+            file.set_position(descriptor_start + 0x10 * i as u64);
+            write_u32(&mut file, name_ptr, endian.clone())?;
+            write_u32(&mut file, offset as u32, endian.clone())?;
+            write_u32(&mut file, chunk.data.len() as u32, endian.clone())?;
+            write_u32(&mut file, chunk.alignment, endian.clone())?;
+
+            chunk_offsets.push(offset);
+            offset = (offset + chunk.data.len() as u64).next_multiple_of(SECTION_ALIGNMENT);
+        }
+
+        file.set_position(attribute_location as u64);
+        file.write_all(&names.into_inner())?;
+
+        for (chunk, offset) in chunks.iter().zip(chunk_offsets) {
+            file.set_position(offset);
+            file.write_all(&chunk.data)?;
+        }
+
+        Ok(file.into_inner())
+    }
+
+    /// Writes every field of an object into its block. Objects it references are queued on the context
+    fn write_object(
+        imm: &mut igMetadataManager,
+        object_stream_manager: &igObjectStreamManager,
+        ctx: &mut IgzSaverContext,
+        endian: Endian,
+        object: &igObject,
+        block: usize,
+    ) -> Result<(), IgzSaverError> {
+        let object_name = object.read().unwrap().object_name();
+        if !imm.has_meta(&object_name) {
+            return Err(IgzSaverError::UnknownType(object_name));
+        }
+        let meta = object.read().unwrap().meta_type(imm);
+        let meta = meta.read().unwrap();
+
+        let mut handle = Cursor::new(vec![0u8; meta.size(ctx.platform.clone()) as usize]);
+        ctx.blocks[block].alignment = meta.alignment(ctx.platform.clone());
+        let previous_block = ctx.set_current_block(block);
+
+        let bitfields = imm.pack_bitfields(&meta, object)?;
+        let mut fields: Vec<_> = meta.field_storage.name_lookup.iter().collect();
+        fields.sort_by_key(|(_, field)| field.offset);
+        for (name, field) in fields {
+            match field._type.as_ref() {
+                "igStaticMetaField" | "igPropertyFieldMetaField" => {
+                    // ignored, not stored on a per-object basis.
+                }
+                "igBitFieldMetaField" => {
+                    // packed into its storage field instead
+                }
+                &_ => {
+                    let value = match bitfields.get(name) {
+                        Some(value) => Ok(Some(value.clone())),
+                        None => object.read().unwrap().get_field(name),
+                    };
+                    let value = value.map_err(|_| IgzSaverError::MissingField {
+                        object: meta.name.clone(),
+                        field: name.clone(),
+                    })?;
+                    let metafield = imm.meta_field_registry.get(field.clone(), imm, ctx.platform.clone());
+                    handle.set_position(field.offset as u64);
+                    metafield.value_into_igz(&imm.meta_field_registry, imm, object_stream_manager, &mut handle, endian.clone(), ctx, value)?;
+                }
+            }
+        }
+
+        // The vtable index is written last so a field overlapping the start of the object can't overwrite it
+        let vtbl_index = ctx.vtbl_index(&meta.name);
+        handle.set_position(0);
+        write_ptr(&mut handle, vtbl_index as u64, ctx.platform.clone(), endian)?;
+
+        ctx.set_current_block(previous_block);
+        ctx.set_block_data(block, handle.into_inner());
+        Ok(())
+    }
+}
+
+/// Writes a fixup's header followed by its data, padded to a multiple of 4
+fn write_fixup(fixups: &mut Cursor<Vec<u8>>, endian: Endian, magic: &[u8; 4], count: usize, data: &[u8]) -> std::io::Result<()> {
+    let length = (FIXUP_HEADER_SIZE as usize + data.len()).next_multiple_of(4);
+    write_u32(fixups, u32::from_le_bytes(*magic), endian.clone())?;
+    write_u32(fixups, count as u32, endian.clone())?;
+    write_u32(fixups, length as u32, endian.clone())?;
+    write_u32(fixups, FIXUP_HEADER_SIZE, endian)?;
+    fixups.write_all(data)?;
+    fixups.write_all(&vec![0; length - FIXUP_HEADER_SIZE as usize - data.len()])
+}
+
+/// Writes a TMET/TSTR string. Versions above 0x07 pad each string to an even length
+fn write_aligned_string(handle: &mut Cursor<Vec<u8>>, version: u32, string: &str) -> std::io::Result<()> {
+    write_string(handle, string)?;
+    if version > 7 && string.len().is_multiple_of(2) {
+        handle.write_all(&[0])?;
+    }
+    Ok(())
+}
+
+/// Encodes serialized offsets into the packed nibbles runtime fixups are stored as. The inverse of [unpack_serialized_ints](crate::core::load::ig_igz_loader::unpack_serialized_ints).
+/// Each value is stored as its distance from the previous one divided by 4, split into 3 bit chunks with the top bit of every nibble marking that another chunk follows.
+/// Before version 0x09 every distance is stored with 4 taken off it, so offsets must be strictly increasing and the first can't be 0.
+/// Offsets have to be serialized (section index and offset into the section) rather than positions in the file, see [IgzSaverContext::serialize_offset]
+pub(crate) fn pack_compressed_ints(version: u32, offsets: &[u64]) -> Result<Vec<u8>, IgzSaverError> {
+    let bias = if version < 9 { 4 } else { 0 };
+    let mut nibbles = Vec::with_capacity(offsets.len() * 2);
+    let mut previous = 0;
+
+    for offset in offsets {
+        if *offset < previous + bias || offset % 4 != 0 {
+            return Err(IgzSaverError::UnpackableOffset { offset: *offset, previous });
+        }
+        let mut delta = (offset - previous - bias) / 4;
+        previous = *offset;
+
+        loop {
+            let nibble = (delta & 0x7) as u8;
+            delta >>= 3;
+            if delta == 0 {
+                nibbles.push(nibble);
+                break;
+            }
+            nibbles.push(nibble | 0x8);
+        }
+    }
+
+    Ok(nibbles.chunks(2).map(|pair| pair[0] | (*pair.get(1).unwrap_or(&0) << 4)).collect())
 }
-pub enum IgzSaverError {
-    Unknown,
-}
\ No newline at end of file
@@ -1,16 +1,41 @@
 #![allow(non_snake_case)]
 
-use crate::core::ig_ark_core::{igArkCore, EGame};
+use crate::core::ig_archive::{igArchive, igArchiveError, CompressionType};
+use crate::core::ig_ark_core::{igArkCore, register_metafields, EGame};
 use crate::core::ig_core_platform::IG_CORE_PLATFORM;
-use crate::core::ig_file_context::igFileContext;
+use crate::core::ig_custom::{igHashTable, igNameList, igObjectDirectoryList, igObjectList, CastTo};
+use crate::core::ig_external_ref::igExternalReferenceSystem;
+use crate::core::ig_file_context::{igFileContext, OPEN_FLAG_HEADER_ONLY};
+use crate::core::ig_fs::Endian;
+use crate::core::ig_math::{igMatrix44f, igQuaternionf, igRandom, igVec2f, igVec3f, igVec4f};
 use crate::core::ig_memory::igMemoryPool;
-use crate::core::ig_objects::{igAny, ObjectExt};
-use crate::core::ig_registry::igRegistry;
+use crate::core::ig_objects::{igAny, igObject, igObjectDirectory, igObjectStreamManager, ObjectExt};
+use crate::core::ig_registry::{igRegistry, BuildTool};
+use crate::core::load::ig_igb_loader::{IgbHeader, IgbLoaderError, IgbLoaderErrorKind, IGB_NULL_INDEX};
+use crate::core::load::ig_igz_loader::{
+    fixup_data_size, get_chunk_descriptor_start, igIGZLoader, igIGZObjectLoader, unpack_serialized_ints, IgzLoaderError, IgzLoaderErrorKind,
+    IgzLoaderLocation,
+};
+use crate::core::load::ig_loader::{igObjectLoader, igObjectLoaderRegistry, LoadReport, LoaderError, LoaderMatch};
+use crate::core::load::ig_igz_dump::IgzDump;
+use crate::core::memory::{igMemory, igNullElement};
 use crate::core::meta::ig_metadata_manager::{
-    __internalObjectBase, igMetaObject, FieldDoesntExist, SetObjectFieldError,
+    __internalObjectBase, igEnumValue, igMetaObject, igMetadataManager, FieldDoesntExist, SetObjectFieldError,
+};
+use crate::core::meta::ig_xml_metadata::{
+    ArcMetaEnum, ArcMetaField, ArkMetaObjectField, BitShiftInfo, HashTableInfo, MetaEnumValue, MetaObject, PlatformSizingInfo,
+    RawArkMetaObjectField, VectorInfo,
 };
+use crate::core::save::ig_igx_saver::{igIGXSaver, IgxSaverError};
+use crate::core::save::ig_igz_saver::{igIGZSaver, object_key, pack_compressed_ints, IgzSaverContext, IgzSaverError};
 use crate::util::ig_common::igAlchemy;
+use crate::util::ig_hash::{hash, hash_lower};
+use crate::util::ig_hash_dictionary::{add_string, clear_dictionary, find_string, load_dictionary, save_dictionary};
+use crate::util::ig_name::igName;
+use proptest::prelude::*;
 use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::io::{Cursor, Read};
 use std::ops::Sub;
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
@@ -54,7 +79,11 @@ struct igModelData {
 }
 
 impl __internalObjectBase for igModelData {
-    fn meta_type(&self) -> Arc<RwLock<igMetaObject>> {
+    fn object_name(&self) -> Arc<str> {
+        todo!()
+    }
+
+    fn meta_type(&self, metadata_manager: &mut igMetadataManager) -> Arc<RwLock<igMetaObject>> {
         todo!()
     }
 
@@ -99,7 +128,11 @@ struct igModelInfo {
 }
 
 impl __internalObjectBase for igModelInfo {
-    fn meta_type(&self) -> Arc<RwLock<igMetaObject>> {
+    fn object_name(&self) -> Arc<str> {
+        todo!()
+    }
+
+    fn meta_type(&self, metadata_manager: &mut igMetadataManager) -> Arc<RwLock<igMetaObject>> {
         todo!()
     }
 
@@ -171,3 +204,1932 @@ fn test_type_usability() {
         }
     };
 }
+
+/// Verifies archives made with [igArchive::build] can be opened again and give back the exact same files.
+#[test]
+fn test_archive_round_trip() {
+    // Simple LCG so the test data doesn't compress or repeat too nicely
+    let mut seed = 0x12345678u32;
+    let mut random_bytes = |len: usize| -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 24) as u8
+            })
+            .collect()
+    };
+    let entries = vec![
+        ("empty.bin".to_string(), vec![]),
+        ("Scripts/Small.lua".to_string(), random_bytes(0x123)),
+        ("actors/one_block.igz".to_string(), random_bytes(0x8000)),
+        ("Models/Medium.igz".to_string(), random_bytes(0x7F * 0x800 + 0x4567)),
+        (
+            "Scripts/Repeating.lua".to_string(),
+            b"igArchive".repeat(0x2000).to_vec(),
+        ),
+    ];
+
+    let temp_dir = std::env::temp_dir();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    for (version, compression) in [
+        (0x04, CompressionType::kUncompressed),
+        (0x04, CompressionType::kLzma),
+        (0x04, CompressionType::kLz4),
+        (0x08, CompressionType::kZlib),
+        (0x0C, CompressionType::kLzma),
+        (0x0B, CompressionType::kUncompressed),
+        (0x0B, CompressionType::kZlib),
+        (0x0B, CompressionType::kLzma),
+        (0x0B, CompressionType::kLz4),
+        (0x0C, CompressionType::kZlib),
+        (0x0D, CompressionType::kLz4),
+    ] {
+        for (endian, flags) in [(Endian::Little, 1), (Endian::Big, 0)] {
+            let archive =
+                igArchive::build(entries.clone(), version, endian, flags, compression.clone())
+                    .unwrap();
+            let archive_name = format!(
+                "ig-library-round-trip-{:X}-{:?}-{}.arc",
+                version, compression, flags
+            );
+            let saved = archive.save(&ig_registry).unwrap();
+            std::fs::write(temp_dir.join(&archive_name), &saved).unwrap();
+
+            let reopened = igArchive::open(&ig_file_context, &ig_registry, &archive_name).unwrap();
+            assert!(reopened.save_preserving_layout(&ig_registry).unwrap() == saved);
+            assert_eq!(reopened._files.read().unwrap().len(), entries.len());
+            for (ordinal, (name, data)) in entries.iter().enumerate() {
+                assert_eq!(reopened.read_file(name).unwrap().as_ref(), Some(data), "{}", name);
+                if version >= 0x0B {
+                    let hash = reopened.hash_file_path(name);
+                    let files = reopened._files.read().unwrap();
+                    let file = files.iter().find(|x| x._hash == hash).unwrap();
+                    assert_eq!(file._ordinal, ordinal as u32);
+                }
+            }
+            if flags & 1 != 0 {
+                assert!(reopened.read_file("MODELS\\medium.igz").unwrap().is_some());
+            }
+            assert!(reopened.read_file("missing.igz").unwrap().is_none());
+
+            // Random data should never compress, but the repeating data always should
+            let is_compressed = |name: &str| {
+                let hash = reopened.hash_file_path(name);
+                let files = reopened._files.read().unwrap();
+                let file = files.iter().find(|x| x._hash == hash).unwrap();
+                file._blocks.as_ref().unwrap().iter().any(|x| x & 0x80000000 != 0)
+            };
+            assert!(!is_compressed("Models/Medium.igz"));
+            assert_eq!(
+                is_compressed("Scripts/Repeating.lua"),
+                !matches!(compression, CompressionType::kUncompressed)
+            );
+
+            // The archive stays memory mapped until it's dropped
+            drop(reopened);
+            std::fs::remove_file(temp_dir.join(&archive_name)).unwrap();
+        }
+    }
+}
+
+/// Verifies saving with the original layout doesn't overwrite a block table entry that a file shares with the file after it.
+#[test]
+fn test_archive_shared_block_entries() {
+    let data = b"igArchive".repeat(0x100);
+    let entries = vec![
+        ("zero.bin".to_string(), vec![]),
+        ("data.bin".to_string(), data.clone()),
+    ];
+    let archive =
+        igArchive::build(entries, 0x0B, Endian::Little, 0, CompressionType::kZlib).unwrap();
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    let temp_dir = std::env::temp_dir();
+    let archive_name = "ig-library-shared-block-entries.arc";
+    std::fs::write(temp_dir.join(archive_name), archive.save(&ig_registry).unwrap()).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let reopened = igArchive::open(&ig_file_context, &ig_registry, archive_name).unwrap();
+
+    // The empty file ends on the compressed first block of the other file
+    {
+        let mut files = reopened._files.write().unwrap();
+        let data_index = files.iter().position(|x| x._length != 0).unwrap();
+        let empty_index = files.iter().position(|x| x._length == 0).unwrap();
+        assert!(empty_index > data_index);
+        let first_block = files[data_index]._block_index & 0x0FFFFFFF;
+        files[empty_index]._block_index = (files[empty_index]._block_index & 0xF0000000) | first_block;
+    }
+    let saved = reopened.save_preserving_layout(&ig_registry).unwrap();
+    drop(reopened);
+    std::fs::write(temp_dir.join(archive_name), saved).unwrap();
+
+    let reopened = igArchive::open(&ig_file_context, &ig_registry, archive_name).unwrap();
+    assert_eq!(reopened.read_file("data.bin").unwrap(), Some(data));
+    assert_eq!(reopened.read_file("zero.bin").unwrap(), Some(vec![]));
+    drop(reopened);
+    std::fs::remove_file(temp_dir.join(archive_name)).unwrap();
+}
+
+/// Verifies files can be added, replaced, renamed and deleted in an archive and that it can still be saved afterward.
+#[test]
+fn test_archive_patching() {
+    let entries = vec![
+        ("keep.bin".to_string(), b"keep".repeat(0x100)),
+        ("replace.bin".to_string(), b"before".to_vec()),
+        ("rename.bin".to_string(), b"rename".to_vec()),
+        ("delete.bin".to_string(), b"delete".to_vec()),
+    ];
+    let archive =
+        igArchive::build(entries, 0x0B, Endian::Little, 1, CompressionType::kZlib).unwrap();
+
+    let replacement = b"after".repeat(0x3000);
+    archive.replace_file("replace.bin", &replacement).unwrap();
+    archive
+        .add_file("added/new.bin", b"new", CompressionType::kLz4)
+        .unwrap();
+    archive.rename_file("rename.bin", "renamed.bin").unwrap();
+    archive.delete_file("delete.bin").unwrap();
+    assert!(matches!(
+        archive.add_file("KEEP.bin", b"", CompressionType::kUncompressed),
+        Err(igArchiveError::FileExists(_))
+    ));
+    assert!(matches!(
+        archive.rename_file("renamed.bin", "keep.bin"),
+        Err(igArchiveError::FileExists(_))
+    ));
+    assert!(matches!(
+        archive.delete_file("delete.bin"),
+        Err(igArchiveError::FileNotFound(_))
+    ));
+    assert!(matches!(
+        CompressionType::kZlib.to_index(0x04),
+        Err(igArchiveError::UnsupportedCompressionForVersion { version: 0x04, .. })
+    ));
+
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    let temp_dir = std::env::temp_dir();
+    let archive_name = "ig-library-patching.arc";
+    std::fs::write(temp_dir.join(archive_name), archive.save(&ig_registry).unwrap()).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let reopened = igArchive::open(&ig_file_context, &ig_registry, archive_name).unwrap();
+
+    assert_eq!(reopened._files.read().unwrap().len(), 4);
+    assert_eq!(reopened.read_file("keep.bin").unwrap(), Some(b"keep".repeat(0x100)));
+    assert_eq!(reopened.read_file("replace.bin").unwrap(), Some(replacement));
+    assert_eq!(reopened.read_file("added/new.bin").unwrap(), Some(b"new".to_vec()));
+    assert_eq!(reopened.read_file("renamed.bin").unwrap(), Some(b"rename".to_vec()));
+    assert_eq!(reopened.read_file("rename.bin").unwrap(), None);
+    assert_eq!(reopened.read_file("delete.bin").unwrap(), None);
+
+    // Saving over the mapped archive copies it out of the mapping first, so untouched files can still be read afterward
+    reopened.replace_file("added/new.bin", b"newer").unwrap();
+    reopened.save_to(&ig_registry, &temp_dir.join(archive_name), false).unwrap();
+    assert_eq!(reopened.read_file("keep.bin").unwrap(), Some(b"keep".repeat(0x100)));
+    let resaved = igArchive::open(&ig_file_context, &ig_registry, archive_name).unwrap();
+    assert_eq!(resaved.read_file("keep.bin").unwrap(), Some(b"keep".repeat(0x100)));
+    assert_eq!(resaved.read_file("added/new.bin").unwrap(), Some(b"newer".to_vec()));
+    drop(reopened);
+    drop(resaved);
+    std::fs::remove_file(temp_dir.join(archive_name)).unwrap();
+}
+
+/// Verifies files are only read out of an opened archive when needed, and that cached files don't outlive edits.
+#[test]
+fn test_archive_lazy_reading() {
+    let entries = vec![
+        ("first.bin".to_string(), b"first".repeat(0x2000)),
+        ("second.bin".to_string(), b"second".repeat(0x10)),
+    ];
+    let archive =
+        igArchive::build(entries, 0x0D, Endian::Little, 1, CompressionType::kLz4).unwrap();
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    let temp_dir = std::env::temp_dir();
+    let archive_name = "ig-library-lazy-reading.arc";
+    let saved = archive.save(&ig_registry).unwrap();
+    std::fs::write(temp_dir.join(archive_name), &saved).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let reopened = igArchive::open(&ig_file_context, &ig_registry, archive_name).unwrap();
+
+    assert!(reopened
+        ._files
+        .read()
+        .unwrap()
+        .iter()
+        .all(|x| x._compressed_data.is_none() && x._compressed_size != 0));
+
+    reopened.set_cache_capacity(0x10000);
+    for _ in 0..2 {
+        assert_eq!(reopened.read_file("first.bin").unwrap(), Some(b"first".repeat(0x2000)));
+        assert_eq!(reopened.read_file("second.bin").unwrap(), Some(b"second".repeat(0x10)));
+    }
+    reopened.replace_file("first.bin", b"edited").unwrap();
+    assert_eq!(reopened.read_file("first.bin").unwrap(), Some(b"edited".to_vec()));
+    let edited = |name: &str| {
+        let hash = reopened.hash_file_path(name);
+        reopened._files.read().unwrap().iter().find(|x| x._hash == hash).unwrap()._compressed_data.is_some()
+    };
+    assert!(edited("first.bin") && !edited("second.bin"));
+    reopened.rename_file("second.bin", "third.bin").unwrap();
+    assert_eq!(reopened.read_file("second.bin").unwrap(), None);
+    assert_eq!(reopened.read_file("third.bin").unwrap(), Some(b"second".repeat(0x10)));
+
+    // Untouched files are copied straight out of the mapped archive when saving
+    reopened.set_cache_capacity(0);
+    reopened.rename_file("third.bin", "second.bin").unwrap();
+    reopened.replace_file("first.bin", &b"first".repeat(0x2000)).unwrap();
+    assert!(reopened.save(&ig_registry).unwrap() == saved);
+
+    drop(reopened);
+    std::fs::remove_file(temp_dir.join(archive_name)).unwrap();
+}
+
+/// Verifies extracting everything at once gives back the same files as reading them one at a time.
+#[test]
+fn test_archive_extract_all() {
+    let entries = vec![
+        ("Textures/Many_Blocks.igz".to_string(), (0..0x64321).map(|x| (x % 251) as u8).collect()),
+        ("cwd:/../Scripts/Script.lua".to_string(), b"print()".to_vec()),
+        ("empty.bin".to_string(), vec![]),
+    ];
+    let archive = igArchive::build(
+        entries.clone(),
+        0x0B,
+        Endian::Little,
+        1,
+        CompressionType::kLzma,
+    )
+    .unwrap();
+
+    let mut extracted = archive.extract_all().unwrap();
+    extracted.sort();
+    let mut expected = entries.clone();
+    expected.sort();
+    assert!(extracted == expected);
+
+    let out_dir = std::env::temp_dir().join("ig-library-extract-all");
+    archive.extract_all_to(&out_dir).unwrap();
+    assert_eq!(
+        std::fs::read(out_dir.join("Textures/Many_Blocks.igz")).unwrap(),
+        entries[0].1
+    );
+    assert_eq!(
+        std::fs::read(out_dir.join("cwd/Scripts/Script.lua")).unwrap(),
+        entries[1].1
+    );
+    assert_eq!(std::fs::read(out_dir.join("empty.bin")).unwrap(), entries[2].1);
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+/// Verifies damaged archives are reported as errors instead of panicking.
+#[test]
+fn test_archive_errors() {
+    let archive = igArchive::build(
+        vec![("file.bin".to_string(), b"igArchive".repeat(0x1000))],
+        0x0B,
+        Endian::Little,
+        1,
+        CompressionType::kZlib,
+    )
+    .unwrap();
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    let saved = archive.save(&ig_registry).unwrap();
+
+    let temp_dir = std::env::temp_dir();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let archive_name = "ig-library-errors.arc";
+    let open_damaged = |damage: &dyn Fn(&mut Vec<u8>)| {
+        let mut damaged = saved.clone();
+        damage(&mut damaged);
+        std::fs::write(temp_dir.join(archive_name), damaged).unwrap();
+        let result = igArchive::open(&ig_file_context, &ig_registry, archive_name);
+        std::fs::remove_file(temp_dir.join(archive_name)).ok();
+        result
+    };
+
+    assert!(matches!(
+        open_damaged(&|x| x[0] = 0),
+        Err(igArchiveError::BadMagic(_))
+    ));
+    assert!(matches!(
+        open_damaged(&|x| x[4] = 0x09),
+        Err(igArchiveError::UnsupportedVersion(0x09))
+    ));
+    // The file info of Swap Force and Lost Islands archives hasn't been checked against real files yet
+    assert!(matches!(
+        open_damaged(&|x| x[4] = 0x0A),
+        Err(igArchiveError::UnsupportedVersion(0x0A))
+    ));
+    assert!(matches!(
+        open_damaged(&|x| x.truncate(0x40)),
+        Err(igArchiveError::TruncatedToc(_))
+    ));
+    assert!(matches!(
+        open_damaged(&|x| x[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes())),
+        Err(igArchiveError::NameTableOutOfRange(_))
+    ));
+    assert!(matches!(
+        open_damaged(&|x| x[0x48..0x4B].copy_from_slice(&[0xFF, 0xFF, 0xFF])),
+        Err(igArchiveError::BadBlockIndex { .. })
+    ));
+    assert!(matches!(
+        open_damaged(&|x| x[0x3C..0x40].copy_from_slice(&u32::MAX.to_le_bytes())),
+        Err(igArchiveError::FileDataOutOfRange(_))
+    ));
+
+    // The first block is compressed, break the deflate stream right after its size
+    let file_offset = u32::from_le_bytes(saved[0x3C..0x40].try_into().unwrap()) as usize;
+    let damaged = open_damaged(&|x| x[file_offset + 2] = 0xFF).unwrap();
+    assert!(matches!(
+        damaged.read_file("file.bin"),
+        Err(igArchiveError::Decompression { block: 0, .. })
+    ));
+
+    // Only 3 bytes are left for the ordinal next to the 5 byte offset
+    archive._files.write().unwrap()[0]._ordinal = 0x100_0000;
+    assert!(archive.save(&ig_registry).is_err());
+
+    // How files are looked up depends on the build tool, which is unset until the init script is read
+    std::fs::write(temp_dir.join(archive_name), &saved).unwrap();
+    ig_file_context.load_archive(&ig_registry, archive_name).unwrap();
+    assert!(ig_file_context.open(&ig_registry, "file.bin", 0)._handle.is_none());
+    let mut tfb_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    tfb_registry.build_tool = BuildTool::TfbTool;
+    assert!(ig_file_context.open(&tfb_registry, "file.bin", 0)._handle.is_none());
+    let mut alchemy_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    alchemy_registry.build_tool = BuildTool::AlchemyLaboratory;
+    assert!(ig_file_context.open(&alchemy_registry, "file.bin", 0)._handle.is_some());
+    drop(ig_file_context);
+    std::fs::remove_file(temp_dir.join(archive_name)).unwrap();
+}
+
+/// Verifies [igArchive::verify] passes a good archive and reports every problem in a damaged one.
+#[test]
+fn test_archive_verify() {
+    let archive = igArchive::build(
+        vec![
+            ("first.bin".to_string(), b"first".repeat(0x2000)),
+            ("second.bin".to_string(), b"second".repeat(0x2000)),
+        ],
+        0x0B,
+        Endian::Little,
+        1,
+        CompressionType::kZlib,
+    )
+    .unwrap();
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    let saved = archive.save(&ig_registry).unwrap();
+
+    let temp_dir = std::env::temp_dir();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let archive_name = "ig-library-verify.arc";
+    let verify = |data: &[u8]| {
+        std::fs::write(temp_dir.join(archive_name), data).unwrap();
+        let report = igArchive::verify(&ig_file_context, &ig_registry, archive_name);
+        std::fs::remove_file(temp_dir.join(archive_name)).ok();
+        report
+    };
+
+    let report = verify(&saved);
+    assert!(report.is_ok(), "{}", report);
+
+    // Break the hash of the first file in the toc and the deflate stream of the second one
+    let names: Vec<String> = archive
+        ._files
+        .read()
+        .unwrap()
+        .iter()
+        .map(|x| x._logical_name.clone())
+        .collect();
+    let mut damaged = saved.clone();
+    damaged[0x38] ^= 0xFF;
+    let second_offset = u32::from_le_bytes(saved[0x50..0x54].try_into().unwrap()) as usize;
+    damaged[second_offset + 2] = 0xFF;
+    let report = verify(&damaged);
+    assert!(report.archive_problems.is_empty(), "{}", report);
+    assert_eq!(report.file_problems.len(), 2, "{}", report);
+    let problems = |name: &str| &report.file_problems.iter().find(|x| x.0 == name).unwrap().1;
+    assert!(problems(&names[0])
+        .iter()
+        .any(|x| matches!(x, igArchiveError::HashMismatch { .. })));
+    assert!(problems(&names[1])
+        .iter()
+        .any(|x| matches!(x, igArchiveError::Decompression { block: 0, .. })));
+}
+
+#[test]
+fn test_hash_dictionary() {
+    let string = "IgLibraryDictionaryTest";
+    assert!(igName::from_hash(hash(string)).string.is_none());
+
+    add_string(string);
+    assert_eq!(igName::from_hash(hash(string)).string.as_deref(), Some(string));
+    assert_eq!(igName::from_hash(hash_lower(string)).string.as_deref(), Some(string));
+    // Looked up strings are only for showing the name, they may not be what the hash was made from
+    assert!(igName::from_hash(hash_lower(string)).recovered);
+    assert_eq!(igName::from_hash(hash_lower(string)).source_string(), None);
+    assert_eq!(igName::new(string.to_string()).source_string(), Some(string));
+
+    let temp_dir = std::env::temp_dir();
+    let loaded_path = temp_dir.join("ig-library-dictionary-load.txt");
+    std::fs::write(&loaded_path, "IgLibraryLoadedString\r\n\nIgLibrarySecondString\n").unwrap();
+    assert_eq!(load_dictionary(&loaded_path).unwrap(), 2);
+    std::fs::remove_file(&loaded_path).ok();
+    assert_eq!(find_string(hash("IgLibraryLoadedString")).as_deref(), Some("IgLibraryLoadedString"));
+    assert_eq!(find_string(hash_lower("IgLibrarySecondString")).as_deref(), Some("IgLibrarySecondString"));
+
+    let saved_path = temp_dir.join("ig-library-dictionary-save.txt");
+    save_dictionary(&saved_path).unwrap();
+    let saved = std::fs::read_to_string(&saved_path).unwrap();
+    std::fs::remove_file(&saved_path).ok();
+    assert!(saved.lines().any(|x| x == string));
+    assert!(saved.lines().any(|x| x == "IgLibraryLoadedString"));
+
+    clear_dictionary();
+    assert_eq!(find_string(hash(string)), None);
+    assert_eq!(find_string(hash("IgLibraryLoadedString")), None);
+    assert_eq!(find_string(hash_lower("tfbScript")).as_deref(), Some("tfbScript"));
+}
+
+fn test_field(_type: &str, offset: u16, name: Option<&str>, memory_type: Option<&str>) -> ArkMetaObjectField {
+    Arc::new(RwLock::new(RawArkMetaObjectField {
+        _type: Arc::from(_type),
+        offset,
+        name: name.map(Arc::from),
+        meta_object: None,
+        required_alignment: Some(4),
+        ig_vector_info: None,
+        ig_memory_ref_info: memory_type.map(|x| test_field(x, 0, None, None)),
+        ig_bit_shift_info: None,
+        ig_property_info: None,
+        ig_meta_enum: None,
+        ig_static_info: None,
+        num: None,
+    }))
+}
+
+fn test_meta_object(name: &str, base_type: Option<&str>, new_fields: Vec<ArkMetaObjectField>) -> MetaObject {
+    MetaObject {
+        _type: "igMetaObject".to_string(),
+        ref_name: Arc::from(name),
+        base_type: base_type.map(|x| x.to_string()),
+        object_list_type: None,
+        hash_table_info: None,
+        new_fields,
+        overriden_fields: vec![],
+        compound_fields: vec![],
+        tfb_script_binding: None,
+    }
+}
+
+/// Builds just enough metadata by hand to write and read igz files without the ArkCore of a real game.
+fn test_metadata_manager() -> igMetadataManager {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let fields = [
+        ("igIntMetaField", 4),
+        ("igStringMetaField", 4),
+        ("igObjectRefMetaField", 4),
+        ("igMemoryRefMetaField", 8),
+        ("igNameMetaField", 8),
+        ("igSizeTypeMetaField", 4),
+        ("igBoolMetaField", 1),
+        ("igCharMetaField", 1),
+        ("igUnsignedCharMetaField", 1),
+        ("igShortMetaField", 2),
+        ("igUnsignedShortMetaField", 2),
+        ("igUnsignedIntMetaField", 4),
+        ("igFloatMetaField", 4),
+        ("igTimeMetaField", 4),
+        ("igLongMetaField", 8),
+        ("igUnsignedLongMetaField", 8),
+        ("igRandomMetaField", 8),
+        ("igVec2fMetaField", 8),
+        ("igVec3fMetaField", 12),
+        ("igVec3fAlignedMetaField", 16),
+        ("igVec4fMetaField", 16),
+        ("igQuaternionfMetaField", 16),
+        ("igMatrix44fMetaField", 64),
+        ("igIntArrayMetaField", 4),
+        ("igObjectRefArrayMetaField", 4),
+        ("igVec3fArrayMetaField", 12),
+        ("igVectorMetaField", 12),
+        ("igEnumMetaField", 4),
+        ("igBitFieldMetaField", 0),
+    ].into_iter().map(|(name, size)| ArcMetaField {
+        name: Arc::from(name),
+        platform_info: HashMap::from([(platform.clone(), PlatformSizingInfo { align: 4, size })]),
+    }).collect();
+    let platforms = ArcMetaEnum {
+        ref_name: Arc::from("IG_CORE_PLATFORM"),
+        values: ["IG_CORE_PLATFORM_DEFAULT", "IG_CORE_PLATFORM_WIN32", "IG_CORE_PLATFORM_CAFE"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| MetaEnumValue { name: Arc::from(name), value: i as i32 })
+            .collect(),
+    };
+    let colors = ArcMetaEnum {
+        ref_name: Arc::from("TEST_COLOR"),
+        values: [("TEST_COLOR_RED", 0), ("TEST_COLOR_GREEN", 1), ("TEST_COLOR_BLUE", 5)]
+            .into_iter()
+            .map(|(name, value)| MetaEnumValue { name: Arc::from(name), value })
+            .collect(),
+    };
+    let list_fields = |child_type: &str| vec![
+        test_field("igIntMetaField", 0x8, Some("_count"), None),
+        test_field("igIntMetaField", 0xC, Some("_capacity"), None),
+        test_field("igMemoryRefMetaField", 0x10, Some("_data"), Some(child_type)),
+    ];
+    let array_field = |_type: &str, offset: u16, name: &str, num: u16| {
+        let field = test_field(_type, offset, Some(name), None);
+        field.write().unwrap().num = Some(num);
+        field
+    };
+    let enum_field = |offset: u16, name: &str, meta_enum: &str| {
+        let field = test_field("igEnumMetaField", offset, Some(name), None);
+        field.write().unwrap().ig_meta_enum = Some(Arc::from(meta_enum));
+        field
+    };
+    let bit_field = |name: &str, shift: u8, bits: u8, inner: ArkMetaObjectField| {
+        let field = test_field("igBitFieldMetaField", 0x8, Some(name), None);
+        field.write().unwrap().ig_bit_shift_info = Some(Arc::new(RwLock::new(BitShiftInfo { shift, bits, storage_field: "_flags".to_string(), _type: Some(inner) })));
+        field
+    };
+    let vector_field = |offset: u16, name: &str, element_type: &str| {
+        let field = test_field("igVectorMetaField", offset, Some(name), None);
+        field.write().unwrap().ig_vector_info = Some(VectorInfo { field: Some(test_field(element_type, 0, None, None)), mem_type_alignment_multiple: 1 });
+        field
+    };
+    let mut string_int_hash_table = test_meta_object("TestStringIntHashTable", Some("igHashTable"), vec![]);
+    string_int_hash_table.overriden_fields = vec![
+        test_field("igMemoryRefMetaField", 0x8, Some("_values"), Some("igIntMetaField")),
+        test_field("igMemoryRefMetaField", 0x10, Some("_keys"), Some("igStringMetaField")),
+    ];
+    string_int_hash_table.hash_table_info = Some(HashTableInfo { invalid_value: "-84215046".to_string(), invalid_key: "(null)".to_string() });
+    let mut hash_table_child = test_meta_object("TestHashTableChild", Some("TestStringIntHashTable"), vec![
+        test_field("igIntMetaField", 0x24, Some("_extra"), None),
+    ]);
+    hash_table_child.hash_table_info = string_int_hash_table.hash_table_info.clone();
+    let objects = vec![
+        test_meta_object("igObject", None, vec![]),
+        test_meta_object("igObjectList", Some("igObject"), list_fields("igObjectRefMetaField")),
+        test_meta_object("igNameList", Some("igObject"), list_fields("igNameMetaField")),
+        test_meta_object("TestObject", Some("igObject"), vec![
+            test_field("igIntMetaField", 0x8, Some("_value"), None),
+            test_field("igStringMetaField", 0xC, Some("_name"), None),
+            test_field("igObjectRefMetaField", 0x10, Some("_next"), None),
+            test_field("igMemoryRefMetaField", 0x14, Some("_numbers"), Some("igIntMetaField")),
+            test_field("igObjectRefMetaField", 0x1C, Some("_type"), None),
+        ]),
+        test_meta_object("TestValues", Some("igObject"), vec![
+            test_field("igBoolMetaField", 0x8, Some("_bool"), None),
+            test_field("igCharMetaField", 0x9, Some("_char"), None),
+            test_field("igUnsignedCharMetaField", 0xA, Some("_unsignedChar"), None),
+            test_field("igShortMetaField", 0xC, Some("_short"), None),
+            test_field("igUnsignedShortMetaField", 0xE, Some("_unsignedShort"), None),
+            test_field("igUnsignedIntMetaField", 0x10, Some("_unsignedInt"), None),
+            test_field("igFloatMetaField", 0x14, Some("_float"), None),
+            test_field("igTimeMetaField", 0x18, Some("_time"), None),
+            test_field("igLongMetaField", 0x20, Some("_long"), None),
+            test_field("igUnsignedLongMetaField", 0x28, Some("_unsignedLong"), None),
+            test_field("igRandomMetaField", 0x30, Some("_random"), None),
+            test_field("igVec2fMetaField", 0x38, Some("_vec2f"), None),
+            test_field("igVec3fMetaField", 0x40, Some("_vec3f"), None),
+            test_field("igVec3fAlignedMetaField", 0x50, Some("_vec3fAligned"), None),
+            test_field("igVec4fMetaField", 0x60, Some("_vec4f"), None),
+            test_field("igQuaternionfMetaField", 0x70, Some("_quaternionf"), None),
+            test_field("igMatrix44fMetaField", 0x80, Some("_matrix44f"), None),
+            test_field("igMemoryRefMetaField", 0xC0, Some("_points"), Some("igVec3fAlignedMetaField")),
+        ]),
+        test_meta_object("TestArrays", Some("igObject"), vec![
+            array_field("igIntArrayMetaField", 0x8, "_ints", 4),
+            array_field("igObjectRefArrayMetaField", 0x18, "_objects", 3),
+            array_field("igVec3fArrayMetaField", 0x24, "_points", 2),
+        ]),
+        test_meta_object("TestVectors", Some("igObject"), vec![
+            vector_field(0x8, "_ints", "igIntMetaField"),
+            vector_field(0x14, "_names", "igStringMetaField"),
+        ]),
+        test_meta_object("igHashTable", Some("igObject"), vec![
+            test_field("igMemoryRefMetaField", 0x8, Some("_values"), Some("igObjectRefMetaField")),
+            test_field("igMemoryRefMetaField", 0x10, Some("_keys"), Some("igObjectRefMetaField")),
+            test_field("igIntMetaField", 0x18, Some("_hashItemCount"), None),
+            test_field("igBoolMetaField", 0x1C, Some("_autoRehash"), None),
+            test_field("igFloatMetaField", 0x20, Some("_loadFactor"), None),
+        ]),
+        string_int_hash_table,
+        hash_table_child,
+        test_meta_object("TestEnums", Some("igObject"), vec![
+            enum_field(0x8, "_named", "TEST_COLOR"),
+            enum_field(0xC, "_byName", "TEST_COLOR"),
+            enum_field(0x10, "_unnamed", "TEST_COLOR"),
+            enum_field(0x14, "_missingEnum", "TEST_MISSING"),
+        ]),
+        test_meta_object("TestBitFields", Some("igObject"), vec![
+            test_field("igUnsignedIntMetaField", 0x8, Some("_flags"), None),
+            bit_field("_enabled", 0, 1, test_field("igBoolMetaField", 0, None, None)),
+            bit_field("_color", 1, 3, enum_field(0, "", "TEST_COLOR")),
+            bit_field("_offset", 4, 4, test_field("igIntMetaField", 0, None, None)),
+            bit_field("_count", 8, 8, test_field("igUnsignedCharMetaField", 0, None, None)),
+            test_field("igIntMetaField", 0xC, Some("_after"), None),
+        ]),
+        test_meta_object("TestBitFieldsChild", Some("TestBitFields"), vec![]),
+    ];
+
+    let mut imm = igMetadataManager::new(fields, vec![platforms, colors], objects, platform);
+    register_metafields(&mut imm);
+    imm
+}
+
+fn test_instantiate(imm: &mut igMetadataManager, type_name: &str) -> igObject {
+    let meta = imm.get_or_create_meta(type_name).unwrap();
+    let object = meta.read().unwrap().raw_instantiate(igMemoryPool::Default, false).unwrap();
+    object
+}
+
+fn test_get_field(object: &igObject, name: &str) -> Option<igAny> {
+    object.read().unwrap().get_field(name).unwrap_or_else(|_| panic!("missing field {}", name))
+}
+
+fn test_int_memory(values: Vec<i32>) -> igAny {
+    let mut memory: igMemory<igAny> = igMemory::new();
+    memory.data = values.into_iter().map(|x| {
+        let value: igAny = Arc::new(RwLock::new(x));
+        value
+    }).collect();
+    Arc::new(RwLock::new(memory))
+}
+
+fn test_directory(imm: &mut igMetadataManager, path: &str, objects: Vec<igObject>) -> igObjectDirectory {
+    let object_list: Arc<RwLock<igObjectList>> = test_instantiate(imm, "igObjectList").cast_to().unwrap();
+    for object in objects {
+        object_list.read().unwrap().push(object);
+    }
+    igObjectDirectory {
+        path: path.to_string(),
+        name: igName::new(path.trim_end_matches(".igz").to_string()),
+        dependencies: igObjectDirectoryList::new(),
+        use_name_list: false,
+        object_list,
+        name_list: Arc::new(RwLock::new(igNameList::new())),
+        loader: Arc::new(RwLock::new(igIGZObjectLoader)),
+        load_report: LoadReport::default(),
+        loader_match: None,
+    }
+}
+
+fn test_same_object(a: &igObject, b: &igObject) -> bool {
+    Arc::as_ptr(a) as *const () == Arc::as_ptr(b) as *const ()
+}
+
+/// Writes a directory through the igz saver and checks that loading it back produces the same objects.
+#[test]
+fn test_igz_round_trip() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let test_object_meta: igObject = imm.get_or_create_meta("TestObject").unwrap();
+
+    let first = test_instantiate(&mut imm, "TestObject");
+    let second = test_instantiate(&mut imm, "TestObject");
+    {
+        let mut guard = first.write().unwrap();
+        guard.set_field("_value", Some(Arc::new(RwLock::new(7i32)))).unwrap();
+        guard.set_field("_name", Some(Arc::new(RwLock::new(Arc::<str>::from("first"))))).unwrap();
+        guard.set_field("_next", Some(Arc::new(RwLock::new(second.clone())))).unwrap();
+        guard.set_field("_numbers", Some(test_int_memory(vec![1, 2, 3]))).unwrap();
+        guard.set_field("_type", Some(Arc::new(RwLock::new(test_object_meta.clone())))).unwrap();
+    }
+    {
+        let mut guard = second.write().unwrap();
+        guard.set_field("_value", Some(Arc::new(RwLock::new(-3i32)))).unwrap();
+        guard.set_field("_next", Some(Arc::new(RwLock::new(first.clone())))).unwrap();
+        guard.set_field("_numbers", Some(test_int_memory(vec![]))).unwrap();
+    }
+
+    let object_list: Arc<RwLock<igObjectList>> = test_instantiate(&mut imm, "igObjectList").cast_to().unwrap();
+    object_list.read().unwrap().push(first.clone());
+    object_list.read().unwrap().push(second.clone());
+    let name_list: Arc<RwLock<igNameList>> = test_instantiate(&mut imm, "igNameList").cast_to().unwrap();
+    name_list.read().unwrap().push(igName::new("first".to_string()));
+    name_list.read().unwrap().push(igName::new("second".to_string()));
+    let dir = igObjectDirectory {
+        path: "ig-library-igz-round-trip.igz".to_string(),
+        name: igName::new("ig-library-igz-round-trip".to_string()),
+        dependencies: igObjectDirectoryList::new(),
+        use_name_list: true,
+        object_list,
+        name_list,
+        loader: Arc::new(RwLock::new(igIGZObjectLoader)),
+        load_report: LoadReport::default(),
+        loader_match: None,
+    };
+
+    let mut object_stream_manager = igObjectStreamManager::new();
+    let saved = igIGZSaver::save(&mut imm, &object_stream_manager, &dir, 0x09, 0, platform.clone(), Endian::Big).unwrap();
+    let temp_dir = std::env::temp_dir();
+    std::fs::write(temp_dir.join(&dir.path), &saved).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let loaded = object_stream_manager
+        .load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, dir.path.clone())
+        .unwrap();
+    std::fs::remove_file(temp_dir.join(&dir.path)).ok();
+
+    let loaded = loaded.read().unwrap();
+    assert!(loaded.use_name_list);
+    let names: Vec<String> = loaded.name_list.read().unwrap().iter().map(|x| x.string.unwrap()).collect();
+    assert_eq!(names, ["first", "second"]);
+    let objects = loaded.object_list.read().unwrap();
+    assert_eq!(objects.len(), 2);
+    let (loaded_first, loaded_second) = (objects.get(0).unwrap(), objects.get(1).unwrap());
+
+    let int = |object: &igObject, name: &str| *test_get_field(object, name).unwrap().read().unwrap().downcast_ref::<i32>().unwrap();
+    let object = |object: &igObject, name: &str| test_get_field(object, name).map(|x| x.read().unwrap().downcast_ref::<igObject>().unwrap().clone());
+    let ints = |object: &igObject, name: &str| -> Vec<i32> {
+        let value = test_get_field(object, name).unwrap();
+        let guard = value.read().unwrap();
+        let memory = guard.downcast_ref::<igMemory<igAny>>().unwrap();
+        memory.data.iter().map(|x| *x.read().unwrap().downcast_ref::<i32>().unwrap()).collect()
+    };
+
+    assert_eq!(int(&loaded_first, "_value"), 7);
+    assert_eq!(test_get_field(&loaded_first, "_name").unwrap().read().unwrap().downcast_ref::<Arc<str>>().unwrap().as_ref(), "first");
+    assert!(test_same_object(&object(&loaded_first, "_next").unwrap(), &loaded_second));
+    assert_eq!(ints(&loaded_first, "_numbers"), [1, 2, 3]);
+    assert!(test_same_object(&object(&loaded_first, "_type").unwrap(), &test_object_meta));
+
+    assert_eq!(int(&loaded_second, "_value"), -3);
+    assert!(test_get_field(&loaded_second, "_name").is_none());
+    assert!(test_same_object(&object(&loaded_second, "_next").unwrap(), &loaded_first));
+    assert!(ints(&loaded_second, "_numbers").is_empty());
+    assert!(object(&loaded_second, "_type").is_none());
+}
+
+/// Breaks an igz in ways a game dump could be broken and checks that strict loading fails with a useful error while lenient loading gets as far as it can.
+#[test]
+fn test_igz_error_reporting() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let object = test_instantiate(&mut imm, "TestObject");
+    object.write().unwrap().set_field("_value", Some(Arc::new(RwLock::new(1i32)))).unwrap();
+    let dir = test_directory(&mut imm, "ig-library-igz-error-reporting.igz", vec![object]);
+    let mut saved = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform.clone(), Endian::Big).unwrap();
+
+    // Rename the type in TMET to one the metadata doesn't know about
+    let type_name = saved.windows(10).position(|x| x == b"TestObject").unwrap();
+    saved[type_name + 9] = b'X';
+    let temp_dir = std::env::temp_dir();
+    let unknown_type_path = "ig-library-igz-unknown-type.igz";
+    let bad_magic_path = "ig-library-igz-bad-magic.igz";
+    std::fs::write(temp_dir.join(unknown_type_path), &saved).unwrap();
+    std::fs::write(temp_dir.join(bad_magic_path), b"NOTANIGZFILE").unwrap();
+
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let mut load = |lenient: bool, path: &str| {
+        let mut object_stream_manager = igObjectStreamManager::new();
+        object_stream_manager.lenient = lenient;
+        let result = object_stream_manager.load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, path.to_string());
+        (result, object_stream_manager)
+    };
+
+    let (result, object_stream_manager) = load(false, bad_magic_path);
+    let Err(LoaderError::Igz(IgzLoaderError { kind: IgzLoaderErrorKind::InvalidMagic(_), location: IgzLoaderLocation::Header, .. })) = result else {
+        panic!("bad magic should fail to load");
+    };
+    assert!(object_stream_manager.path_to_directory_lookup.is_empty());
+
+    let (result, _) = load(false, unknown_type_path);
+    let Err(LoaderError::Igz(IgzLoaderError { kind: IgzLoaderErrorKind::UnknownType(name), location: IgzLoaderLocation::Fixup(fixup), .. })) = result else {
+        panic!("unknown types should fail to load when not lenient");
+    };
+    assert_eq!((name.as_str(), fixup.as_str()), ("TestObjecX", "TMET"));
+
+    let (result, _) = load(true, unknown_type_path);
+    let loaded = result.unwrap();
+    std::fs::remove_file(temp_dir.join(unknown_type_path)).ok();
+    std::fs::remove_file(temp_dir.join(bad_magic_path)).ok();
+
+    let loaded = loaded.read().unwrap();
+    assert_eq!(loaded.load_report.warnings.len(), 1);
+    let objects = loaded.object_list.read().unwrap();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects.get(0).unwrap().read().unwrap().object_name().as_ref(), "igNull");
+}
+
+/// Dumps the structure of a saved igz and checks it matches what the saver wrote.
+#[test]
+fn test_igz_dump() {
+    let mut imm = test_metadata_manager();
+    let object = test_instantiate(&mut imm, "TestObject");
+    object.write().unwrap().set_field("_name", Some(Arc::new(RwLock::new(Arc::<str>::from("dumped"))))).unwrap();
+    let dir = test_directory(&mut imm, "ig-library-igz-dump.igz", vec![object]);
+    let saved = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE, Endian::Big).unwrap();
+
+    let dump = IgzDump::from_bytes(&imm, &dir.path, saved).unwrap();
+    assert_eq!((dump.version, dump.platform.clone(), dump.big_endian), (0x09, IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE, true));
+    assert_eq!(dump.sections[0].name, "Default");
+    assert_eq!(dump.fixups.len(), dump.fixup_count as usize);
+    assert!(dump.fixups.iter().all(|x| x.fixup.is_some()));
+    // The saver writes strings into their own section instead of TSTR
+    assert!(dump.strings.is_empty());
+    assert_eq!(dump.runtime_fields.string_references.len(), 1);
+    assert_eq!(dump.objects.len(), 2);
+    assert_eq!(dump.runtime_fields.object_lists, [dump.objects[0].offset]);
+    let mut types: Vec<&str> = dump.objects.iter().filter_map(|x| x.type_name.as_deref()).collect();
+    types.sort();
+    assert_eq!(types, ["TestObject", "igObjectList"]);
+    assert!(dump.to_json().contains("\"magic\": \"RVTB\""));
+}
+
+/// Builds the header and chunk descriptors of every igz version that can be loaded and checks they are parsed into the same sections, and that 0x07 and unknown versions are refused.
+/// From 0x08 the fixup count follows the platform and the descriptors follow it, before that the count is in the fixup section and the descriptors overlap the platform.
+#[test]
+fn test_igz_header_versions() {
+    let imm = test_metadata_manager();
+    let platform_index = imm.get_enum_index::<IG_CORE_PLATFORM>("IG_CORE_PLATFORM_CAFE").unwrap() as u32;
+    for version in [0x05u32, 0x06, 0x08, 0x09] {
+        let (descriptor_start, attribute_location) = if version >= 0x08 { (0x14, 0x224) } else { (0xC, 0x56C) };
+        let mut file = vec![0u8; 0x1010];
+        let mut write = |offset: usize, value: u32| file[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        write(0x4, version);
+        write(0xC, platform_index);
+        if version >= 0x08 {
+            write(0x10, 3);
+        } else {
+            write(0x810, 3);
+        }
+        // The fixup section, then a section in the System pool named at offset 0x10 of the attributes
+        write(descriptor_start + 0x4, 0x800);
+        write(descriptor_start + 0x8, 0x20);
+        write(descriptor_start + 0x10, 0x10);
+        write(descriptor_start + 0x14, 0x1000);
+        write(descriptor_start + 0x18, 0x10);
+        file[..4].copy_from_slice(b"\x01ZGI");
+        file[attribute_location + 0x10..attribute_location + 0x16].copy_from_slice(b"System");
+
+        let mut handle = Cursor::new(file);
+        let mut ctx = igIGZLoader::read_header(&mut handle, &imm, "ig-library-igz-header.igz").unwrap();
+        igIGZLoader::parse_sections(&mut handle, Endian::Little, &mut ctx).unwrap();
+        assert_eq!((ctx.version, ctx.platform.clone(), ctx.fixup_count), (version, IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE, 3));
+        assert_eq!((ctx.section_count, ctx.fixup_offset), (2, 0x800));
+        assert_eq!((ctx.loaded_pools[0], ctx.loaded_pointers[0]), (igMemoryPool::System, 0x1000));
+    }
+
+    for version in [0x07u32, 0x0A] {
+        let mut unsupported = Cursor::new([b"\x01ZGI".as_slice(), &version.to_le_bytes(), &[0; 0x20]].concat());
+        let result = igIGZLoader::read_header(&mut unsupported, &imm, "ig-library-igz-header.igz");
+        let refused = matches!(&result, Err(IgzLoaderError { kind: IgzLoaderErrorKind::UnsupportedVersion(x), offset: 0x4, .. }) if *x == version);
+        assert!(refused, "igz version {:#x} should fail to load", version);
+        assert!(matches!(get_chunk_descriptor_start(version), Err(IgzLoaderErrorKind::UnsupportedVersion(_))));
+    }
+}
+
+/// Runtime fixups with a broken header or data that ends too early fail with an error rather than panicking.
+#[test]
+fn test_runtime_fixup_bounds() {
+    assert_eq!(unpack_serialized_ints(0x09, &[0x21, 0x03], 3).unwrap(), [0x4, 0xC, 0x18]);
+    // Both nibbles say another follows, but the data ends
+    assert!(matches!(unpack_serialized_ints(0x09, &[0x88], 1), Err(IgzLoaderErrorKind::Io(_))));
+    assert!(matches!(unpack_serialized_ints(0x09, &[0x11], 3), Err(IgzLoaderErrorKind::InvalidIndex { index: 3, .. })));
+    assert_eq!(fixup_data_size(0x20, 0x10).unwrap(), 0x10);
+    assert!(matches!(fixup_data_size(0x10, 0x20), Err(IgzLoaderErrorKind::Io(_))));
+}
+
+/// Writes a directory through the igx saver and checks that loading it back produces the same objects.
+#[test]
+fn test_igx_round_trip() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let test_object_meta: igObject = imm.get_or_create_meta("TestObject").unwrap();
+
+    let first = test_instantiate(&mut imm, "TestObject");
+    let second = test_instantiate(&mut imm, "TestObject");
+    {
+        let mut guard = first.write().unwrap();
+        guard.set_field("_value", Some(Arc::new(RwLock::new(7i32)))).unwrap();
+        guard.set_field("_name", Some(Arc::new(RwLock::new(Arc::<str>::from(" <first> & \"second\" "))))).unwrap();
+        guard.set_field("_next", Some(Arc::new(RwLock::new(second.clone())))).unwrap();
+        guard.set_field("_numbers", Some(test_int_memory(vec![1, 2, 3]))).unwrap();
+        guard.set_field("_type", Some(Arc::new(RwLock::new(test_object_meta.clone())))).unwrap();
+    }
+    second.write().unwrap().set_field("_next", Some(Arc::new(RwLock::new(first.clone())))).unwrap();
+
+    let mut dir = test_directory(&mut imm, "ig-library-igx-round-trip.igx", vec![first, second]);
+    dir.use_name_list = true;
+    dir.name_list = test_instantiate(&mut imm, "igNameList").cast_to().unwrap();
+    dir.name_list.read().unwrap().push(igName::new("first".to_string()));
+    dir.name_list.read().unwrap().push(igName::from_hash(0x1234));
+
+    let mut object_stream_manager = igObjectStreamManager::new();
+    let saved = igIGXSaver::save(&mut imm, &object_stream_manager, &dir).unwrap();
+    let temp_dir = std::env::temp_dir();
+    std::fs::write(temp_dir.join(&dir.path), &saved).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let loaded = object_stream_manager
+        .load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, dir.path.clone())
+        .unwrap();
+    std::fs::remove_file(temp_dir.join(&dir.path)).ok();
+
+    let loaded = loaded.read().unwrap();
+    assert!(loaded.load_report.is_clean());
+    let names: Vec<(Option<String>, u32)> = loaded.name_list.read().unwrap().iter().map(|x| (x.string, x.hash)).collect();
+    assert_eq!(names, [(Some("first".to_string()), hash_lower("first")), (find_string(0x1234), 0x1234)]);
+    let objects = loaded.object_list.read().unwrap();
+    assert_eq!(objects.len(), 2);
+    let (loaded_first, loaded_second) = (objects.get(0).unwrap(), objects.get(1).unwrap());
+
+    let object = |object: &igObject, name: &str| test_get_field(object, name).map(|x| x.read().unwrap().downcast_ref::<igObject>().unwrap().clone());
+    assert_eq!(*test_get_field(&loaded_first, "_value").unwrap().read().unwrap().downcast_ref::<i32>().unwrap(), 7);
+    assert_eq!(test_get_field(&loaded_first, "_name").unwrap().read().unwrap().downcast_ref::<Arc<str>>().unwrap().as_ref(), " <first> & \"second\" ");
+    assert!(test_same_object(&object(&loaded_first, "_next").unwrap(), &loaded_second));
+    assert!(test_same_object(&object(&loaded_first, "_type").unwrap(), &test_object_meta));
+    let numbers = test_get_field(&loaded_first, "_numbers").unwrap();
+    let numbers: Vec<i32> = numbers.read().unwrap().downcast_ref::<igMemory<igAny>>().unwrap().data.iter().map(|x| *x.read().unwrap().downcast_ref::<i32>().unwrap()).collect();
+    assert_eq!(numbers, [1, 2, 3]);
+
+    assert!(test_get_field(&loaded_second, "_name").is_none());
+    assert!(test_same_object(&object(&loaded_second, "_next").unwrap(), &loaded_first));
+    assert!(object(&loaded_second, "_type").is_none());
+}
+
+/// Verifies the names of external references are only written as a string when the hash can be made from it again.
+#[test]
+fn test_igx_external_names() {
+    let mut imm = test_metadata_manager();
+    let recovered = "IgLibraryRecoveredName";
+    add_string(recovered);
+    let names = [
+        igName::new("Lowered".to_string()),
+        igName::from_hash(hash(recovered)),
+        igName::with_hash("Cased".to_string(), hash("Cased")),
+    ];
+    let targets: Vec<igObject> = names.iter().map(|_| test_instantiate(&mut imm, "TestObject")).collect();
+    let mut other = test_directory(&mut imm, "ig-library-igx-other.igz", targets.clone());
+    other.use_name_list = true;
+    other.name_list = test_instantiate(&mut imm, "igNameList").cast_to().unwrap();
+    for name in &names {
+        other.name_list.read().unwrap().push(name.clone());
+    }
+    let mut object_stream_manager = igObjectStreamManager::new();
+    object_stream_manager.path_to_directory_lookup.insert(hash_lower(&other.path), Arc::new(RwLock::new(other)));
+
+    let objects: Vec<igObject> = targets.iter().map(|target| {
+        let object = test_instantiate(&mut imm, "TestObject");
+        object.write().unwrap().set_field("_next", Some(Arc::new(RwLock::new(target.clone())))).unwrap();
+        object
+    }).collect();
+    let dir = test_directory(&mut imm, "ig-library-igx-external-names.igx", objects);
+    let saved = String::from_utf8(igIGXSaver::save(&mut imm, &object_stream_manager, &dir).unwrap()).unwrap();
+
+    assert!(saved.contains("name=\"Lowered\""));
+    assert!(!saved.contains(recovered));
+    assert!(saved.contains(&format!("nameHash=\"{:#x}\"", hash(recovered))));
+    assert!(saved.contains(&format!("name=\"Cased\" nameHash=\"{:#x}\"", hash("Cased"))));
+    assert_eq!(saved.matches("nameHash=").count(), 2);
+}
+
+/// Builds a little endian igb by hand, as no tool writes them anymore, and checks the objects, memory and root list it describes are loaded.
+#[test]
+fn test_igb_load() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+
+    let u32s = |values: &[u32]| -> Vec<u8> { values.iter().flat_map(|x| x.to_le_bytes()).collect() };
+    let names = |names: &[&str]| -> Vec<u8> { names.iter().flat_map(|x| [x.as_bytes(), &[0]].concat()).collect() };
+    let meta_fields = ["igIntMetaField", "igStringMetaField", "igObjectRefMetaField", "igMemoryRefMetaField"];
+    // (name, (meta field, slot, size) per field)
+    let meta_objects: [(&str, &[[u16; 3]]); 4] = [
+        ("igObjectDirEntry", &[]),
+        ("igMemoryDirEntry", &[]),
+        ("TestObject", &[[0, 0, 4], [1, 1, 4], [2, 2, 4], [3, 3, 4], [2, 4, 4]]),
+        ("igObjectList", &[[0, 0, 4], [0, 1, 4], [3, 2, 4]]),
+    ];
+
+    let mut meta_field_table: Vec<u8> = meta_fields.iter().flat_map(|x| u32s(&[x.len() as u32 + 1, 1, 0])).collect();
+    meta_field_table.extend(names(&meta_fields));
+    let mut meta_object_table = vec![];
+    for (name, fields) in meta_objects {
+        meta_object_table.extend(u32s(&[name.len() as u32 + 1, 1, 0, fields.len() as u32]));
+        meta_object_table.extend(fields.iter().flatten().flat_map(|x| x.to_le_bytes()));
+    }
+    meta_object_table.extend(names(&meta_objects.map(|(name, _)| name)));
+    // Two TestObjects, the igObjectList holding them, the ints of the first and the list's data
+    let entries = [
+        u32s(&[0, 12, 2]),
+        u32s(&[0, 12, 2]),
+        u32s(&[0, 12, 3]),
+        u32s(&[1, 16, 12, 0]),
+        u32s(&[1, 16, 8, 2]),
+    ].concat();
+    let refs = u32s(&[5, 0, 1, 2, 3, 4]);
+    let objects = [
+        u32s(&[30, 7, 6]),
+        b"first\0".to_vec(),
+        u32s(&[1, 3, IGB_NULL_INDEX]),
+        u32s(&[24, -3i32 as u32, 0, 0, IGB_NULL_INDEX, IGB_NULL_INDEX]),
+        u32s(&[16, 2, 2, 4]),
+    ].concat();
+    let memory = u32s(&[1, 2, 3, 0, 1]);
+
+    let header = u32s(&[
+        entries.len() as u32, 5,
+        meta_object_table.len() as u32, 4,
+        objects.len() as u32, 3,
+        memory.len() as u32, 2,
+        meta_field_table.len() as u32, 4,
+        0xFADA, 1 | IgbHeader::HAS_INFO,
+    ]);
+    let info = u32s(&[2]);
+    let igb = [header, meta_field_table, meta_object_table, entries, refs, info, objects, memory].concat();
+
+    let temp_dir = std::env::temp_dir();
+    let path = "ig-library-igb-load.igb";
+    let bad_magic_path = "ig-library-igb-bad-magic.igb";
+    std::fs::write(temp_dir.join(path), &igb).unwrap();
+    std::fs::write(temp_dir.join(bad_magic_path), [0u8; 0x30]).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let mut object_stream_manager = igObjectStreamManager::new();
+    let result = object_stream_manager.load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, bad_magic_path.to_string());
+    let Err(LoaderError::Igb(IgbLoaderError { kind: IgbLoaderErrorKind::InvalidMagic(0), .. })) = result else {
+        panic!("bad magic should fail to load");
+    };
+    let loaded = object_stream_manager
+        .load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, path.to_string())
+        .unwrap();
+    std::fs::remove_file(temp_dir.join(path)).ok();
+    std::fs::remove_file(temp_dir.join(bad_magic_path)).ok();
+
+    let loaded = loaded.read().unwrap();
+    assert!(loaded.load_report.is_clean());
+    let objects = loaded.object_list.read().unwrap();
+    assert_eq!(objects.len(), 2);
+    let (loaded_first, loaded_second) = (objects.get(0).unwrap(), objects.get(1).unwrap());
+
+    let object = |object: &igObject, name: &str| test_get_field(object, name).map(|x| x.read().unwrap().downcast_ref::<igObject>().unwrap().clone());
+    assert_eq!(*test_get_field(&loaded_first, "_value").unwrap().read().unwrap().downcast_ref::<i32>().unwrap(), 7);
+    assert_eq!(test_get_field(&loaded_first, "_name").unwrap().read().unwrap().downcast_ref::<Arc<str>>().unwrap().as_ref(), "first");
+    assert!(test_same_object(&object(&loaded_first, "_next").unwrap(), &loaded_second));
+    let numbers = test_get_field(&loaded_first, "_numbers").unwrap();
+    let numbers: Vec<i32> = numbers.read().unwrap().downcast_ref::<igMemory<igAny>>().unwrap().data.iter().map(|x| *x.read().unwrap().downcast_ref::<i32>().unwrap()).collect();
+    assert_eq!(numbers, [1, 2, 3]);
+    assert!(object(&loaded_first, "_type").is_none());
+
+    assert_eq!(*test_get_field(&loaded_second, "_value").unwrap().read().unwrap().downcast_ref::<i32>().unwrap(), -3);
+    assert!(test_get_field(&loaded_second, "_name").is_none());
+    assert!(test_same_object(&object(&loaded_second, "_next").unwrap(), &loaded_first));
+    assert!(test_get_field(&loaded_second, "_numbers").is_none());
+}
+
+/// A loader for a made up format, standing in for one added outside of ig-library.
+struct TestLoader;
+
+impl igObjectLoader for TestLoader {
+    fn can_read(&self, file_name: &str) -> bool {
+        file_name.ends_with(".test")
+    }
+
+    fn can_read_magic(&self, header: &[u8]) -> bool {
+        header.starts_with(b"TEST")
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Test"
+    }
+
+    fn get_type(&self) -> &'static str {
+        "ig-library tests"
+    }
+
+    fn read_file(
+        &self,
+        _ig_file_context: &igFileContext,
+        _ig_registry: &igRegistry,
+        _ig_object_stream_manager: &mut igObjectStreamManager,
+        _ig_ext_ref_system: &mut igExternalReferenceSystem,
+        _ig_metadata_manager: &mut igMetadataManager,
+        dir: &mut igObjectDirectory,
+        file_path: &str,
+    ) -> Result<(), LoaderError> {
+        if file_path.contains("broken") {
+            return Err(LoaderError::Custom(format!("{} is broken", file_path).into()));
+        }
+        dir.use_name_list = true;
+        Ok(())
+    }
+}
+
+/// Checks loaders are picked by magic before extension, that loaders registered at runtime are used by the stream manager and that they can fail with their own errors.
+#[test]
+fn test_loader_registry() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let dir = test_directory(&mut imm, "ig-library-loader-registry.igz", vec![]);
+    let saved = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform.clone(), Endian::Big).unwrap();
+
+    let temp_dir = std::env::temp_dir();
+    let igz_path = "ig-library-loader-registry.dat";
+    let magic_path = "ig-library-loader-registry.igz";
+    let extension_path = "ig-library-loader-registry.test";
+    let broken_path = "ig-library-loader-registry-broken.test";
+    std::fs::write(temp_dir.join(igz_path), &saved).unwrap();
+    std::fs::write(temp_dir.join(magic_path), b"TEST").unwrap();
+    std::fs::write(temp_dir.join(extension_path), b"").unwrap();
+    std::fs::write(temp_dir.join(broken_path), b"TEST").unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+
+    let loaders = Arc::new(RwLock::new(igObjectLoaderRegistry::new()));
+    let detect = |path: &str| {
+        let choice = loaders.read().unwrap().detect(&ig_file_context, &ig_registry, path)?;
+        let name = choice.loader.read().unwrap().get_name();
+        Some((name, choice.matched_by))
+    };
+    assert_eq!(detect(igz_path), Some(("Alchemy Platform", LoaderMatch::Magic)));
+    assert_eq!(detect(magic_path), Some(("Alchemy Platform", LoaderMatch::Extension)));
+    assert_eq!(detect(extension_path), None);
+
+    loaders.write().unwrap().register(Arc::new(RwLock::new(TestLoader)));
+    assert_eq!(detect(magic_path), Some(("Test", LoaderMatch::Magic)));
+    assert_eq!(detect(extension_path), Some(("Test", LoaderMatch::Extension)));
+
+    let mut object_stream_manager = igObjectStreamManager::with_loaders(loaders.clone());
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let loaded = object_stream_manager
+        .load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, extension_path.to_string())
+        .unwrap();
+    let loaded_igz = object_stream_manager
+        .load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, igz_path.to_string())
+        .unwrap();
+    let broken = object_stream_manager.load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, broken_path.to_string());
+    for path in [igz_path, magic_path, extension_path, broken_path] {
+        std::fs::remove_file(temp_dir.join(path)).ok();
+    }
+
+    let loaded = loaded.read().unwrap();
+    assert_eq!(loaded.loader.read().unwrap().get_name(), "Test");
+    assert_eq!(loaded.loader_match, Some(LoaderMatch::Extension));
+    assert!(loaded.use_name_list);
+    let loaded_igz = loaded_igz.read().unwrap();
+    assert_eq!(loaded_igz.loader.read().unwrap().get_name(), "Alchemy Platform");
+    assert_eq!(loaded_igz.loader_match, Some(LoaderMatch::Magic));
+    assert_eq!(loaded_igz.object_list.read().unwrap().len(), 0);
+    let Err(LoaderError::Custom(e)) = broken else {
+        panic!("the test loader should have failed to load {}", broken_path);
+    };
+    assert_eq!(e.to_string(), format!("{} is broken", broken_path));
+    assert!(!object_stream_manager.path_to_directory_lookup.values().any(|x| x.read().unwrap().path == broken_path));
+}
+
+/// Checks that picking a loader for a file in an archive only decompresses the first block of it.
+#[test]
+fn test_loader_detection_in_archive() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let dir = test_directory(&mut imm, "ig-library-header-only.igz", vec![]);
+    let mut saved = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform.clone(), Endian::Big).unwrap();
+    saved.resize(0x18000, 0);
+    let archive = igArchive::build(vec![(dir.path.clone(), saved.clone())], 0x0B, Endian::Little, 0, CompressionType::kZlib).unwrap();
+
+    let mut ig_registry = igRegistry::new(platform);
+    ig_registry.build_tool = BuildTool::AlchemyLaboratory;
+    let temp_dir = std::env::temp_dir();
+    let archive_name = "ig-library-header-only.arc";
+    std::fs::write(temp_dir.join(archive_name), archive.save(&ig_registry).unwrap()).unwrap();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    ig_file_context.load_archive(&ig_registry, archive_name).unwrap();
+
+    let start = ig_file_context.open(&ig_registry, &dir.path, OPEN_FLAG_HEADER_ONLY);
+    assert_eq!(start._size, 0x18000);
+    assert_eq!(start._handle.unwrap().into_inner(), saved[..0x8000]);
+    let whole = ig_file_context.open(&ig_registry, &dir.path, 0);
+    assert_eq!(whole._handle.unwrap().into_inner(), saved);
+
+    let choice = igObjectLoaderRegistry::new().detect(&ig_file_context, &ig_registry, &dir.path).unwrap();
+    assert_eq!((choice.loader.read().unwrap().get_name(), choice.matched_by), ("Alchemy Platform", LoaderMatch::Magic));
+    drop(ig_file_context);
+    std::fs::remove_file(temp_dir.join(archive_name)).unwrap();
+}
+
+/// Writes every primitive and math metafield through igz in both endians and through igx, and checks the values read back are the same.
+#[test]
+fn test_inline_values_round_trip() {
+    let mut imm = test_metadata_manager();
+    let object = test_instantiate(&mut imm, "TestValues");
+    let mut matrix = igMatrix44f::identity();
+    matrix.m[3] = [1.5, -2.0, 3.25, 1.0];
+    let values: Vec<(&str, igAny)> = vec![
+        ("_bool", Arc::new(RwLock::new(true))),
+        ("_char", Arc::new(RwLock::new(-5i8))),
+        ("_unsignedChar", Arc::new(RwLock::new(200u8))),
+        ("_short", Arc::new(RwLock::new(-1234i16))),
+        ("_unsignedShort", Arc::new(RwLock::new(0xBEEFu16))),
+        ("_unsignedInt", Arc::new(RwLock::new(0xDEADBEEFu32))),
+        ("_float", Arc::new(RwLock::new(0.1f32))),
+        ("_time", Arc::new(RwLock::new(12.5f32))),
+        ("_long", Arc::new(RwLock::new(i64::MIN))),
+        ("_unsignedLong", Arc::new(RwLock::new(0x0123456789ABCDEFu64))),
+        ("_random", Arc::new(RwLock::new(igRandom { seed: 1, state: 0xFFFFFFFF }))),
+        ("_vec2f", Arc::new(RwLock::new(igVec2f { x: 1.0, y: -1.0 }))),
+        ("_vec3f", Arc::new(RwLock::new(igVec3f { x: 1.0, y: 2.0, z: 3.0 }))),
+        ("_vec3fAligned", Arc::new(RwLock::new(igVec3f { x: 4.0, y: 5.0, z: 6.0 }))),
+        ("_vec4f", Arc::new(RwLock::new(igVec4f { x: 0.0, y: f32::MAX, z: f32::MIN_POSITIVE, w: -0.0 }))),
+        ("_quaternionf", Arc::new(RwLock::new(igQuaternionf { x: 0.0, y: 0.0, z: 0.0, w: 1.0 }))),
+        ("_matrix44f", Arc::new(RwLock::new(matrix))),
+    ];
+    for (name, value) in &values {
+        object.write().unwrap().set_field(name, Some(value.clone())).unwrap();
+    }
+    let mut points: igMemory<igAny> = igMemory::new();
+    points.data = (0..3).map(|i| {
+        let point: igAny = Arc::new(RwLock::new(igVec3f { x: i as f32, y: 0.5, z: -(i as f32) }));
+        point
+    }).collect();
+    object.write().unwrap().set_field("_points", Some(Arc::new(RwLock::new(points)))).unwrap();
+
+    fn same(a: &igAny, b: &igAny) -> bool {
+        let (a, b) = (a.read().unwrap(), b.read().unwrap());
+        macro_rules! compare {
+            ($($type:ty),*) => {
+                $(if let (Some(a), Some(b)) = (a.downcast_ref::<$type>(), b.downcast_ref::<$type>()) {
+                    return a == b;
+                })*
+            };
+        }
+        compare!(bool, i8, u8, i16, u16, u32, i64, u64, igRandom, igVec2f, igVec3f, igVec4f, igQuaternionf, igMatrix44f);
+        // Compared by bits so -0.0 is checked to stay negative
+        match (a.downcast_ref::<f32>(), b.downcast_ref::<f32>()) {
+            (Some(a), Some(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+
+    for (path, loaded) in round_trip(&mut imm, "ig-library-inline-values", vec![object.clone()]) {
+        let loaded = loaded.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        for (name, value) in &values {
+            assert!(same(&test_get_field(&loaded, name).unwrap(), value), "{} changed in {}", name, path);
+        }
+        let points = test_get_field(&loaded, "_points").unwrap();
+        let points: Vec<igVec3f> = points.read().unwrap().downcast_ref::<igMemory<igAny>>().unwrap().data.iter().map(|x| *x.read().unwrap().downcast_ref::<igVec3f>().unwrap()).collect();
+        assert_eq!(points, [0.0, 1.0, 2.0].map(|i| igVec3f { x: i, y: 0.5, z: -i }), "_points changed in {}", path);
+    }
+}
+
+/// Writes fixed size arrays of values, object references and math types through igz in both endians and through igx, and checks the graph read back is the same.
+#[test]
+fn test_array_values_round_trip() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let meta = imm.get_or_create_meta("TestArrays").unwrap();
+    assert_eq!(meta.read().unwrap().size(platform.clone()), 0x3C);
+
+    let targets: Vec<igObject> = (0..2).map(|_| test_instantiate(&mut imm, "TestObject")).collect();
+    for (i, target) in targets.iter().enumerate() {
+        let mut guard = target.write().unwrap();
+        guard.set_field("_value", Some(Arc::new(RwLock::new(i as i32)))).unwrap();
+        guard.set_field("_numbers", Some(test_int_memory(vec![i as i32]))).unwrap();
+    }
+    let array = |values: Vec<Option<igAny>>| -> Option<igAny> { Some(Arc::new(RwLock::new(values))) };
+    let value = |x: igAny| Some(x);
+    let object = test_instantiate(&mut imm, "TestArrays");
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_ints", array([1, -2, i32::MAX, 0].into_iter().map(|x| value(Arc::new(RwLock::new(x)))).collect())).unwrap();
+        guard.set_field("_objects", array(vec![
+            value(Arc::new(RwLock::new(targets[1].clone()))),
+            None,
+            value(Arc::new(RwLock::new(targets[0].clone()))),
+        ])).unwrap();
+        guard.set_field("_points", array(vec![
+            value(Arc::new(RwLock::new(igVec3f { x: 1.0, y: 2.0, z: 3.0 }))),
+            value(Arc::new(RwLock::new(igVec3f { x: -1.0, y: 0.5, z: f32::MAX }))),
+        ])).unwrap();
+    }
+
+    for (path, loaded) in round_trip(&mut imm, "ig-library-array-values", vec![object.clone(), targets[0].clone()]) {
+        let dir = test_directory(&mut imm, &path, vec![object.clone(), targets[0].clone()]);
+        let result = GraphComparison::new(&mut imm).directories(&dir, &loaded.read().unwrap());
+        assert_eq!(result, Ok(()), "arrays changed in {}", path);
+    }
+
+    // An array has to be given exactly as many elements as its metadata lists
+    object.write().unwrap().set_field("_ints", array(vec![value(Arc::new(RwLock::new(1i32)))])).unwrap();
+    let dir = test_directory(&mut imm, "ig-library-array-values.igz", vec![object]);
+    let result = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform, Endian::Big);
+    assert!(matches!(result, Err(IgzSaverError::InvalidArrayLength { expected: 4, found: 1 })));
+}
+
+/// Writes vectors through igz in both endians and through igx, checking that only the elements in use are kept and null elements stay in their place.
+#[test]
+fn test_vector_values_round_trip() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let meta = imm.get_or_create_meta("TestVectors").unwrap();
+    assert_eq!(meta.read().unwrap().size(platform.clone()), 0x20);
+
+    let mut names: igMemory<igAny> = igMemory::new();
+    names.data = vec![Arc::new(RwLock::new(Arc::<str>::from("first"))), Arc::new(RwLock::new(igNullElement)), Arc::new(RwLock::new(Arc::<str>::from("third")))];
+    let object = test_instantiate(&mut imm, "TestVectors");
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_ints", Some(test_int_memory(vec![3, -1, 0, i32::MIN]))).unwrap();
+        guard.set_field("_names", Some(Arc::new(RwLock::new(names)))).unwrap();
+    }
+
+    for (path, loaded) in round_trip(&mut imm, "ig-library-vector-values", vec![object.clone()]) {
+        let dir = test_directory(&mut imm, &path, vec![object.clone()]);
+        let result = GraphComparison::new(&mut imm).directories(&dir, &loaded.read().unwrap());
+        assert_eq!(result, Ok(()), "vectors changed in {}", path);
+        let loaded = loaded.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        let names = test_get_field(&loaded, "_names").unwrap();
+        assert!(names.read().unwrap().downcast_ref::<igMemory<igAny>>().unwrap().data[1].read().unwrap().is::<igNullElement>(), "null name was lost in {}", path);
+    }
+}
+
+/// Checks igHashTable only exposes filled slots as a map, and that every slot, empty or not, survives igz in both endians and igx.
+#[test]
+fn test_hash_table_round_trip() {
+    let mut imm = test_metadata_manager();
+    let object = test_instantiate(&mut imm, "TestStringIntHashTable");
+    assert!(object.read().unwrap().as_any().is::<igHashTable>());
+
+    let mut keys: igMemory<igAny> = igMemory::new();
+    keys.data = vec![
+        Arc::new(RwLock::new(Arc::<str>::from("a"))),
+        Arc::new(RwLock::new(igNullElement)),
+        Arc::new(RwLock::new(Arc::<str>::from("b"))),
+        Arc::new(RwLock::new(igNullElement)),
+    ];
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_keys", Some(Arc::new(RwLock::new(keys)))).unwrap();
+        guard.set_field("_values", Some(test_int_memory(vec![1, -84215046, 2, -84215046]))).unwrap();
+        guard.set_field("_loadFactor", Some(Arc::new(RwLock::new(0.75f32)))).unwrap();
+    }
+    {
+        let mut guard = object.write().unwrap();
+        let table = guard.as_mut_any().downcast_mut::<igHashTable>().unwrap();
+        assert_eq!((table.len(), table.capacity()), (2, 4));
+        let get = |table: &igHashTable, key: &str| table.get(&Arc::<str>::from(key)).map(|x| *x.read().unwrap().downcast_ref::<i32>().unwrap());
+        assert_eq!(get(table, "a"), Some(1));
+        assert_eq!(get(table, "b"), Some(2));
+        assert_eq!(get(table, "c"), None);
+        let mut keys: Vec<Arc<str>> = table.iter().map(|(key, _)| key.read().unwrap().downcast_ref::<Arc<str>>().unwrap().clone()).collect();
+        keys.sort();
+        assert_eq!(keys, [Arc::from("a"), Arc::from("b")]);
+
+        let old = table.replace(&Arc::<str>::from("b"), Arc::new(RwLock::new(20i32))).unwrap();
+        assert_eq!(*old.read().unwrap().downcast_ref::<i32>().unwrap(), 2);
+        assert_eq!(get(table, "b"), Some(20));
+        assert!(table.replace(&Arc::<str>::from("c"), Arc::new(RwLock::new(3i32))).is_none());
+        assert!(!table.contains_key(&Arc::<str>::from("c")));
+    }
+    let item_count = test_get_field(&object, "_hashItemCount").unwrap();
+    assert_eq!(*item_count.read().unwrap().downcast_ref::<i32>().unwrap(), 2);
+
+    for (path, loaded) in round_trip(&mut imm, "ig-library-hash-table", vec![object.clone()]) {
+        let dir = test_directory(&mut imm, &path, vec![object.clone()]);
+        let result = GraphComparison::new(&mut imm).directories(&dir, &loaded.read().unwrap());
+        assert_eq!(result, Ok(()), "hash table changed in {}", path);
+        let loaded = loaded.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        let guard = loaded.read().unwrap();
+        let table = guard.as_any().downcast_ref::<igHashTable>().unwrap();
+        assert_eq!((table.len(), table.capacity(), table.load_factor), (2, 4, 0.75), "slots changed in {}", path);
+    }
+}
+
+/// Checks fields added by types extending igHashTable are kept, and that the item count a table was read with is saved instead of being worked out from its slots
+#[test]
+fn test_hash_table_fields_kept() {
+    let mut imm = test_metadata_manager();
+    let object = test_instantiate(&mut imm, "TestHashTableChild");
+    assert!(object.read().unwrap().as_any().is::<igHashTable>());
+    let item_count = |object: &igObject| *test_get_field(object, "_hashItemCount").unwrap().read().unwrap().downcast_ref::<i32>().unwrap();
+
+    let mut keys: igMemory<igAny> = igMemory::new();
+    keys.data = vec![Arc::new(RwLock::new(Arc::<str>::from("a"))), Arc::new(RwLock::new(igNullElement))];
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_keys", Some(Arc::new(RwLock::new(keys)))).unwrap();
+        guard.set_field("_values", Some(test_int_memory(vec![1, -84215046]))).unwrap();
+    }
+    assert_eq!(item_count(&object), 1);
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_hashItemCount", Some(Arc::new(RwLock::new(2i32)))).unwrap();
+        guard.set_field("_extra", Some(Arc::new(RwLock::new(7i32)))).unwrap();
+        assert!(matches!(guard.set_field("_missing", None), Err(SetObjectFieldError::FieldDoesntExist)));
+        assert!(guard.get_field("_missing").is_err());
+    }
+    assert_eq!(item_count(&object), 2);
+
+    for (path, loaded) in round_trip(&mut imm, "ig-library-hash-table-fields", vec![object.clone()]) {
+        let loaded = loaded.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        assert_eq!(item_count(&loaded), 2, "_hashItemCount changed in {}", path);
+        assert_eq!(test_get_field(&loaded, "_extra").unwrap().read().unwrap().downcast_ref::<i32>(), Some(&7), "_extra changed in {}", path);
+    }
+}
+
+/// Writes enum fields through igz in both endians and through igx, checking values are named from their meta enum, can be saved by name or number and that unknown names are refused.
+#[test]
+fn test_enum_values_round_trip() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let enum_value = |x: igEnumValue| -> Option<igAny> { Some(Arc::new(RwLock::new(x))) };
+    let object = test_instantiate(&mut imm, "TestEnums");
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_named", enum_value(igEnumValue::from_value(1))).unwrap();
+        guard.set_field("_byName", enum_value(igEnumValue::from_name("TEST_COLOR_BLUE"))).unwrap();
+        guard.set_field("_unnamed", enum_value(igEnumValue::from_value(3))).unwrap();
+        guard.set_field("_missingEnum", enum_value(igEnumValue::from_value(-7))).unwrap();
+    }
+    let expected = [
+        ("_named", igEnumValue { name: Some(Arc::from("TEST_COLOR_GREEN")), value: 1 }),
+        ("_byName", igEnumValue { name: Some(Arc::from("TEST_COLOR_BLUE")), value: 5 }),
+        ("_unnamed", igEnumValue::from_value(3)),
+        ("_missingEnum", igEnumValue::from_value(-7)),
+    ];
+
+    let dir = test_directory(&mut imm, "ig-library-enum-values.igx", vec![object.clone()]);
+    let text = String::from_utf8(igIGXSaver::save(&mut imm, &igObjectStreamManager::new(), &dir).unwrap()).unwrap();
+    assert!(text.contains("TEST_COLOR_BLUE") && text.contains("TEST_COLOR_GREEN"), "igx didn't name its enum values");
+    for (path, loaded) in round_trip(&mut imm, "ig-library-enum-values", vec![object.clone()]) {
+        let loaded = loaded.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        for (name, expected) in &expected {
+            let value = test_get_field(&loaded, name).unwrap();
+            assert_eq!(value.read().unwrap().downcast_ref::<igEnumValue>(), Some(expected), "{} changed in {}", name, path);
+        }
+    }
+
+    // A name has to be one the meta enum has
+    object.write().unwrap().set_field("_byName", enum_value(igEnumValue::from_name("TEST_COLOR_PURPLE"))).unwrap();
+    let dir = test_directory(&mut imm, "ig-library-enum-values.igz", vec![object.clone()]);
+    let result = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform, Endian::Big);
+    assert!(matches!(result, Err(IgzSaverError::UnknownEnumValue { meta_enum, name }) if meta_enum.as_ref() == "TEST_COLOR" && name.as_ref() == "TEST_COLOR_PURPLE"));
+    let result = igIGXSaver::save(&mut imm, &igObjectStreamManager::new(), &dir);
+    assert!(matches!(result, Err(IgxSaverError::UnknownEnumValue { .. })));
+}
+
+/// Packs bitfields of every kind into their storage field through igz in both endians and through igx, checking the bits outside of them are kept and values that don't fit are refused.
+#[test]
+fn test_bit_field_round_trip() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let meta = imm.get_or_create_meta("TestBitFields").unwrap();
+    assert_eq!(meta.read().unwrap().size(platform.clone()), 0x10);
+    // Bitfields share their offset with the storage field, which mustn't stop them from being inherited
+    let child = imm.get_or_create_meta("TestBitFieldsChild").unwrap();
+    let mut names: Vec<_> = child.read().unwrap().field_storage.name_lookup.keys().cloned().collect();
+    names.sort();
+    assert_eq!(names, ["_after", "_color", "_count", "_enabled", "_flags", "_offset"].map(Arc::from));
+    assert_eq!(child.read().unwrap().size(platform.clone()), 0x10);
+
+    let object = test_instantiate(&mut imm, "TestBitFields");
+    {
+        let mut guard = object.write().unwrap();
+        guard.set_field("_flags", Some(Arc::new(RwLock::new(0xF000_0000u32)))).unwrap();
+        guard.set_field("_enabled", Some(Arc::new(RwLock::new(true)))).unwrap();
+        guard.set_field("_color", Some(Arc::new(RwLock::new(igEnumValue::from_name("TEST_COLOR_BLUE"))))).unwrap();
+        guard.set_field("_offset", Some(Arc::new(RwLock::new(-3i32)))).unwrap();
+        guard.set_field("_count", Some(Arc::new(RwLock::new(200u8)))).unwrap();
+        guard.set_field("_after", Some(Arc::new(RwLock::new(-1i32)))).unwrap();
+    }
+
+    for (path, loaded) in round_trip(&mut imm, "ig-library-bit-fields", vec![object.clone()]) {
+        let loaded = loaded.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        let value = |name: &str| test_get_field(&loaded, name).unwrap();
+        assert_eq!(value("_flags").read().unwrap().downcast_ref::<u32>(), Some(&0xF000_C8DB), "_flags changed in {}", path);
+        assert_eq!(value("_enabled").read().unwrap().downcast_ref::<bool>(), Some(&true), "_enabled changed in {}", path);
+        assert_eq!(value("_color").read().unwrap().downcast_ref::<igEnumValue>(), Some(&igEnumValue { name: Some(Arc::from("TEST_COLOR_BLUE")), value: 5 }), "_color changed in {}", path);
+        assert_eq!(value("_offset").read().unwrap().downcast_ref::<i32>(), Some(&-3), "_offset changed in {}", path);
+        assert_eq!(value("_count").read().unwrap().downcast_ref::<u8>(), Some(&200), "_count changed in {}", path);
+        assert_eq!(value("_after").read().unwrap().downcast_ref::<i32>(), Some(&-1), "_after changed in {}", path);
+    }
+
+    // -8 to 7 fit in the 4 bits of _offset
+    object.write().unwrap().set_field("_offset", Some(Arc::new(RwLock::new(8i32)))).unwrap();
+    let dir = test_directory(&mut imm, "ig-library-bit-fields.igz", vec![object]);
+    let result = igIGZSaver::save(&mut imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform, Endian::Big);
+    assert!(matches!(result, Err(IgzSaverError::BitFieldOutOfRange { field, bits: 4 }) if field.as_ref() == "_offset"));
+}
+
+/// Saves the objects as an igz in both endians and as an igx, loading each file back. Returns what was loaded along with the path it was saved to
+fn round_trip(imm: &mut igMetadataManager, name: &str, objects: Vec<igObject>) -> Vec<(String, Arc<RwLock<igObjectDirectory>>)> {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let temp_dir = std::env::temp_dir();
+    let ig_file_context = igFileContext::new(temp_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let saves: [(String, Option<Endian>); 3] = [
+        (format!("{}-be.igz", name), Some(Endian::Big)),
+        (format!("{}-le.igz", name), Some(Endian::Little)),
+        (format!("{}.igx", name), None),
+    ];
+    saves.into_iter().map(|(path, endian)| {
+        let dir = test_directory(imm, &path, objects.clone());
+        let saved = match endian {
+            Some(endian) => igIGZSaver::save(imm, &igObjectStreamManager::new(), &dir, 0x09, 0, platform.clone(), endian).unwrap(),
+            None => igIGXSaver::save(imm, &igObjectStreamManager::new(), &dir).unwrap(),
+        };
+        std::fs::write(temp_dir.join(&path), &saved).unwrap();
+        let loaded = igObjectStreamManager::new()
+            .load(&ig_file_context, &ig_registry, imm, &mut ig_ext_ref_system, path.clone())
+            .unwrap();
+        std::fs::remove_file(temp_dir.join(&path)).ok();
+        (path, loaded)
+    }).collect()
+}
+
+/// Walks two object graphs side by side and reports the first place they differ. Objects are paired up by where they are reached from, so a reference to the wrong object is caught even when both objects hold the same values
+struct GraphComparison<'a> {
+    imm: &'a mut igMetadataManager,
+    /// Objects already paired up, in both directions
+    pairs: HashMap<usize, usize>,
+    reverse_pairs: HashMap<usize, usize>,
+    /// Objects that were paired up but haven't had their fields compared yet. Kept as a queue as recursing through long chains of objects would overflow the stack
+    pending: VecDeque<(String, igObject, igObject)>,
+}
+
+impl<'a> GraphComparison<'a> {
+    fn new(imm: &'a mut igMetadataManager) -> Self {
+        GraphComparison { imm, pairs: HashMap::new(), reverse_pairs: HashMap::new(), pending: VecDeque::new() }
+    }
+
+    fn directories(&mut self, a: &igObjectDirectory, b: &igObjectDirectory) -> Result<(), String> {
+        if a.use_name_list != b.use_name_list {
+            return Err(format!("use_name_list: {} vs {}", a.use_name_list, b.use_name_list));
+        }
+        let names = |dir: &igObjectDirectory| -> Vec<(Option<String>, u32)> { dir.name_list.read().unwrap().iter().map(|x| (x.string, x.hash)).collect() };
+        if names(a) != names(b) {
+            return Err(format!("name list: {:?} vs {:?}", names(a), names(b)));
+        }
+        let dependencies = |dir: &igObjectDirectory| -> Vec<String> { dir.dependencies.iter().map(|x| x.read().unwrap().path.clone()).collect() };
+        if dependencies(a) != dependencies(b) {
+            return Err(format!("dependencies: {:?} vs {:?}", dependencies(a), dependencies(b)));
+        }
+
+        let (object_list_a, object_list_b): (igObject, igObject) = (a.object_list.clone(), b.object_list.clone());
+        self.pair("objectList", &object_list_a, &object_list_b)?;
+        while let Some((path, a, b)) = self.pending.pop_front() {
+            self.objects(&path, &a, &b)?;
+        }
+        Ok(())
+    }
+
+    /// Pairs two references up, queueing the objects to be compared the first time they are seen
+    fn pair(&mut self, path: &str, a: &igObject, b: &igObject) -> Result<(), String> {
+        let (key_a, key_b) = (object_key(a), object_key(b));
+        match (self.pairs.get(&key_a), self.reverse_pairs.get(&key_b)) {
+            (None, None) => {
+                self.pairs.insert(key_a, key_b);
+                self.reverse_pairs.insert(key_b, key_a);
+                self.pending.push_back((path.to_string(), a.clone(), b.clone()));
+                Ok(())
+            }
+            (Some(paired), _) if *paired == key_b => Ok(()),
+            _ => Err(format!("{}: references a different object", path)),
+        }
+    }
+
+    fn objects(&mut self, path: &str, a: &igObject, b: &igObject) -> Result<(), String> {
+        let (name_a, name_b) = (a.read().unwrap().object_name(), b.read().unwrap().object_name());
+        if name_a != name_b {
+            return Err(format!("{}: type {} vs {}", path, name_a, name_b));
+        }
+        if let Some(meta_a) = a.read().unwrap().as_any().downcast_ref::<igMetaObject>() {
+            let meta_b = b.read().unwrap();
+            let meta_b = meta_b.as_any().downcast_ref::<igMetaObject>().unwrap();
+            return match meta_a.name == meta_b.name {
+                true => Ok(()),
+                false => Err(format!("{}: metaobject {} vs {}", path, meta_a.name, meta_b.name)),
+            };
+        }
+        if name_a.as_ref() == "igNull" || !self.imm.has_meta(&name_a) {
+            return Err(format!("{}: {} has no metadata to compare it with", path, name_a));
+        }
+
+        let meta = a.read().unwrap().meta_type(self.imm);
+        let mut fields: Vec<_> = meta.read().unwrap().field_storage.name_lookup
+            .iter()
+            .filter(|(_, field)| !matches!(field._type.as_ref(), "igStaticMetaField" | "igPropertyFieldMetaField"))
+            .map(|(name, field)| (name.clone(), field.offset))
+            .collect();
+        fields.sort_by_key(|(_, offset)| *offset);
+        for (name, _) in fields {
+            let field_path = format!("{}.{}", path, name);
+            let value_a = a.read().unwrap().get_field(&name).map_err(|_| format!("{}: missing from the first graph", field_path))?;
+            let value_b = b.read().unwrap().get_field(&name).map_err(|_| format!("{}: missing from the second graph", field_path))?;
+            match (value_a, value_b) {
+                (None, None) => {}
+                (Some(value_a), Some(value_b)) => self.values(&field_path, &value_a, &value_b)?,
+                (value_a, value_b) => return Err(format!("{}: null {} vs {}", field_path, value_a.is_none(), value_b.is_none())),
+            }
+        }
+        Ok(())
+    }
+
+    fn values(&mut self, path: &str, a: &igAny, b: &igAny) -> Result<(), String> {
+        let (guard_a, guard_b) = (a.read().unwrap(), b.read().unwrap());
+        let (a, b): (&(dyn Any + Send + Sync), &(dyn Any + Send + Sync)) = (&*guard_a, &*guard_b);
+        if a.type_id() != b.type_id() {
+            return Err(format!("{}: values are of different types", path));
+        }
+
+        if let (Some(a), Some(b)) = (a.downcast_ref::<igObject>(), b.downcast_ref::<igObject>()) {
+            return self.pair(path, a, b);
+        }
+        if let (Some(a), Some(b)) = (a.downcast_ref::<igMemory<igAny>>(), b.downcast_ref::<igMemory<igAny>>()) {
+            if a.data.len() != b.data.len() || a.pool != b.pool {
+                return Err(format!("{}: memory of {} in {:?} vs {} in {:?}", path, a.data.len(), a.pool, b.data.len(), b.pool));
+            }
+            for (i, (a, b)) in a.data.iter().zip(b.data.iter()).enumerate() {
+                self.values(&format!("{}[{}]", path, i), a, b)?;
+            }
+            return Ok(());
+        }
+        if let (Some(a), Some(b)) = (a.downcast_ref::<Vec<Option<igAny>>>(), b.downcast_ref::<Vec<Option<igAny>>>()) {
+            if a.len() != b.len() {
+                return Err(format!("{}: array of {} vs {}", path, a.len(), b.len()));
+            }
+            for (i, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+                match (a, b) {
+                    (None, None) => {}
+                    (Some(a), Some(b)) => self.values(&format!("{}[{}]", path, i), a, b)?,
+                    (a, b) => return Err(format!("{}[{}]: null {} vs {}", path, i, a.is_none(), b.is_none())),
+                }
+            }
+            return Ok(());
+        }
+        if let (Some(a), Some(b)) = (a.downcast_ref::<igName>(), b.downcast_ref::<igName>()) {
+            return match (&a.string, a.hash) == (&b.string, b.hash) {
+                true => Ok(()),
+                false => Err(format!("{}: name {:?} vs {:?}", path, a.string, b.string)),
+            };
+        }
+
+        fn same<T: PartialEq + std::fmt::Debug + 'static>(path: &str, a: &dyn Any, b: &dyn Any) -> Option<Result<(), String>> {
+            let (a, b) = (a.downcast_ref::<T>()?, b.downcast_ref::<T>()?);
+            Some(match a == b {
+                true => Ok(()),
+                false => Err(format!("{}: {:?} vs {:?}", path, a, b)),
+            })
+        }
+        if let (Some(a), Some(b)) = (a.downcast_ref::<f32>(), b.downcast_ref::<f32>()) {
+            // Compared by bits so NaN is equal to itself
+            return match a.to_bits() == b.to_bits() {
+                true => Ok(()),
+                false => Err(format!("{}: {:?} vs {:?}", path, a, b)),
+            };
+        }
+        if a.is::<igNullElement>() {
+            // Unused slots hold no value, the types were already checked to match
+            return Ok(());
+        }
+        let (a, b): (&dyn Any, &dyn Any) = (a, b);
+        same::<i32>(path, a, b)
+            .or_else(|| same::<u32>(path, a, b))
+            .or_else(|| same::<u64>(path, a, b))
+            .or_else(|| same::<bool>(path, a, b))
+            .or_else(|| same::<i8>(path, a, b))
+            .or_else(|| same::<u8>(path, a, b))
+            .or_else(|| same::<i16>(path, a, b))
+            .or_else(|| same::<u16>(path, a, b))
+            .or_else(|| same::<i64>(path, a, b))
+            .or_else(|| same::<igVec2f>(path, a, b))
+            .or_else(|| same::<igVec3f>(path, a, b))
+            .or_else(|| same::<igVec4f>(path, a, b))
+            .or_else(|| same::<igQuaternionf>(path, a, b))
+            .or_else(|| same::<igMatrix44f>(path, a, b))
+            .or_else(|| same::<igRandom>(path, a, b))
+            .or_else(|| same::<Arc<str>>(path, a, b))
+            .or_else(|| same::<igEnumValue>(path, a, b))
+            .or_else(|| same::<Vec<u8>>(path, a, b))
+            // A type not known here can't be said to match, so it fails rather than hiding a difference
+            .unwrap_or_else(|| Err(format!("{}: values of {:?} can't be compared", path, a.type_id())))
+    }
+}
+
+/// Loads an igz, saves it with the igz saver and loads what was saved, returning where the two object graphs differ.
+/// The saved igz is written to `resave_path` so the path of the igz being tested doesn't change where its dependencies are looked for.
+/// It is always saved as version 0x09, the only version the saver writes, so only igz files of that version should be given
+fn test_igz_resave(
+    imm: &mut igMetadataManager,
+    ig_file_context: &igFileContext,
+    ig_registry: &igRegistry,
+    path: &str,
+    resave_path: &std::path::Path,
+) -> Result<(), String> {
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let mut object_stream_manager = igObjectStreamManager::new();
+    let loaded = object_stream_manager
+        .load(ig_file_context, ig_registry, imm, &mut ig_ext_ref_system, path.to_string())
+        .map_err(|e| format!("first load failed: {}", e))?;
+    let original = ig_file_context.open(ig_registry, path, 0)._handle.unwrap().into_inner();
+    let header = IgzDump::from_bytes(imm, path, original).map_err(|e| e.to_string())?;
+    let endian = if header.big_endian { Endian::Big } else { Endian::Little };
+    let saved = igIGZSaver::save(imm, &object_stream_manager, &loaded.read().unwrap(), 0x09, header.meta_object_version, header.platform, endian)
+        .map_err(|e| format!("save failed: {}", e))?;
+    std::fs::write(resave_path, saved).map_err(|e| e.to_string())?;
+
+    let mut resaved_stream_manager = igObjectStreamManager::new();
+    let resaved = resaved_stream_manager
+        .load(ig_file_context, ig_registry, imm, &mut ig_ext_ref_system, resave_path.to_str().unwrap().to_string())
+        .map_err(|e| format!("second load failed: {}", e))?;
+    std::fs::remove_file(resave_path).ok();
+
+    let (loaded, resaved) = (loaded.read().unwrap(), resaved.read().unwrap());
+    GraphComparison::new(imm).directories(&loaded, &resaved)
+}
+
+/// Writes small igz fixtures with the igz saver and checks each survives being loaded, saved and loaded again.
+#[test]
+fn test_igz_resave_fixtures() {
+    let platform = IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE;
+    let mut imm = test_metadata_manager();
+    let test_object_meta: igObject = imm.get_or_create_meta("TestObject").unwrap();
+    let fixture_dir = std::env::temp_dir().join("ig-library-igz-fixtures");
+    std::fs::create_dir_all(&fixture_dir).unwrap();
+    let ig_file_context = igFileContext::new(fixture_dir.to_str().unwrap().to_string());
+    let ig_registry = igRegistry::new(platform.clone());
+
+    for (endian, suffix) in [(Endian::Big, "be"), (Endian::Little, "le")] {
+        let path = |name: &str| format!("{}-{}.igz", name, suffix);
+        let save = |imm: &mut igMetadataManager, object_stream_manager: &igObjectStreamManager, dir: &igObjectDirectory| {
+            let saved = igIGZSaver::save(imm, object_stream_manager, dir, 0x09, 0, platform.clone(), endian.clone()).unwrap();
+            std::fs::write(fixture_dir.join(&dir.path), saved).unwrap();
+        };
+
+        let empty = test_directory(&mut imm, &path("empty"), vec![]);
+        save(&mut imm, &igObjectStreamManager::new(), &empty);
+
+        // Values of every metafield the test metadata has, with a name list
+        let scalars = test_instantiate(&mut imm, "TestObject");
+        {
+            let mut guard = scalars.write().unwrap();
+            guard.set_field("_value", Some(Arc::new(RwLock::new(i32::MIN)))).unwrap();
+            guard.set_field("_name", Some(Arc::new(RwLock::new(Arc::<str>::from("scalars"))))).unwrap();
+            guard.set_field("_numbers", Some(test_int_memory(vec![0, -1, i32::MAX]))).unwrap();
+            guard.set_field("_type", Some(Arc::new(RwLock::new(test_object_meta.clone())))).unwrap();
+        }
+        let mut scalars_dir = test_directory(&mut imm, &path("scalars"), vec![scalars]);
+        scalars_dir.use_name_list = true;
+        scalars_dir.name_list = test_instantiate(&mut imm, "igNameList").cast_to().unwrap();
+        scalars_dir.name_list.read().unwrap().push(igName::new("scalars".to_string()));
+        save(&mut imm, &igObjectStreamManager::new(), &scalars_dir);
+
+        // A cycle, an object referenced twice and an object only reachable through references
+        let objects: Vec<igObject> = (0..3).map(|_| test_instantiate(&mut imm, "TestObject")).collect();
+        for (i, object) in objects.iter().enumerate() {
+            let mut guard = object.write().unwrap();
+            guard.set_field("_value", Some(Arc::new(RwLock::new(i as i32)))).unwrap();
+            guard.set_field("_next", Some(Arc::new(RwLock::new(objects[(i + 1) % 3].clone())))).unwrap();
+        }
+        let graph = test_directory(&mut imm, &path("graph"), vec![objects[0].clone(), objects[1].clone(), objects[0].clone()]);
+        save(&mut imm, &igObjectStreamManager::new(), &graph);
+
+        // An object pointing into another igz
+        let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+        let mut object_stream_manager = igObjectStreamManager::new();
+        let dependency = object_stream_manager
+            .load(&ig_file_context, &ig_registry, &mut imm, &mut ig_ext_ref_system, path("scalars"))
+            .unwrap();
+        let external = test_instantiate(&mut imm, "TestObject");
+        let target = dependency.read().unwrap().object_list.read().unwrap().get(0).unwrap();
+        external.write().unwrap().set_field("_next", Some(Arc::new(RwLock::new(target)))).unwrap();
+        let external_dir = test_directory(&mut imm, &path("external"), vec![external]);
+        external_dir.dependencies.push(dependency);
+        save(&mut imm, &object_stream_manager, &external_dir);
+
+        for name in ["empty", "scalars", "graph", "external"] {
+            let resave_path = fixture_dir.join(format!("resaved-{}", path(name)));
+            let result = test_igz_resave(&mut imm, &ig_file_context, &ig_registry, &path(name), &resave_path);
+            assert_eq!(result, Ok(()), "{} did not survive a resave", path(name));
+        }
+    }
+
+    // The comparison has to notice a difference for the tests above to mean anything
+    let mut ig_ext_ref_system = igExternalReferenceSystem::new();
+    let mut load = |imm: &mut igMetadataManager| {
+        igObjectStreamManager::new().load(&ig_file_context, &ig_registry, imm, &mut ig_ext_ref_system, "graph-be.igz".to_string()).unwrap()
+    };
+    let (a, b) = (load(&mut imm), load(&mut imm));
+    let changed = b.read().unwrap().object_list.read().unwrap().get(1).unwrap();
+    changed.write().unwrap().set_field("_value", Some(Arc::new(RwLock::new(5i32)))).unwrap();
+    let result = GraphComparison::new(&mut imm).directories(&a.read().unwrap(), &b.read().unwrap());
+    std::fs::remove_dir_all(&fixture_dir).ok();
+    assert_eq!(result, Err("objectList._data[1]._value: 1 vs 5".to_string()));
+
+    // Values of a type the comparison doesn't know can't be told apart, so they don't count as equal
+    let unknown = |x: usize| -> igAny { Arc::new(RwLock::new(x)) };
+    let result = GraphComparison::new(&mut imm).values("unknown", &unknown(1), &unknown(1));
+    assert!(result.is_err_and(|e| e.starts_with("unknown: values of")));
+}
+
+proptest! {
+    /// Packing the serialized offsets of a runtime fixup and unpacking them again gives back the same offsets for every igz version.
+    #[test]
+    fn test_compressed_int_round_trip(
+        version in prop::sample::select(vec![0x05u32, 0x06, 0x07, 0x08, 0x09]),
+        locations in prop::collection::vec((0usize..0x1F, 0u64..0x10000), 0..200),
+    ) {
+        let ctx = IgzSaverContext::new(version, IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+        let mut offsets: Vec<u64> = locations.iter().map(|(section, offset)| ctx.serialize_offset(*section, offset * 4)).collect();
+        offsets.sort();
+        offsets.dedup();
+        if version < 9 {
+            // The bias before version 0x09 means nothing can be stored at the very start of the first section
+            offsets.retain(|x| *x != 0);
+        }
+
+        let packed = pack_compressed_ints(version, &offsets).unwrap();
+        prop_assert_eq!(unpack_serialized_ints(version, &packed, offsets.len() as u32).unwrap(), offsets.clone());
+        // Cutting the data short fails instead of reading past it, as the last byte always holds the end of the last offset
+        if !packed.is_empty() {
+            prop_assert!(unpack_serialized_ints(version, &packed[..packed.len() - 1], offsets.len() as u32).is_err());
+        }
+
+        // Offsets out of order can't be stored as a distance from the one before
+        if offsets.len() > 1 {
+            prop_assert!(pack_compressed_ints(version, &[offsets[1], offsets[0]]).is_err());
+        }
+    }
+}
+
+/// Offsets off their alignment can't be stored as a distance from the one before, and neither can ones the bias before version 0x09 puts out of reach.
+#[test]
+fn test_compressed_int_unpackable_offsets() {
+    assert!(matches!(pack_compressed_ints(0x09, &[0x12]), Err(IgzSaverError::UnpackableOffset { offset: 0x12, previous: 0 })));
+    assert!(matches!(pack_compressed_ints(0x08, &[0x8, 0x8]), Err(IgzSaverError::UnpackableOffset { offset: 0x8, previous: 0x8 })));
+    assert!(matches!(pack_compressed_ints(0x08, &[0x0]), Err(IgzSaverError::UnpackableOffset { offset: 0x0, previous: 0 })));
+}
+
+/// Rebuilds every archive found in the folder set by IG_ARCHIVE_TEST_DIR and checks that it is identical to the original file, and that every file in it
+/// decompresses to the size its file info lists.
+/// Nothing is tested when the variable isn't set as the archives come from game dumps.
+#[test]
+fn test_archive_fidelity() {
+    let Ok(archive_dir) = std::env::var("IG_ARCHIVE_TEST_DIR") else {
+        return;
+    };
+
+    let ig_file_context = igFileContext::new(archive_dir.clone());
+    let ig_registry = igRegistry::new(IG_CORE_PLATFORM::IG_CORE_PLATFORM_CAFE);
+    for entry in walkdir::WalkDir::new(&archive_dir) {
+        let path = entry.unwrap().into_path();
+        let extension = path.extension().and_then(|x| x.to_str()).unwrap_or_default();
+        if !["arc", "pak", "bld"].contains(&extension.to_lowercase().as_str()) {
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(&archive_dir).unwrap().to_str().unwrap();
+        let report = igArchive::verify(&ig_file_context, &ig_registry, relative_path);
+        assert!(report.is_ok(), "{} failed to verify:\n{}", relative_path, report);
+        let archive = igArchive::open(&ig_file_context, &ig_registry, relative_path).unwrap();
+        let rebuilt = archive.save_preserving_layout(&ig_registry).unwrap();
+        assert!(rebuilt == std::fs::read(&path).unwrap(), "{} did not rebuild 1:1", relative_path);
+    }
+}
+
+/// Loads, saves and reloads every igz found in the folder set by IG_IGZ_TEST_DIR and checks the object graphs match. The igz files are read with the
+/// metadata of the game [load_alchemy] sets up, and files that aren't version 0x09 are skipped. Nothing is tested when the variable isn't set as the igz files come from game dumps.
+#[test]
+fn test_igz_resave_game_files() {
+    let Ok(igz_dir) = std::env::var("IG_IGZ_TEST_DIR") else {
+        return;
+    };
+
+    let mut ig_alchemy = load_alchemy();
+    let ig_file_context = igFileContext::new(igz_dir.clone());
+    let resave_path = std::env::temp_dir().join("ig-library-igz-resave.igz");
+    let mut failures = vec![];
+    let mut skipped = vec![];
+    for entry in walkdir::WalkDir::new(&igz_dir) {
+        let path = entry.unwrap().into_path();
+        if !path.extension().is_some_and(|x| x.eq_ignore_ascii_case("igz")) {
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(&igz_dir).unwrap().to_str().unwrap().to_string();
+        // Only version 0x09 can be saved, so files of any other version would be compared against a different version of themselves
+        // Game dumps are large, so only the magic and version are read here
+        let mut header = [0u8; 8];
+        let header_read = std::fs::File::open(&path).and_then(|mut file| file.read_exact(&mut header));
+        if header_read.is_err() || !matches!(header[4..8], [0x09, 0, 0, 0] | [0, 0, 0, 0x09]) {
+            skipped.push(relative_path);
+            continue;
+        }
+        let imm = &mut ig_alchemy.ark_core.metadata_manager;
+        if let Err(e) = test_igz_resave(imm, &ig_file_context, &ig_alchemy.registry, &relative_path, &resave_path) {
+            failures.push(format!("{}: {}", relative_path, e));
+        }
+    }
+    if !skipped.is_empty() {
+        log::warn!("Skipped {} igz files that aren't version 0x09:\n{}", skipped.len(), skipped.join("\n"));
+    }
+    assert!(failures.is_empty(), "{} igz files did not survive a resave:\n{}", failures.len(), failures.join("\n"));
+}
@@ -2,9 +2,9 @@
 
 use crate::core::ig_core_platform::IG_CORE_PLATFORM;
 use crate::core::ig_fs::Endian;
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 use paste::paste;
-use std::io::{Cursor, ErrorKind, Read};
+use std::io::{Cursor, ErrorKind, Read, Write};
 use std::slice::from_raw_parts;
 
 // Endian is ignored here so it needs a custom implementation
@@ -13,6 +13,12 @@ pub fn read_u8(cursor: &mut Cursor<Vec<u8>>, _endian: Endian) -> std::io::Result
     cursor.read_u8()
 }
 
+// Endian is ignored here so it needs a custom implementation
+#[inline]
+pub fn read_i8(cursor: &mut Cursor<Vec<u8>>, _endian: Endian) -> std::io::Result<i8> {
+    cursor.read_i8()
+}
+
 macro_rules! define_read {
     ($type:ty) => {
         paste! {
@@ -116,10 +122,67 @@ pub fn read_struct_array_u8_ref<'a>(
     Ok(slice)
 }
 
+// Endian is ignored here so it needs a custom implementation
+#[inline]
+pub fn write_u8(cursor: &mut Cursor<Vec<u8>>, value: u8, _endian: Endian) -> std::io::Result<()> {
+    cursor.write_u8(value)
+}
+
+// Endian is ignored here so it needs a custom implementation
+#[inline]
+pub fn write_i8(cursor: &mut Cursor<Vec<u8>>, value: i8, _endian: Endian) -> std::io::Result<()> {
+    cursor.write_i8(value)
+}
+
+macro_rules! define_write {
+    ($type:ty) => {
+        paste! {
+            #[inline]
+            pub fn [<write_ $type>](cursor: &mut Cursor<Vec<u8>>, value: $type, endian: Endian) -> std::io::Result<()> {
+                match endian {
+                    Endian::Little => cursor.[<write_ $type>]::<LittleEndian>(value),
+                    Endian::Big => cursor.[<write_ $type>]::<BigEndian>(value),
+                    Endian::Unknown => Err(std::io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "Endianness not set",
+                    )),
+                }
+            }
+        }
+    };
+}
+
+pub fn write_ptr(
+    cursor: &mut Cursor<Vec<u8>>,
+    value: u64,
+    platform: IG_CORE_PLATFORM,
+    endian: Endian,
+) -> std::io::Result<()> {
+    if platform.is_64bit() {
+        write_u64(cursor, value, endian)
+    } else {
+        write_u32(cursor, value as u32, endian)
+    }
+}
+
+/// Writes a null terminated string
+pub fn write_string(cursor: &mut Cursor<Vec<u8>>, value: &str) -> std::io::Result<()> {
+    cursor.write_all(value.as_bytes())?;
+    cursor.write_u8(0)
+}
+
 define_read!(u16);
 define_read!(i16);
 define_read!(u32);
 define_read!(i32);
 define_read!(u64);
 define_read!(i64);
+define_read!(f32);
 define_read_struct_array!(u16, u32, u64);
+define_write!(u16);
+define_write!(i16);
+define_write!(u32);
+define_write!(i32);
+define_write!(u64);
+define_write!(i64);
+define_write!(f32);
@@ -6,6 +6,8 @@ use crate::core::ig_core_platform::IG_CORE_PLATFORM::*;
 use crate::core::ig_file_context::igFileContext;
 use crate::core::ig_objects::igObjectStreamManager;
 use crate::core::ig_registry::igRegistry;
+use crate::core::load::ig_loader::{igObjectLoader, igObjectLoaderRegistry};
+use std::sync::{Arc, RwLock};
 
 /// Used as a placeholder where no value is used but one is needed
 pub struct igNoValue;
@@ -16,21 +18,30 @@ pub struct igAlchemy {
     pub file_context: igFileContext,
     pub registry: igRegistry,
     pub object_stream_manager: igObjectStreamManager,
+    /// Loaders used to read files. Shared with the [igObjectStreamManager]
+    pub loader_registry: Arc<RwLock<igObjectLoaderRegistry>>,
     pub ig_ext_ref_system: igExternalReferenceSystem,
     pub client: CClient,
 }
 
 impl igAlchemy {
     pub fn new(ig_file_context: igFileContext, ig_registry: igRegistry, ig_ark_core: igArkCore) -> igAlchemy {
+        let loader_registry = Arc::new(RwLock::new(igObjectLoaderRegistry::new()));
         igAlchemy {
             ark_core: ig_ark_core,
             file_context: ig_file_context,
-            object_stream_manager: igObjectStreamManager::new(),
+            object_stream_manager: igObjectStreamManager::with_loaders(loader_registry.clone()),
+            loader_registry,
             ig_ext_ref_system: igExternalReferenceSystem::new(),
             client: CClient::init(&ig_registry),
             registry: ig_registry,
         }
     }
+
+    /// Lets files of a custom format be loaded. The loader is asked before any already registered, see [igObjectLoaderRegistry::register]
+    pub fn register_loader(&self, loader: Arc<RwLock<dyn igObjectLoader>>) {
+        self.loader_registry.write().unwrap().register(loader);
+    }
 }
 
 pub fn get_platform_string(platform: IG_CORE_PLATFORM) -> String {
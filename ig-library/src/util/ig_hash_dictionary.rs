@@ -0,0 +1,111 @@
+use crate::util::ig_hash::{hash, hash_lower};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::RwLock;
+
+/// Where the dictionary is loaded from, next to the metadata of every game. One string per line
+pub const DICTIONARY_PATH: &str = "ArkCore/hashes.txt";
+
+/// Namespaces that are commonly only referenced by their hash
+const BUILT_IN_STRINGS: [&str; 12] = [
+    "tfbScript",
+    "other",
+    "global",
+    "tfb",
+    "system",
+    "game",
+    "interface",
+    "client",
+    "custom",
+    "app",
+    "application",
+    "internal",
+];
+
+/// Maps hashes back to the string that was hashed. Every string is stored under both its [hash] and [hash_lower]
+static DICTIONARY: Lazy<RwLock<HashMap<u32, String>>> = Lazy::new(|| RwLock::new(built_in_dictionary()));
+
+fn built_in_dictionary() -> HashMap<u32, String> {
+    let mut dictionary = HashMap::new();
+    for string in BUILT_IN_STRINGS {
+        insert(&mut dictionary, string);
+    }
+    dictionary
+}
+
+fn insert(dictionary: &mut HashMap<u32, String>, string: &str) {
+    // The first string seen for a hash wins, collisions are rare enough to not be worth tracking
+    dictionary
+        .entry(hash(string))
+        .or_insert_with(|| string.to_string());
+    dictionary
+        .entry(hash_lower(string))
+        .or_insert_with(|| string.to_string());
+}
+
+/// Adds a string to the dictionary so anything referencing its hash can be shown by name
+pub fn add_string(string: &str) {
+    if string.is_empty() {
+        return;
+    }
+
+    // Most strings are seen many times while loading, so avoid taking the write lock when possible
+    if let Ok(dictionary) = DICTIONARY.read() {
+        if dictionary.contains_key(&hash(string)) && dictionary.contains_key(&hash_lower(string)) {
+            return;
+        }
+    }
+    if let Ok(mut dictionary) = DICTIONARY.write() {
+        insert(&mut dictionary, string);
+    }
+}
+
+/// Looks up the string a hash was made from. Works for both [hash] and [hash_lower]
+pub fn find_string(hash: u32) -> Option<String> {
+    DICTIONARY.read().ok()?.get(&hash).cloned()
+}
+
+/// Forgets every string added since startup, leaving only the built in namespaces. The dictionary is shared by the whole process and grows with every
+/// file loaded, so long running tools working through many files should clear it between them
+pub fn clear_dictionary() {
+    if let Ok(mut dictionary) = DICTIONARY.write() {
+        *dictionary = built_in_dictionary();
+    }
+}
+
+/// Adds every line of a text file to the dictionary. Returns the number of strings read
+pub fn load_dictionary(path: &Path) -> std::io::Result<usize> {
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut count = 0;
+    for line in reader.lines() {
+        let line = line?;
+        let string = line.trim_end_matches('\r');
+        if !string.is_empty() {
+            add_string(string);
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Writes every known string to a text file so it can be loaded again with [load_dictionary]
+pub fn save_dictionary(path: &Path) -> std::io::Result<()> {
+    let mut strings: Vec<String> = DICTIONARY
+        .read()
+        .unwrap()
+        .values()
+        .filter(|string| !string.contains('\n'))
+        .cloned()
+        .collect();
+    strings.sort();
+    strings.dedup();
+
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+    for string in strings {
+        writeln!(writer, "{}", string)?;
+    }
+    writer.flush()
+}
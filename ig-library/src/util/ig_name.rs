@@ -1,9 +1,9 @@
 use crate::core::ig_objects::igObjectStreamManager;
 use crate::core::ig_fs::Endian;
 use crate::core::ig_objects::igAny;
-use crate::core::load::ig_igb_loader::IgbLoaderContext;
-use crate::core::load::ig_igx_loader::IgxLoaderContext;
-use crate::core::load::ig_igz_loader::IgzLoaderContext;
+use crate::core::load::ig_igb_loader::{IgbLoaderContext, IgbLoaderErrorKind};
+use crate::core::load::ig_igx_loader::{IgxLoaderContext, IgxLoaderErrorKind, IgxNode};
+use crate::core::load::ig_igz_loader::{IgzLoaderContext, IgzLoaderErrorKind};
 use crate::core::meta::field::ig_metafield_registry::igMetafieldRegistry;
 use crate::core::meta::field::ig_metafields::igMetaField;
 use crate::core::meta::field::r#impl::ig_string_meta_field::igStringMetaField;
@@ -12,26 +12,54 @@ use crate::core::save::ig_igb_saver::{IgbSaverContext, IgbSaverError};
 use crate::core::save::ig_igx_saver::{IgxSaverContext, IgxSaverError};
 use crate::core::save::ig_igz_saver::{IgzSaverContext, IgzSaverError};
 use crate::util::ig_hash::hash_lower;
+use crate::util::ig_hash_dictionary::{add_string, find_string};
 use ig_proc_macros::igStruct;
 use std::io::Cursor;
 use std::sync::Arc;
 
 #[igStruct]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct igName {
     pub string: Option<String>,
     pub hash: u32,
+    /// Set when the string was looked up from the hash in the [dictionary](crate::util::ig_hash_dictionary). The string is then only for showing the name,
+    /// as it may not be the string the hash was made from
+    #[ig_skip]
+    pub recovered: bool,
 }
 
 impl igName {
     pub fn new(string: String) -> Self {
+        add_string(&string);
         igName {
             hash: hash_lower(&string),
             string: Some(string),
+            recovered: false,
         }
     }
 
+    /// Creates a name from only a hash. The string is filled in when the hash is in the [dictionary](crate::util::ig_hash_dictionary)
     pub fn from_hash(hash: u32) -> Self {
-        igName { hash, string: None }
+        let string = find_string(hash);
+        igName {
+            hash,
+            recovered: string.is_some(),
+            string,
+        }
+    }
+
+    /// Creates a name from a string and a hash that wasn't made from it with [hash_lower]
+    pub fn with_hash(string: String, hash: u32) -> Self {
+        add_string(&string);
+        igName {
+            string: Some(string),
+            hash,
+            recovered: false,
+        }
+    }
+
+    /// The string of the name when [igName::new] would give back the same hash. Savers write names by their hash when this is [None]
+    pub fn source_string(&self) -> Option<&str> {
+        self.string.as_deref().filter(|string| !self.recovered && hash_lower(string) == self.hash)
     }
 }
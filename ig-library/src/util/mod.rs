@@ -1,4 +1,5 @@
 pub mod ig_hash;
+pub mod ig_hash_dictionary;
 pub mod byteorder_fixes;
 pub mod ig_common;
 pub mod ig_name;
\ No newline at end of file
@@ -20,19 +20,47 @@ pub fn derive_meta_enum(input: TokenStream) -> TokenStream {
 #[allow(non_snake_case)]
 #[proc_macro_attribute]
 pub fn igStruct(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as DeriveInput);
-    let struct_name = &input.ident;
+    let mut input = parse_macro_input!(item as DeriveInput);
+    let struct_name = input.ident.clone();
     let meta_struct_name =
         syn::Ident::new(&format!("{}MetaField", struct_name), struct_name.span());
 
-    let fields = match &input.data {
-        Data::Struct(data_struct) => match &data_struct.fields {
-            Fields::Named(named) => &named.named,
+    let all_fields = match &mut input.data {
+        Data::Struct(data_struct) => match &mut data_struct.fields {
+            Fields::Named(named) => &mut named.named,
             _ => panic!("Expected named fields"),
         },
         _ => panic!("#[igStruct] only supports structs"),
     };
 
+    // Fields marked #[ig_skip] only exist at runtime, they aren't read or written and start out as their default value
+    let mut skipped_fields = Vec::new();
+    for field in all_fields.iter_mut() {
+        let attribute_count = field.attrs.len();
+        field.attrs.retain(|attribute| !attribute.path().is_ident("ig_skip"));
+        if field.attrs.len() != attribute_count {
+            skipped_fields.push(field.ident.clone().expect("internal igStruct error #1"));
+        }
+    }
+    let fields: Vec<_> = all_fields
+        .iter()
+        .filter(|field| !skipped_fields.contains(field.ident.as_ref().unwrap()))
+        .cloned()
+        .collect();
+
+    // Only strings and u32s can be read and written, anything else fails to compile at the field
+    if let Some(field) = fields.iter().find(|field| {
+        let ty = &field.ty;
+        let ty = quote!(#ty).to_string();
+        !ty.contains("Option < String") && ty != "u32"
+    }) {
+        let error = syn::Error::new_spanned(&field.ty, "#[igStruct] fields must be a u32 or an Option<String>, or be marked #[ig_skip]").to_compile_error();
+        return TokenStream::from(quote! {
+            #input
+            #error
+        });
+    }
+
     // Generate reading code for each field (simplified)
     let read_fields = fields.iter().map(|field| {
         let name = field.ident.as_ref().expect("internal igStruct error #1");
@@ -41,25 +69,121 @@ pub fn igStruct(_attr: TokenStream, item: TokenStream) -> TokenStream {
             quote! {
                 let string_meta_field = igStringMetaField;
 
-                let #name = string_meta_field.value_from_igz(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx)
-                    .map(|s| Some(s.read().unwrap().downcast_ref::<Arc<str>>().expect("igStruct string downcast failed.").to_string()))
-                    .unwrap_or(None);
+                let #name = string_meta_field.value_from_igz(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx)?
+                    .map(|s| s.read().unwrap().downcast_ref::<Arc<str>>().expect("igStruct string downcast failed.").to_string());
+            }
+        } else if quote!(#ty).to_string() == "u32" {
+            quote! {
+                let #name = read_u32(handle, endian.clone())?;
+            }
+        } else {
+            unreachable!("internal igStruct error #2")
+        }
+    });
+
+    // Generate writing code for each field, mirroring the reading code above
+    let write_fields = fields.iter().map(|field| {
+        let name = field.ident.as_ref().expect("internal igStruct error #1");
+        let ty = &field.ty;
+        if quote!(#ty).to_string().contains("Option < String") {
+            quote! {
+                let string_meta_field = igStringMetaField;
+                let string = value.#name.clone().map(|s| {
+                    let object: igAny = std::sync::Arc::new(std::sync::RwLock::new(std::sync::Arc::<str>::from(s)));
+                    object
+                });
+
+                string_meta_field.value_into_igz(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx, string)?;
+            }
+        } else if quote!(#ty).to_string() == "u32" {
+            quote! {
+                write_u32(handle, value.#name, endian.clone())?;
+            }
+        } else {
+            unreachable!("internal igStruct error #2")
+        }
+    });
+
+    // igb stores the same layout as igz, but strings are inline instead of behind a pointer
+    let read_igb_fields = fields.iter().map(|field| {
+        let name = field.ident.as_ref().expect("internal igStruct error #1");
+        let ty = &field.ty;
+        if quote!(#ty).to_string().contains("Option < String") {
+            quote! {
+                let string_meta_field = igStringMetaField;
+
+                let #name = string_meta_field.value_from_igb(registry, metadata_manager, object_stream_manager, handle, endian.clone(), ctx)?
+                    .map(|s| s.read().unwrap().downcast_ref::<Arc<str>>().expect("igStruct string downcast failed.").to_string());
+            }
+        } else if quote!(#ty).to_string() == "u32" {
+            quote! {
+                let #name = read_u32(handle, endian.clone())?;
+            }
+        } else {
+            unreachable!("internal igStruct error #2")
+        }
+    });
+
+    // Each field becomes a child element named after it when read from or written to an igx
+    let read_igx_fields = fields.iter().map(|field| {
+        let name = field.ident.as_ref().expect("internal igStruct error #1");
+        let name_lit = name.to_string();
+        let ty = &field.ty;
+        if quote!(#ty).to_string().contains("Option < String") {
+            quote! {
+                let string_meta_field = igStringMetaField;
+                let #name = match node.child(#name_lit) {
+                    Some(child) if !child.is_null() => string_meta_field.value_from_igx(registry, metadata_manager, object_stream_manager, child, ctx)?
+                        .map(|s| s.read().unwrap().downcast_ref::<Arc<str>>().expect("igStruct string downcast failed.").to_string()),
+                    _ => None,
+                };
             }
         } else if quote!(#ty).to_string() == "u32" {
             quote! {
-                let #name = read_u32(handle, endian.clone()).expect("igStruct impl u32 decoding failed");
+                let #name = match node.child(#name_lit) {
+                    Some(child) => child.text.trim().parse::<u32>().map_err(|_| IgxLoaderErrorKind::InvalidValue { expected: "u32", value: child.text.clone() })?,
+                    None => 0,
+                };
             }
         } else {
+            unreachable!("internal igStruct error #2")
+        }
+    });
+
+    let write_igx_fields = fields.iter().map(|field| {
+        let name = field.ident.as_ref().expect("internal igStruct error #1");
+        let name_lit = name.to_string();
+        let ty = &field.ty;
+        if quote!(#ty).to_string().contains("Option < String") {
             quote! {
-                let #name = todo!("Unsupported field type");
+                let mut child = IgxNode::new(#name_lit);
+                match value.#name.clone() {
+                    Some(s) => {
+                        let string_meta_field = igStringMetaField;
+                        let string: igAny = std::sync::Arc::new(std::sync::RwLock::new(std::sync::Arc::<str>::from(s)));
+                        string_meta_field.value_into_igx(registry, metadata_manager, object_stream_manager, &mut child, ctx, string)?;
+                    }
+                    None => child.set_null(),
+                }
+                node.children.push(child);
             }
+        } else if quote!(#ty).to_string() == "u32" {
+            quote! {
+                let mut child = IgxNode::new(#name_lit);
+                child.text = value.#name.to_string();
+                node.children.push(child);
+            }
+        } else {
+            unreachable!("internal igStruct error #2")
         }
     });
 
-    let init_fields = fields.iter().map(|f| {
+    let init_fields: Vec<_> = fields.iter().map(|f| {
         let name = &f.ident;
         quote!(#name,)
-    });
+    }).chain(skipped_fields.iter().map(|name| {
+        quote!(#name: Default::default(),)
+    })).collect();
 
     let expanded = quote! {
         #input
@@ -79,12 +203,12 @@ pub fn igStruct(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 handle: &mut std::io::Cursor<Vec<u8>>,
                 endian: Endian,
                 ctx: &mut IgzLoaderContext,
-            ) -> Option<igAny> {
+            ) -> Result<Option<igAny>, IgzLoaderErrorKind> {
                 use crate::util::byteorder_fixes::*;
                 #(#read_fields)*
-                Some(std::sync::Arc::new(std::sync::RwLock::new(#struct_name {
+                Ok(Some(std::sync::Arc::new(std::sync::RwLock::new(#struct_name {
                     #(#init_fields)*
-                })))
+                }))))
             }
             
             fn value_into_igz(
@@ -94,33 +218,44 @@ pub fn igStruct(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 object_stream_manager: &igObjectStreamManager,
                 handle: &mut Cursor<Vec<u8>>,
                 endian: Endian,
-                ctx: &mut IgzSaverContext
+                ctx: &mut IgzSaverContext,
+                value: Option<igAny>,
             ) -> Result<(), IgzSaverError> {
-                todo!()
+                use crate::util::byteorder_fixes::*;
+                let value = match value {
+                    Some(value) => value.read().unwrap().downcast_ref::<#struct_name>().ok_or(IgzSaverError::InvalidValueType(stringify!(#struct_name)))?.clone(),
+                    None => #struct_name::default(),
+                };
+                #(#write_fields)*
+                Ok(())
             }
 
             fn value_from_igx(
-                &self, 
+                &self,
                 registry: &igMetafieldRegistry,
                 metadata_manager: &igMetadataManager,
                 object_stream_manager: &igObjectStreamManager,
-                handle: &mut Cursor<Vec<u8>>,
-                endian: Endian,
-                ctx: &mut IgxLoaderContext
-            ) -> Option<igAny> {
-                todo!()
+                node: &IgxNode,
+                ctx: &mut IgxLoaderContext,
+            ) -> Result<Option<igAny>, IgxLoaderErrorKind> {
+                #(#read_igx_fields)*
+                Ok(Some(std::sync::Arc::new(std::sync::RwLock::new(#struct_name {
+                    #(#init_fields)*
+                }))))
             }
-        
+
             fn value_into_igx(
-                &self, 
+                &self,
                 registry: &igMetafieldRegistry,
                 metadata_manager: &igMetadataManager,
                 object_stream_manager: &igObjectStreamManager,
-                handle: &mut Cursor<Vec<u8>>, 
-                endian: Endian, 
-                ctx: &mut IgxSaverContext
+                node: &mut IgxNode,
+                ctx: &mut IgxSaverContext,
+                value: igAny,
             ) -> Result<(), IgxSaverError> {
-                todo!()
+                let value = value.read().unwrap().downcast_ref::<#struct_name>().ok_or(IgxSaverError::InvalidValueType(stringify!(#struct_name)))?.clone();
+                #(#write_igx_fields)*
+                Ok(())
             }
         
             fn value_from_igb(
@@ -131,19 +266,25 @@ pub fn igStruct(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 handle: &mut Cursor<Vec<u8>>,
                 endian: Endian,
                 ctx: &mut IgbLoaderContext,
-            ) -> Option<igAny> {
-                todo!()
+            ) -> Result<Option<igAny>, IgbLoaderErrorKind> {
+                use crate::util::byteorder_fixes::*;
+                #(#read_igb_fields)*
+                Ok(Some(std::sync::Arc::new(std::sync::RwLock::new(#struct_name {
+                    #(#init_fields)*
+                }))))
             }
         
             fn value_into_igb(
-                &self, registry: &igMetafieldRegistry,
-                metadata_manager: &igMetadataManager,
-                object_stream_manager: &igObjectStreamManager,
-                handle: &mut Cursor<Vec<u8>>,
-                endian: Endian, 
-                ctx: &mut IgbSaverContext,
+                &self,
+                _registry: &igMetafieldRegistry,
+                _metadata_manager: &igMetadataManager,
+                _object_stream_manager: &igObjectStreamManager,
+                _handle: &mut Cursor<Vec<u8>>,
+                _endian: Endian,
+                _ctx: &mut IgbSaverContext,
+                _value: Option<igAny>,
             ) -> Result<(), IgbSaverError> {
-                todo!()
+                Err(IgbSaverError::Unsupported(stringify!(#meta_struct_name)))
             }
         }
     };